@@ -1,6 +1,12 @@
-use axum::{response::Json, http::StatusCode};
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    response::{Json, Response},
+};
 use serde::{Deserialize, Serialize};
 
+use crate::{proxy, AppState};
+
 #[derive(Serialize)]
 pub struct ContentListResponse {
     items: Vec<ContentItem>,
@@ -31,3 +37,10 @@ pub async fn create_content(Json(payload): Json<CreateContentRequest>) -> Status
     // TODO: Implement content creation
     StatusCode::CREATED
 }
+
+/// Generation streams as Server-Sent Events, so it goes straight through to
+/// the content service without buffering
+pub async fn generate_stream(State(state): State<AppState>, req: Request) -> Response {
+    let url = format!("{}/generate/stream", state.config.content_service_url);
+    proxy::proxy_request(&state.http_client, url, req).await
+}
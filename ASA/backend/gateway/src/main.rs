@@ -1,23 +1,10 @@
-use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{IntoResponse, Json},
-    routing::{get, post},
-    Router,
-};
-use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tower::ServiceBuilder;
-use tower_http::{
-    compression::CompressionLayer,
-    cors::CorsLayer,
-    trace::TraceLayer,
-};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod config;
 mod middleware;
 mod handlers;
+mod proxy;
 mod router;
 
 use config::Config;
@@ -26,6 +13,7 @@ use config::Config;
 #[derive(Clone)]
 pub struct AppState {
     config: Arc<Config>,
+    http_client: reqwest::Client,
 }
 
 #[tokio::main]
@@ -50,6 +38,7 @@ async fn main() -> anyhow::Result<()> {
     // Build application state
     let state = AppState {
         config: Arc::new(config.clone()),
+        http_client: reqwest::Client::new(),
     };
 
     // Build application router
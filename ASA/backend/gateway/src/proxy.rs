@@ -1 +1,85 @@
-// Proxy utilities - placeholder for future implementation
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+/// Forward `req` to `url` and relay the upstream response. Bodies are streamed
+/// in both directions, so Server-Sent Events from the services reach the
+/// client as they are sent rather than when the upstream finishes.
+pub async fn proxy_request(client: &reqwest::Client, url: String, req: Request) -> Response {
+    let (parts, body) = req.into_parts();
+
+    // reqwest is on an older `http` than axum, so method, headers and status
+    // cross over as their string/byte forms
+    let method = match reqwest::Method::from_bytes(parts.method.as_str().as_bytes()) {
+        Ok(method) => method,
+        Err(_) => return StatusCode::METHOD_NOT_ALLOWED.into_response(),
+    };
+
+    let mut request = client
+        .request(method, &url)
+        .body(reqwest::Body::wrap_stream(body.into_data_stream()));
+
+    // Forward end-to-end headers; reqwest sets Host and manages its own connection
+    for (key, value) in parts.headers.iter() {
+        if key != header::HOST && !is_hop_by_hop(key.as_str()) {
+            request = request.header(key.as_str(), value.as_bytes());
+        }
+    }
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(err) => {
+            tracing::error!("Proxy request to {} failed: {:?}", url, err);
+            return StatusCode::BAD_GATEWAY.into_response();
+        }
+    };
+
+    let mut builder = Response::builder().status(response.status().as_u16());
+    for (key, value) in response.headers() {
+        if !is_hop_by_hop(key.as_str()) {
+            builder = builder.header(key.as_str(), value.as_bytes());
+        }
+    }
+
+    builder
+        .body(Body::from_stream(response.bytes_stream()))
+        .unwrap_or_else(|err| {
+            tracing::error!("Invalid upstream response from {}: {:?}", url, err);
+            StatusCode::BAD_GATEWAY.into_response()
+        })
+}
+
+/// Connection-level headers that must not be copied across the proxy in
+/// either direction
+fn is_hop_by_hop(name: &str) -> bool {
+    [
+        "connection",
+        "keep-alive",
+        "transfer-encoding",
+        "te",
+        "trailer",
+        "upgrade",
+        "proxy-authenticate",
+        "proxy-authorization",
+    ]
+    .contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hop_by_hop_headers() {
+        assert!(is_hop_by_hop("connection"));
+        assert!(is_hop_by_hop("keep-alive"));
+        assert!(is_hop_by_hop("transfer-encoding"));
+        assert!(is_hop_by_hop("proxy-authorization"));
+        assert!(!is_hop_by_hop("authorization"));
+        assert!(!is_hop_by_hop("content-type"));
+        assert!(!is_hop_by_hop("x-request-id"));
+    }
+}
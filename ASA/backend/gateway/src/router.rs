@@ -9,7 +9,7 @@ use tower_http::{
     trace::TraceLayer,
 };
 
-use crate::{handlers, middleware, AppState};
+use crate::{handlers, AppState};
 
 pub fn build_router(state: AppState) -> Router {
     Router::new()
@@ -35,6 +35,7 @@ fn api_routes() -> Router<AppState> {
         // Content routes
         .route("/content", get(handlers::content::list_content))
         .route("/content", post(handlers::content::create_content))
+        .route("/generate/stream", post(handlers::content::generate_stream))
 
         // AEO routes
         .route("/aeo/optimize", post(handlers::aeo::optimize_content))
@@ -45,3 +46,121 @@ fn api_routes() -> Router<AppState> {
         .route("/analytics/metrics", get(handlers::analytics::get_metrics))
         .route("/analytics/events", post(handlers::analytics::track_event))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::sync::Arc;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::oneshot,
+    };
+
+    fn test_config(content_service_url: String) -> Config {
+        Config {
+            environment: "test".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 0,
+            jwt_secret: "secret".to_string(),
+            database_url: String::new(),
+            redis_url: String::new(),
+            auth_service_url: String::new(),
+            content_service_url,
+            analytics_service_url: String::new(),
+            search_service_url: String::new(),
+        }
+    }
+
+    /// A bare HTTP/1.1 upstream that sends one SSE chunk, waits for `release`,
+    /// then sends the rest. Hands back the request head it received.
+    async fn stub_upstream(release: oneshot::Receiver<()>) -> (String, oneshot::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (head_tx, head_rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+
+            let mut head = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                head.extend_from_slice(&buf[..n]);
+            }
+            head_tx.send(String::from_utf8_lossy(&head).to_lowercase()).unwrap();
+
+            let first = "event: token\ndata: Hello\n\n";
+            let second = "event: done\ndata: {}\n\n";
+            socket
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\n\
+                         content-type: text/event-stream\r\n\
+                         connection: keep-alive\r\n\
+                         keep-alive: timeout=5\r\n\
+                         transfer-encoding: chunked\r\n\r\n\
+                         {:x}\r\n{}\r\n",
+                        first.len(),
+                        first
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+
+            release.await.unwrap();
+            socket
+                .write_all(format!("{:x}\r\n{}\r\n0\r\n\r\n", second.len(), second).as_bytes())
+                .await
+                .unwrap();
+        });
+
+        (url, head_rx)
+    }
+
+    #[tokio::test]
+    async fn test_generate_stream_is_proxied_unbuffered() {
+        let (release_tx, release_rx) = oneshot::channel();
+        let (upstream_url, head_rx) = stub_upstream(release_rx).await;
+
+        let state = AppState {
+            config: Arc::new(test_config(upstream_url)),
+            http_client: reqwest::Client::new(),
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let gateway_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, build_router(state)).await });
+
+        let mut response = reqwest::Client::new()
+            .post(format!("{}/api/v1/generate/stream", gateway_url))
+            .header("content-type", "application/json")
+            .header("x-request-id", "abc")
+            .header("proxy-authorization", "Basic Zm9vOmJhcg==")
+            .body(r#"{"topic":"Rust"}"#)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        assert!(response.headers().get("keep-alive").is_none());
+
+        let head = head_rx.await.unwrap();
+        assert!(head.starts_with("post /generate/stream "));
+        assert!(head.contains("x-request-id: abc"));
+        assert!(!head.contains("proxy-authorization"));
+
+        // The first event arrives while the upstream is still holding the rest back
+        let first = response.chunk().await.unwrap().unwrap();
+        assert_eq!(&first[..], b"event: token\ndata: Hello\n\n");
+
+        release_tx.send(()).unwrap();
+        let mut rest = Vec::new();
+        while let Some(chunk) = response.chunk().await.unwrap() {
+            rest.extend_from_slice(&chunk);
+        }
+        assert_eq!(rest, b"event: done\ndata: {}\n\n");
+    }
+}
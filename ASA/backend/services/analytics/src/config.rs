@@ -6,6 +6,7 @@ pub struct Config {
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
     pub scylla: ScyllaConfig,
+    pub platform_profiles: Option<String>, // extra/override platform profiles (TOML or JSON)
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -53,6 +54,7 @@ impl Config {
                 keyspace: std::env::var("SCYLLA_KEYSPACE")
                     .unwrap_or_else(|_| "asa_analytics".to_string()),
             },
+            platform_profiles: std::env::var("PLATFORM_PROFILES").ok(),
         })
     }
 }
@@ -2,6 +2,7 @@ use axum::{
     routing::{get, post},
     Router,
 };
+use asa_models::aeo::profile::PlatformRegistry;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
@@ -31,6 +32,15 @@ async fn main() -> anyhow::Result<()> {
     let db_pool = asa_database::PostgresPool::new(&config.database.url).await?;
     let redis_client = asa_database::RedisClient::new(&config.redis.url).await?;
 
+    // Platform profiles citations are tracked against, as the content service
+    // loads them
+    let platforms = match &config.platform_profiles {
+        Some(path) => PlatformRegistry::builtin_with(path)?,
+        None => PlatformRegistry::builtin().clone(),
+    }
+    .install();
+    tracing::info!("Loaded {} platform profiles", platforms.profiles().len());
+
     // Initialize ScyllaDB for time-series data
     let scylla_client = scylla_client::ScyllaClient::new(&config.scylla.nodes).await?;
 
@@ -1,3 +1,4 @@
+use asa_models::aeo::profile::PlatformId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -25,7 +26,7 @@ pub struct EventResponse {
 // AEO Citation tracking
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TrackCitationRequest {
-    pub platform: String,          // ChatGPT, Claude, Perplexity, etc.
+    pub platform: PlatformId, // platform id, name or alias
     pub content_id: Option<Uuid>,
     pub query: String,
     pub cited: bool,
@@ -37,7 +38,7 @@ pub struct TrackCitationRequest {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CitationResponse {
     pub id: Uuid,
-    pub platform: String,
+    pub platform: PlatformId,
     pub content_id: Option<Uuid>,
     pub query: String,
     pub cited: bool,
@@ -1,4 +1,5 @@
 use asa_database::PostgresPool;
+use asa_models::aeo::profile::PlatformId;
 use chrono::{DateTime, Utc};
 use sqlx::Row;
 use uuid::Uuid;
@@ -38,7 +39,7 @@ impl AnalyticsRepository {
     // Citation tracking
     pub async fn track_citation(
         &self,
-        platform: &str,
+        platform: &PlatformId,
         content_id: Option<Uuid>,
         query: &str,
         cited: bool,
@@ -52,7 +53,7 @@ impl AnalyticsRepository {
             RETURNING id
             "#,
         )
-        .bind(platform)
+        .bind(platform.as_str())
         .bind(content_id)
         .bind(query)
         .bind(cited)
@@ -1,338 +1,1118 @@
-use crate::models::{Improvement, OptimizationResponse};
-use asa_models::aeo::platform::AIPlatform;
+use crate::document::{contains_word, Block, Document, Span};
+use crate::models::{Evidence, Improvement, OptimizationResponse, RuleResult, ScoreBreakdown};
+use crate::rule_engine::{RewriteResult, RuleEngine, RuleError};
+use asa_models::aeo::optimization::{AEOScore, ScoreComponents};
+use asa_models::aeo::platform::{OptimizationRule, PlatformStrategy};
+use asa_models::aeo::profile::{PlatformProfile, PlatformRegistry, Signal};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
 
-pub struct AEOOptimizer;
+/// Number of strengths/weaknesses reported in a score summary
+const MAX_SUMMARY_ITEMS: usize = 5;
+
+/// Evidence entries kept per rule
+const MAX_EVIDENCE: usize = 5;
+
+/// Weight of a platform that has no strategy, matching the column default
+const DEFAULT_PRIORITY: u8 = 5;
+
+#[derive(Clone)]
+pub struct AEOOptimizer {
+    rule_engine: RuleEngine,
+    platforms: Arc<PlatformRegistry>,
+}
 
 impl AEOOptimizer {
-    pub fn new() -> Self {
-        Self
+    pub fn new(platforms: Arc<PlatformRegistry>) -> Self {
+        Self {
+            rule_engine: RuleEngine::new(),
+            platforms,
+        }
+    }
+
+    /// Platform profiles this optimizer scores against
+    pub fn platforms(&self) -> &PlatformRegistry {
+        &self.platforms
     }
 
     pub fn optimize_content(
         &self,
         content: &str,
-        platform: &str,
-    ) -> OptimizationResponse {
-        let platform_enum = match platform.to_lowercase().as_str() {
-            "chatgpt" => AIPlatform::ChatGPT,
-            "claude" => AIPlatform::Claude,
-            "perplexity" => AIPlatform::Perplexity,
-            "gemini" => AIPlatform::Gemini,
-            "bing" => AIPlatform::Bing,
-            _ => AIPlatform::ChatGPT,
-        };
-
-        let score = self.calculate_score(content, &platform_enum);
-        let improvements = self.identify_improvements(content, &platform_enum);
-        let optimized_content = self.apply_optimizations(content, &platform_enum);
-        let tips = self.get_platform_tips(&platform_enum);
+        profile: &PlatformProfile,
+        strategy: Option<&PlatformStrategy>,
+    ) -> Result<OptimizationResponse, RuleError> {
+        let doc = Document::parse(content);
+        let breakdown = self.score_breakdown(&doc, profile);
+        let improvements = self.identify_improvements(&doc, &breakdown, profile);
+        let rewrite = self.apply_optimizations(content, profile, strategy)?;
+        let optimized_score = self.calculate_score(&rewrite.body, profile);
+        let tips = self.get_platform_tips(profile);
 
-        OptimizationResponse {
-            score,
+        Ok(OptimizationResponse {
+            score: breakdown.score,
+            optimized_score,
+            breakdown,
             improvements,
-            optimized_content,
+            optimized_content: rewrite.body,
+            applied_rules: rewrite.applied,
+            diff: rewrite.diff,
             platform_specific_tips: tips,
+        })
+    }
+
+    /// Check that a custom rule compiles before it is stored
+    pub fn validate_rule(&self, rule: &OptimizationRule) -> Result<(), RuleError> {
+        self.rule_engine.validate(rule)
+    }
+
+    /// Cross-platform score weighted by strategy priority. Platforms without a
+    /// strategy count at the default priority; inactive strategies are left out.
+    pub fn overall_score(&self, scores: &[(&str, f64)], strategies: &[PlatformStrategy]) -> f64 {
+        let mut weighted = 0.0;
+        let mut total_weight = 0.0;
+        for (platform, score) in scores {
+            let weight = match strategies.iter().find(|s| s.platform == *platform) {
+                Some(strategy) if !strategy.active => 0.0,
+                Some(strategy) => f64::from(strategy.priority),
+                None => f64::from(DEFAULT_PRIORITY),
+            };
+            weighted += score * weight;
+            total_weight += weight;
+        }
+
+        if total_weight > 0.0 {
+            weighted / total_weight
+        } else if scores.is_empty() {
+            0.0
+        } else {
+            // Every platform switched off: fall back to the plain average
+            scores.iter().map(|(_, score)| score).sum::<f64>() / scores.len() as f64
         }
     }
 
-    pub fn calculate_score(&self, content: &str, platform: &AIPlatform) -> f64 {
-        let mut score = 0.0;
-        let mut max_score = 0.0;
+    pub fn calculate_score(&self, content: &str, profile: &PlatformProfile) -> f64 {
+        self.score_breakdown(&Document::parse(content), profile)
+            .score
+    }
+
+    /// Score a document and report every rule that contributed to it
+    pub fn score_breakdown(&self, doc: &Document, profile: &PlatformProfile) -> ScoreBreakdown {
+        let mut scorer = Scorer::new(doc);
 
         // Structure score (30 points)
-        max_score += 30.0;
-        score += self.score_structure(content) * 30.0;
+        scorer.category("structure", 30.0);
+        self.score_structure(&mut scorer);
 
         // Content quality (25 points)
-        max_score += 25.0;
-        score += self.score_quality(content) * 25.0;
+        scorer.category("quality", 25.0);
+        self.score_quality(&mut scorer);
 
         // Platform-specific optimization (25 points)
-        max_score += 25.0;
-        score += self.score_platform_optimization(content, platform) * 25.0;
+        scorer.category("platform", 25.0);
+        self.score_platform_optimization(&mut scorer, profile);
 
         // Readability (20 points)
-        max_score += 20.0;
-        score += self.score_readability(content) * 20.0;
+        scorer.category("readability", 20.0);
+        self.score_readability(&mut scorer);
 
-        (score / max_score) * 100.0
+        scorer.finish(&profile.name)
     }
 
-    fn score_structure(&self, content: &str) -> f64 {
-        let mut score = 0.0;
+    /// Compute the seven weighted AEOScore components from the document and its
+    /// stored signals. `breakdowns` are the per-platform rule results for the same
+    /// document.
+    pub fn calculate_aeo_score(
+        &self,
+        doc: &Document,
+        breakdowns: &[ScoreBreakdown],
+        signals: &ScoreSignals,
+    ) -> AEOScore {
+        // Structure, quality and readability rules are platform-independent
+        let content_quality = breakdowns
+            .first()
+            .map(|b| category_percent(b, |category| category != "platform"))
+            .unwrap_or(0.0);
 
-        // Check for headings
-        if content.contains("##") || content.contains("<h2") {
-            score += 0.3;
+        // How well the content matches what answer engines cite
+        let citation_potential = if breakdowns.is_empty() {
+            0.0
+        } else {
+            breakdowns
+                .iter()
+                .map(|b| category_percent(b, |category| category == "platform"))
+                .sum::<f64>()
+                / breakdowns.len() as f64
+        };
+
+        AEOScore::calculate(ScoreComponents {
+            schema_markup: self.score_schema_markup(signals.schema_types),
+            content_quality,
+            keyword_optimization: self.score_keywords(doc, signals.title, signals.keywords),
+            entity_coverage: self.score_entities(doc, signals.entities),
+            citation_potential,
+            freshness: self.score_freshness(signals.updated_at),
+            engagement: self.score_engagement(doc),
+        })
+    }
+
+    fn score_schema_markup(&self, schema_types: &[String]) -> f64 {
+        let mut distinct: Vec<&String> = schema_types.iter().collect();
+        distinct.sort();
+        distinct.dedup();
+
+        match distinct.len() {
+            0 => 0.0,
+            // One schema is the baseline, each extra type adds coverage
+            n => (50.0 + 25.0 * (n - 1) as f64).min(100.0),
         }
-        if content.contains("###") || content.contains("<h3") {
-            score += 0.2;
+    }
+
+    fn score_keywords(&self, doc: &Document, title: &str, keywords: &[String]) -> f64 {
+        if keywords.is_empty() {
+            return 0.0;
         }
 
-        // Check for lists
-        if content.contains("- ") || content.contains("* ") || content.contains("<ul") {
-            score += 0.2;
+        let headings: Vec<&str> = doc.headings().map(|(_, text, _)| text).collect();
+        let in_body = keywords.iter().filter(|kw| doc.mentions(kw)).count();
+        let in_title_or_headings = keywords
+            .iter()
+            .filter(|kw| contains_word(title, kw) || headings.iter().any(|h| contains_word(h, kw)))
+            .count();
+
+        let total = keywords.len() as f64;
+        (in_body as f64 / total) * 70.0 + (in_title_or_headings as f64 / total) * 30.0
+    }
+
+    fn score_entities(&self, doc: &Document, entities: &[String]) -> f64 {
+        if entities.is_empty() {
+            return 0.0;
         }
-        if content.contains("1. ") || content.contains("<ol") {
-            score += 0.2;
+
+        let covered = entities
+            .iter()
+            .filter(|entity| doc.mentions(entity))
+            .count();
+        covered as f64 / entities.len() as f64 * 100.0
+    }
+
+    fn score_freshness(&self, updated_at: DateTime<Utc>) -> f64 {
+        // Full marks for the first month, then linear decay to zero after a year
+        let age_days = (Utc::now() - updated_at).num_days().max(0) as f64;
+        if age_days <= 30.0 {
+            100.0
+        } else if age_days >= 365.0 {
+            0.0
+        } else {
+            (365.0 - age_days) / (365.0 - 30.0) * 100.0
         }
+    }
 
-        // Check for paragraphs (not too long)
-        let paragraphs: Vec<&str> = content.split("\n\n").collect();
-        if paragraphs.len() > 3 {
-            score += 0.1;
+    fn score_engagement(&self, doc: &Document) -> f64 {
+        let mut score = 0.0;
+
+        if doc.headings().any(|(_, text, _)| text.ends_with('?')) {
+            score += 25.0;
+        }
+        if !doc.images.is_empty() {
+            score += 25.0;
+        }
+        if doc.tables().next().is_some() {
+            score += 25.0;
+        }
+        if doc.mentions("you") || doc.mentions("your") {
+            score += 25.0;
         }
 
-        score.min(1.0)
+        score
     }
 
-    fn score_quality(&self, content: &str) -> f64 {
-        let mut score = 0.0;
-        let word_count = content.split_whitespace().count();
+    /// Build strengths and weaknesses from the rule results of one or more platforms
+    pub fn summarize(&self, breakdowns: &[ScoreBreakdown]) -> (Vec<String>, Vec<String>) {
+        let mut strengths: Vec<&RuleResult> = Vec::new();
+        let mut weaknesses: Vec<(&str, &RuleResult)> = Vec::new();
+
+        for breakdown in breakdowns {
+            for rule in &breakdown.rules {
+                if rule.points_possible <= 0.0 {
+                    continue;
+                }
+                if rule.points_awarded >= rule.points_possible {
+                    if !strengths.iter().any(|r| r.id == rule.id) {
+                        strengths.push(rule);
+                    }
+                } else if !weaknesses.iter().any(|(_, r)| r.id == rule.id) {
+                    weaknesses.push((&breakdown.platform, rule));
+                }
+            }
+        }
+
+        strengths.sort_by(|a, b| b.points_possible.total_cmp(&a.points_possible));
+        weaknesses.sort_by(|(_, a), (_, b)| {
+            (b.points_possible - b.points_awarded)
+                .total_cmp(&(a.points_possible - a.points_awarded))
+        });
+
+        let strengths = strengths
+            .into_iter()
+            .take(MAX_SUMMARY_ITEMS)
+            .map(|rule| match rule.evidence.first() {
+                Some(evidence) => format!("{}: \"{}\"", rule.description, evidence.text),
+                None => rule.description.clone(),
+            })
+            .collect();
+
+        let weaknesses = weaknesses
+            .into_iter()
+            .take(MAX_SUMMARY_ITEMS)
+            .map(|(platform, rule)| {
+                if rule.category == "platform" {
+                    format!("{} ({})", rule.suggestion, platform)
+                } else {
+                    rule.suggestion.clone()
+                }
+            })
+            .collect();
+
+        (strengths, weaknesses)
+    }
+
+    fn score_structure(&self, scorer: &mut Scorer) {
+        let doc = scorer.doc;
+
+        // Check for headings
+        scorer.check(
+            "structure.h2_headings",
+            0.3,
+            "Content is organised under H2 section headings",
+            "Add H2 section headings to break the content into sections",
+            doc.headings_at(2)
+                .map(|(text, span)| Evidence::at(text, span))
+                .collect(),
+        );
+        scorer.check(
+            "structure.h3_headings",
+            0.2,
+            "Sections are broken down with H3 subheadings",
+            "Add H3 subheadings inside longer sections",
+            doc.headings_at(3)
+                .map(|(text, span)| Evidence::at(text, span))
+                .collect(),
+        );
+
+        // Check for lists
+        scorer.check(
+            "structure.bullet_lists",
+            0.2,
+            "Uses bullet lists",
+            "Use bullet lists for sets of related points",
+            doc.lists(false).map(list_evidence).collect(),
+        );
+        scorer.check(
+            "structure.numbered_lists",
+            0.2,
+            "Uses numbered lists",
+            "Use numbered lists for sequences and rankings",
+            doc.lists(true).map(list_evidence).collect(),
+        );
+
+        // Check for paragraphs (content broken into several blocks)
+        let paragraphs: Vec<Evidence> = doc
+            .paragraphs()
+            .map(|(text, span)| Evidence::at(text, span))
+            .collect();
+        scorer.check(
+            "structure.paragraphs",
+            0.1,
+            "Body is split into more than three paragraphs",
+            "Split long passages into shorter paragraphs",
+            if paragraphs.len() > 3 {
+                paragraphs
+            } else {
+                Vec::new()
+            },
+        );
+    }
+
+    fn score_quality(&self, scorer: &mut Scorer) {
+        let doc = scorer.doc;
+        let word_count = doc.word_count();
 
         // Length check (500-2000 words is ideal)
-        if word_count >= 500 && word_count <= 2000 {
-            score += 0.4;
+        let length_weight = if (500..=2000).contains(&word_count) {
+            0.4
         } else if word_count > 2000 {
-            score += 0.3;
+            0.3
         } else if word_count >= 300 {
-            score += 0.2;
-        }
+            0.2
+        } else {
+            0.0
+        };
+        scorer.tiered(
+            "quality.length",
+            0.4,
+            length_weight,
+            "Length is within the ideal 500-2000 word range",
+            "Aim for 500-2000 words of body text",
+            vec![Evidence::note(format!("{} words", word_count))],
+        );
 
         // Check for examples/code blocks
-        if content.contains("```") || content.contains("example:") {
-            score += 0.2;
-        }
+        let mut examples: Vec<Evidence> = doc
+            .code_blocks()
+            .map(|(language, span)| Evidence::at(language.unwrap_or("code block"), span))
+            .collect();
+        examples.extend(scorer.term_evidence(&["example", "examples"]));
+        scorer.check(
+            "quality.examples",
+            0.2,
+            "Includes examples or code blocks",
+            "Add concrete examples or code blocks",
+            examples,
+        );
 
         // Check for questions (engaging)
-        if content.contains('?') {
-            score += 0.1;
-        }
+        let mut questions: Vec<Evidence> = doc
+            .headings()
+            .filter(|(_, text, _)| text.ends_with('?'))
+            .map(|(_, text, span)| Evidence::at(text, span))
+            .collect();
+        questions.extend(
+            doc.paragraphs()
+                .filter(|(text, _)| text.contains('?'))
+                .map(|(text, span)| Evidence::at(text, span)),
+        );
+        scorer.check(
+            "quality.questions",
+            0.1,
+            "Asks the questions readers search for",
+            "Phrase headings or lead-ins as the questions readers ask",
+            questions,
+        );
 
         // Check for actionable content
-        let actionable_words = ["how to", "step", "guide", "tutorial", "learn"];
-        if actionable_words.iter().any(|word| content.to_lowercase().contains(word)) {
-            score += 0.3;
-        }
-
-        score.min(1.0)
+        let actionable =
+            scorer.term_evidence(&["how to", "step", "steps", "guide", "tutorial", "learn"]);
+        scorer.check(
+            "quality.actionable",
+            0.3,
+            "Offers actionable, instructional content",
+            "Add actionable guidance such as how-to steps",
+            actionable,
+        );
     }
 
-    fn score_platform_optimization(&self, content: &str, platform: &AIPlatform) -> f64 {
-        match platform {
-            AIPlatform::ChatGPT => {
-                let mut score = 0.0;
-                // Numbered lists
-                if content.contains("1. ") {
-                    score += 0.3;
-                }
-                // Code examples
-                if content.contains("```") {
-                    score += 0.4;
-                }
-                // Step-by-step
-                if content.to_lowercase().contains("step") {
-                    score += 0.3;
-                }
-                score.min(1.0)
-            }
-            AIPlatform::Claude => {
-                let mut score = 0.0;
-                let word_count = content.split_whitespace().count();
-                // Depth (longer content)
-                if word_count > 1000 {
-                    score += 0.4;
-                }
-                // Multiple perspectives
-                if content.contains("however") || content.contains("alternatively") {
-                    score += 0.3;
-                }
-                // Context
-                if content.contains("background") || content.contains("context") {
-                    score += 0.3;
-                }
-                score.min(1.0)
-            }
-            AIPlatform::Perplexity => {
-                let mut score = 0.0;
-                // Citations
-                if content.contains("[") && content.contains("]") {
-                    score += 0.5;
-                }
-                // Sources
-                if content.to_lowercase().contains("source") || content.to_lowercase().contains("reference") {
-                    score += 0.3;
-                }
-                // Data/statistics
-                if content.contains('%') || content.chars().filter(|c| c.is_numeric()).count() > 10 {
-                    score += 0.2;
-                }
-                score.min(1.0)
-            }
-            AIPlatform::Gemini => {
-                let mut score = 0.0;
-                // Conversational tone
-                if content.contains("you") || content.contains("your") {
-                    score += 0.3;
-                }
-                // Visual descriptions
-                if content.to_lowercase().contains("image") || content.to_lowercase().contains("visual") {
-                    score += 0.4;
+    fn score_platform_optimization(&self, scorer: &mut Scorer, profile: &PlatformProfile) {
+        let doc = scorer.doc;
+        for rule in &profile.scoring {
+            let evidence = match &rule.signal {
+                Signal::Terms { terms } => scorer.term_evidence(terms),
+                Signal::NumberedLists => doc.lists(true).map(list_evidence).collect(),
+                Signal::CodeBlocks => doc
+                    .code_blocks()
+                    .map(|(language, span)| Evidence::at(language.unwrap_or("code block"), span))
+                    .collect(),
+                Signal::ExternalLinks => doc
+                    .external_links()
+                    .map(|link| Evidence::at(&link.describe(), &link.span))
+                    .collect(),
+                Signal::Images { terms } => {
+                    let mut visuals: Vec<Evidence> = doc
+                        .images
+                        .iter()
+                        .map(|image| Evidence::at(image.describe(), &image.span))
+                        .collect();
+                    visuals.extend(scorer.term_evidence(terms));
+                    visuals
                 }
-                // Local relevance
-                if content.to_lowercase().contains("location") || content.to_lowercase().contains("local") {
-                    score += 0.3;
-                }
-                score.min(1.0)
-            }
-            AIPlatform::Bing => {
-                let mut score = 0.0;
-                // Concise
-                let word_count = content.split_whitespace().count();
-                if word_count >= 300 && word_count <= 1000 {
-                    score += 0.4;
+                Signal::Headings { level } => doc
+                    .headings_at(*level)
+                    .map(|(text, span)| Evidence::at(text, span))
+                    .collect(),
+                Signal::WordCount { min, max } => {
+                    let word_count = doc.word_count();
+                    if min.is_none_or(|min| word_count >= min)
+                        && max.is_none_or(|max| word_count <= max)
+                    {
+                        vec![Evidence::note(format!("{} words", word_count))]
+                    } else {
+                        Vec::new()
+                    }
                 }
-                // Clear headings
-                if content.contains("##") {
-                    score += 0.3;
-                }
-                // Authoritative
-                if content.to_lowercase().contains("research") || content.to_lowercase().contains("study") {
-                    score += 0.3;
-                }
-                score.min(1.0)
-            }
+                Signal::DataPoints => doc
+                    .blocks
+                    .iter()
+                    .filter(|block| {
+                        let text = block.text();
+                        text.contains('%') || text.chars().filter(|c| c.is_numeric()).count() > 10
+                    })
+                    .map(Evidence::block)
+                    .collect(),
+            };
+
+            scorer.check(
+                &rule.id,
+                rule.weight,
+                &rule.description,
+                &rule.suggestion,
+                evidence,
+            );
         }
     }
 
-    fn score_readability(&self, content: &str) -> f64 {
-        let mut score = 0.0;
-        let sentences: Vec<&str> = content.split(&['.', '!', '?'][..]).collect();
+    fn score_readability(&self, scorer: &mut Scorer) {
+        let doc = scorer.doc;
 
-        if !sentences.is_empty() {
-            let words: Vec<&str> = content.split_whitespace().collect();
-            let avg_sentence_length = words.len() / sentences.len();
+        // Sentence length is measured on paragraphs only; headings and list
+        // items are fragments by design
+        let paragraph_text = doc
+            .paragraphs()
+            .map(|(text, _)| text)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let sentences = paragraph_text
+            .split(&['.', '!', '?'][..])
+            .filter(|s| !s.trim().is_empty())
+            .count();
 
+        let mut sentence_weight = 0.0;
+        let mut sentence_evidence = Vec::new();
+        if let Some(avg_sentence_length) = paragraph_text
+            .split_whitespace()
+            .count()
+            .checked_div(sentences)
+        {
             // Ideal sentence length: 15-20 words
-            if avg_sentence_length >= 15 && avg_sentence_length <= 20 {
-                score += 0.5;
+            if (15..=20).contains(&avg_sentence_length) {
+                sentence_weight = 0.5;
             } else if avg_sentence_length < 25 {
-                score += 0.3;
+                sentence_weight = 0.3;
             }
+            sentence_evidence.push(Evidence::note(format!(
+                "{} words per sentence on average",
+                avg_sentence_length
+            )));
         }
+        scorer.tiered(
+            "readability.sentence_length",
+            0.5,
+            sentence_weight,
+            "Sentences average 15-20 words",
+            "Aim for sentences of 15-20 words on average",
+            sentence_evidence,
+        );
 
         // Check for transition words
-        let transitions = ["however", "therefore", "additionally", "furthermore", "moreover"];
-        if transitions.iter().any(|t| content.to_lowercase().contains(t)) {
-            score += 0.3;
-        }
+        let transitions = scorer.term_evidence(&[
+            "however",
+            "therefore",
+            "additionally",
+            "furthermore",
+            "moreover",
+        ]);
+        scorer.check(
+            "readability.transitions",
+            0.3,
+            "Uses transition words between ideas",
+            "Link ideas with transition words such as \"however\" or \"therefore\"",
+            transitions,
+        );
 
         // Check for clear formatting
-        if content.contains("\n\n") {
-            score += 0.2;
-        }
-
-        score.min(1.0)
+        let paragraphs: Vec<Evidence> = doc
+            .paragraphs()
+            .map(|(text, span)| Evidence::at(text, span))
+            .collect();
+        scorer.check(
+            "readability.formatting",
+            0.2,
+            "Text is separated into paragraphs",
+            "Separate the text into paragraphs",
+            if paragraphs.len() > 1 {
+                paragraphs
+            } else {
+                Vec::new()
+            },
+        );
     }
 
-    fn identify_improvements(&self, content: &str, platform: &AIPlatform) -> Vec<Improvement> {
+    fn identify_improvements(
+        &self,
+        doc: &Document,
+        breakdown: &ScoreBreakdown,
+        profile: &PlatformProfile,
+    ) -> Vec<Improvement> {
         let mut improvements = Vec::new();
 
         // Structure improvements
-        if !content.contains("##") && !content.contains("<h2") {
+        if doc.headings_at(2).next().is_none() {
             improvements.push(Improvement {
                 category: "Structure".to_string(),
-                description: "Add section headings (H2, H3) to improve content structure".to_string(),
+                description: "Add section headings (H2, H3) to improve content structure"
+                    .to_string(),
                 impact: "high".to_string(),
             });
         }
 
-        // Platform-specific improvements
-        match platform {
-            AIPlatform::ChatGPT => {
-                if !content.contains("```") {
-                    improvements.push(Improvement {
-                        category: "Examples".to_string(),
-                        description: "Add code examples or practical demonstrations".to_string(),
-                        impact: "high".to_string(),
-                    });
-                }
-                if !content.contains("1. ") {
-                    improvements.push(Improvement {
-                        category: "Format".to_string(),
-                        description: "Use numbered lists for step-by-step instructions".to_string(),
-                        impact: "medium".to_string(),
-                    });
-                }
-            }
-            AIPlatform::Perplexity => {
-                if !content.contains("[") {
-                    improvements.push(Improvement {
-                        category: "Citations".to_string(),
-                        description: "Add citations and references to authoritative sources".to_string(),
-                        impact: "high".to_string(),
-                    });
-                }
+        // Platform rules that failed and carry an improvement hint
+        for rule in &profile.scoring {
+            let Some(hint) = &rule.improvement else {
+                continue;
+            };
+            let failed = breakdown
+                .rules
+                .iter()
+                .any(|result| result.id == rule.id && result.points_awarded == 0.0);
+            if failed {
+                improvements.push(Improvement {
+                    category: hint.category.clone(),
+                    description: rule.suggestion.clone(),
+                    impact: hint.impact.clone(),
+                });
             }
-            AIPlatform::Claude => {
-                if content.split_whitespace().count() < 800 {
-                    improvements.push(Improvement {
-                        category: "Depth".to_string(),
-                        description: "Expand content with more detailed explanations and context".to_string(),
-                        impact: "medium".to_string(),
-                    });
+        }
+
+        improvements
+    }
+
+    fn apply_optimizations(
+        &self,
+        content: &str,
+        profile: &PlatformProfile,
+        strategy: Option<&PlatformStrategy>,
+    ) -> Result<RewriteResult, RuleError> {
+        let mut rules = self.rule_engine.default_rules(profile);
+
+        // Custom rules run after the defaults; one sharing a default's name
+        // replaces it, so a strategy can also disable a built-in rule
+        if let Some(strategy) = strategy.filter(|s| s.active && s.platform == profile.id) {
+            for custom in &strategy.custom_rules {
+                match rules.iter_mut().find(|rule| rule.name == custom.name) {
+                    Some(rule) => *rule = custom.clone(),
+                    None => rules.push(custom.clone()),
                 }
             }
-            _ => {}
         }
 
-        improvements
+        self.rule_engine.apply(content, &rules)
     }
 
-    fn apply_optimizations(&self, content: &str, _platform: &AIPlatform) -> String {
-        // In a real implementation, this would apply AI-powered optimizations
-        // For now, return the original content
-        content.to_string()
-    }
-
-    fn get_platform_tips(&self, platform: &AIPlatform) -> Vec<String> {
-        match platform {
-            AIPlatform::ChatGPT => vec![
-                "Use clear, numbered steps for instructions".to_string(),
-                "Include practical code examples".to_string(),
-                "Break down complex topics into digestible sections".to_string(),
-                "Use descriptive headings that preview content".to_string(),
-            ],
-            AIPlatform::Claude => vec![
-                "Provide comprehensive coverage with depth".to_string(),
-                "Include context and background information".to_string(),
-                "Present multiple perspectives on the topic".to_string(),
-                "Use nuanced language that acknowledges complexity".to_string(),
-            ],
-            AIPlatform::Perplexity => vec![
-                "Cite authoritative sources and research".to_string(),
-                "Include recent data and statistics".to_string(),
-                "Link to credible external references".to_string(),
-                "Use verifiable facts and evidence".to_string(),
-            ],
-            AIPlatform::Gemini => vec![
-                "Incorporate multimedia descriptions".to_string(),
-                "Add local and geographic relevance".to_string(),
-                "Use conversational, accessible language".to_string(),
-                "Include visual elements where appropriate".to_string(),
-            ],
-            AIPlatform::Bing => vec![
-                "Keep content concise and to the point".to_string(),
-                "Use authoritative, well-researched information".to_string(),
-                "Ensure content freshness and timeliness".to_string(),
-                "Optimize for featured snippet format".to_string(),
-            ],
+    fn get_platform_tips(&self, profile: &PlatformProfile) -> Vec<String> {
+        profile.tips.clone()
+    }
+}
+
+/// Collects rule results category by category
+struct Scorer<'a> {
+    doc: &'a Document,
+    category: &'static str,
+    category_points: f64,
+    rules: Vec<RuleResult>,
+}
+
+impl<'a> Scorer<'a> {
+    fn new(doc: &'a Document) -> Self {
+        Self {
+            doc,
+            category: "",
+            category_points: 0.0,
+            rules: Vec::new(),
+        }
+    }
+
+    fn category(&mut self, name: &'static str, points: f64) {
+        self.category = name;
+        self.category_points = points;
+    }
+
+    /// Pass/fail rule: full weight when there is any evidence
+    fn check(
+        &mut self,
+        id: &str,
+        weight: f64,
+        description: &str,
+        suggestion: &str,
+        evidence: Vec<Evidence>,
+    ) {
+        let awarded = if evidence.is_empty() { 0.0 } else { weight };
+        self.tiered(id, weight, awarded, description, suggestion, evidence);
+    }
+
+    /// Rule that can award part of its weight
+    fn tiered(
+        &mut self,
+        id: &str,
+        weight: f64,
+        awarded_weight: f64,
+        description: &str,
+        suggestion: &str,
+        mut evidence: Vec<Evidence>,
+    ) {
+        evidence.truncate(MAX_EVIDENCE);
+        self.rules.push(RuleResult {
+            id: id.to_string(),
+            category: self.category.to_string(),
+            description: description.to_string(),
+            suggestion: suggestion.to_string(),
+            weight,
+            points_awarded: awarded_weight * self.category_points,
+            points_possible: weight * self.category_points,
+            evidence,
+        });
+    }
+
+    /// One evidence entry per block mentioning any of `terms`
+    fn term_evidence<T: AsRef<str>>(&self, terms: &[T]) -> Vec<Evidence> {
+        self.doc
+            .blocks
+            .iter()
+            .filter(|block| {
+                terms
+                    .iter()
+                    .any(|term| contains_word(&block.text(), term.as_ref()))
+            })
+            .map(Evidence::block)
+            .collect()
+    }
+
+    fn finish(self, platform: &str) -> ScoreBreakdown {
+        let awarded: f64 = self.rules.iter().map(|r| r.points_awarded).sum();
+        let possible: f64 = self.rules.iter().map(|r| r.points_possible).sum();
+
+        ScoreBreakdown {
+            platform: platform.to_string(),
+            score: if possible > 0.0 {
+                awarded / possible * 100.0
+            } else {
+                0.0
+            },
+            rules: self.rules,
         }
     }
 }
 
+impl Evidence {
+    /// Evidence quoting a piece of the body
+    fn at(text: &str, span: &Span) -> Self {
+        Self {
+            text: excerpt(text),
+            span: Some(span.clone()),
+        }
+    }
+
+    fn block(block: &Block) -> Self {
+        Self::at(&block.text(), block.span())
+    }
+
+    /// Evidence that is a measurement rather than a quote
+    fn note(text: String) -> Self {
+        Self { text, span: None }
+    }
+}
+
+/// Signals stored alongside the body that feed the AEOScore components
+pub struct ScoreSignals<'a> {
+    pub title: &'a str,
+    pub keywords: &'a [String],
+    pub entities: &'a [String],
+    pub schema_types: &'a [String],
+    pub updated_at: DateTime<Utc>,
+}
+
+fn category_percent(breakdown: &ScoreBreakdown, include: impl Fn(&str) -> bool) -> f64 {
+    let (awarded, possible) = breakdown
+        .rules
+        .iter()
+        .filter(|rule| include(&rule.category))
+        .fold((0.0, 0.0), |(a, p), rule| {
+            (a + rule.points_awarded, p + rule.points_possible)
+        });
+
+    if possible > 0.0 {
+        awarded / possible * 100.0
+    } else {
+        0.0
+    }
+}
+
+fn list_evidence((items, span): (&[String], &Span)) -> Evidence {
+    Evidence::at(items.first().map(String::as_str).unwrap_or_default(), span)
+}
+
+fn excerpt(text: &str) -> String {
+    const MAX_CHARS: usize = 120;
+    let first_line = text.lines().next().unwrap_or_default();
+    if first_line.chars().count() > MAX_CHARS {
+        format!(
+            "{}...",
+            first_line.chars().take(MAX_CHARS).collect::<String>()
+        )
+    } else {
+        first_line.to_string()
+    }
+}
+
 impl Default for AEOOptimizer {
     fn default() -> Self {
-        Self::new()
+        Self::new(Arc::new(PlatformRegistry::builtin().clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asa_models::aeo::platform::RuleKind;
+
+    const ARTICLE: &str = r##"# Answer engine optimization
+
+## What is AEO?
+
+Answer engines quote short, direct answers to your questions. However, they also reward clear structure.
+
+## How to start
+
+1. Pick a question your readers ask.
+2. Answer it in the first sentence.
+
+- Keep paragraphs short
+- Link to sources
+
+```rust
+fn main() {}
+```
+
+For example, a recipe page can answer one question per section.
+"##;
+
+    fn chatgpt() -> &'static PlatformProfile {
+        PlatformRegistry::builtin().get("chatgpt").unwrap()
+    }
+
+    fn breakdown() -> ScoreBreakdown {
+        AEOOptimizer::default().score_breakdown(&Document::parse_markdown(ARTICLE), chatgpt())
+    }
+
+    fn result<'a>(breakdown: &'a ScoreBreakdown, id: &str) -> &'a RuleResult {
+        breakdown.rules.iter().find(|rule| rule.id == id).unwrap()
+    }
+
+    fn rule(
+        id: &str,
+        category: &str,
+        awarded: f64,
+        possible: f64,
+        quote: Option<&str>,
+    ) -> RuleResult {
+        RuleResult {
+            id: id.to_string(),
+            category: category.to_string(),
+            description: format!("{} passes", id),
+            suggestion: format!("Fix {}", id),
+            weight: 1.0,
+            points_awarded: awarded,
+            points_possible: possible,
+            evidence: quote
+                .map(|text| Evidence::note(text.to_string()))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn platform(name: &str, rules: Vec<RuleResult>) -> ScoreBreakdown {
+        ScoreBreakdown {
+            platform: name.to_string(),
+            score: 0.0,
+            rules,
+        }
+    }
+
+    #[test]
+    fn test_breakdown_quotes_evidence_with_spans() {
+        let breakdown = breakdown();
+
+        let headings = result(&breakdown, "structure.h2_headings");
+        let quoted: Vec<&str> = headings.evidence.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(quoted, vec!["What is AEO?", "How to start"]);
+        for evidence in &headings.evidence {
+            let span = evidence.span.clone().unwrap();
+            assert!(ARTICLE[span].contains(&evidence.text));
+        }
+
+        let numbered = result(&breakdown, "structure.numbered_lists");
+        assert_eq!(
+            numbered.evidence[0].text,
+            "Pick a question your readers ask."
+        );
+        let code = result(&breakdown, "chatgpt.code_examples");
+        assert_eq!(code.evidence[0].text, "rust");
+
+        // Measurements are notes rather than quotes
+        let length = result(&breakdown, "quality.length");
+        assert!(length.evidence[0].text.ends_with(" words"));
+        assert!(length.evidence[0].span.is_none());
+    }
+
+    #[test]
+    fn test_breakdown_points() {
+        let breakdown = breakdown();
+        assert_eq!(breakdown.platform, "ChatGPT");
+
+        // Pass/fail rules award their whole share of the category or nothing
+        let awarded = |id: &str| {
+            let rule = result(&breakdown, id);
+            (rule.points_awarded, rule.points_possible)
+        };
+        assert_eq!(awarded("structure.h2_headings"), (0.3 * 30.0, 0.3 * 30.0));
+        assert_eq!(awarded("structure.h3_headings"), (0.0, 0.2 * 30.0));
+        assert_eq!(awarded("chatgpt.numbered_lists"), (0.3 * 25.0, 0.3 * 25.0));
+        assert_eq!(awarded("chatgpt.code_examples"), (0.4 * 25.0, 0.4 * 25.0));
+        assert_eq!(awarded("chatgpt.step_by_step"), (0.0, 0.3 * 25.0));
+        assert_eq!(awarded("readability.transitions"), (0.3 * 20.0, 0.3 * 20.0));
+        // Too short for any length tier
+        assert_eq!(awarded("quality.length"), (0.0, 0.4 * 25.0));
+
+        // Every category's weights add up to one, so the rules share 100 points
+        // and the score is the points awarded
+        let possible: f64 = breakdown.rules.iter().map(|r| r.points_possible).sum();
+        let total: f64 = breakdown.rules.iter().map(|r| r.points_awarded).sum();
+        assert!((possible - 100.0).abs() < 1e-9);
+        assert!((breakdown.score - total).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aeo_score_components() {
+        let optimizer = AEOOptimizer::default();
+        let doc = Document::parse_markdown(ARTICLE);
+        let breakdown = breakdown();
+        let keywords = [
+            "answer".to_string(),
+            "sources".to_string(),
+            "blockchain".to_string(),
+        ];
+        let entities = ["AEO".to_string(), "Perplexity".to_string()];
+        let schema_types = [
+            "Article".to_string(),
+            "FAQPage".to_string(),
+            "Article".to_string(),
+        ];
+
+        let score = optimizer.calculate_aeo_score(
+            &doc,
+            std::slice::from_ref(&breakdown),
+            &ScoreSignals {
+                title: "Answer engine optimization",
+                keywords: &keywords,
+                entities: &entities,
+                schema_types: &schema_types,
+                updated_at: Utc::now(),
+            },
+        );
+        let c = &score.components;
+
+        // Two distinct schema types: the baseline plus one
+        assert_eq!(c.schema_markup, 75.0);
+        // Platform-independent rules as a share of their points
+        let (awarded, possible) = breakdown
+            .rules
+            .iter()
+            .filter(|rule| rule.category != "platform")
+            .fold((0.0, 0.0), |(a, p), rule| {
+                (a + rule.points_awarded, p + rule.points_possible)
+            });
+        assert!((c.content_quality - awarded / possible * 100.0).abs() < 1e-9);
+        // Two of three keywords in the body, one in the title
+        assert!((c.keyword_optimization - (2.0 / 3.0 * 70.0 + 1.0 / 3.0 * 30.0)).abs() < 1e-9);
+        // AEO is mentioned, Perplexity isn't
+        assert_eq!(c.entity_coverage, 50.0);
+        // Numbered lists and code examples, but no step-by-step framing
+        assert!((c.citation_potential - 70.0).abs() < 1e-9);
+        assert_eq!(c.freshness, 100.0);
+        // A question heading and addressing the reader; no images or tables
+        assert_eq!(c.engagement, 50.0);
+
+        assert_eq!(score.overall, AEOScore::calculate(c.clone()).overall);
+    }
+
+    #[test]
+    fn test_aeo_score_without_signals() {
+        let optimizer = AEOOptimizer::default();
+        let doc = Document::parse_markdown(ARTICLE);
+        let signals = |days: i64| ScoreSignals {
+            title: "",
+            keywords: &[],
+            entities: &[],
+            schema_types: &[],
+            updated_at: Utc::now() - chrono::Duration::days(days),
+        };
+
+        let score = optimizer.calculate_aeo_score(&doc, &[], &signals(200));
+        let c = &score.components;
+        assert_eq!(
+            (c.schema_markup, c.content_quality, c.keyword_optimization),
+            (0.0, 0.0, 0.0)
+        );
+        assert_eq!((c.entity_coverage, c.citation_potential), (0.0, 0.0));
+        // Decays linearly from a month old to a year old
+        assert!((c.freshness - 165.0 / 335.0 * 100.0).abs() < 1e-9);
+
+        let stale = optimizer.calculate_aeo_score(&doc, &[], &signals(400));
+        assert_eq!(stale.components.freshness, 0.0);
+    }
+
+    fn strategy(platform: &str, priority: u8, active: bool) -> PlatformStrategy {
+        PlatformStrategy {
+            platform: platform.to_string(),
+            priority,
+            active,
+            custom_rules: Vec::new(),
+        }
+    }
+
+    fn regex_rule(name: &str, pattern: &str, replacement: &str, enabled: bool) -> OptimizationRule {
+        OptimizationRule {
+            name: name.to_string(),
+            description: format!("Custom {}", name),
+            kind: RuleKind::Regex,
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            enabled,
+        }
+    }
+
+    #[test]
+    fn test_overall_score_weights_by_priority() {
+        let optimizer = AEOOptimizer::default();
+        let scores = [("chatgpt", 80.0), ("perplexity", 40.0), ("claude", 60.0)];
+
+        // Perplexity is switched off and Claude has no strategy, so it
+        // counts at the default priority
+        let strategies = [
+            strategy("chatgpt", 10, true),
+            strategy("perplexity", 9, false),
+        ];
+        let expected = (80.0 * 10.0 + 60.0 * 5.0) / 15.0;
+        assert!((optimizer.overall_score(&scores, &strategies) - expected).abs() < 1e-9);
+
+        // Without strategies every platform weighs the same
+        assert!((optimizer.overall_score(&scores, &[]) - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_overall_score_without_weights() {
+        let optimizer = AEOOptimizer::default();
+        let scores = [("chatgpt", 80.0), ("perplexity", 40.0)];
+        let strategies = [
+            strategy("chatgpt", 10, false),
+            strategy("perplexity", 3, false),
+        ];
+
+        assert_eq!(optimizer.overall_score(&scores, &strategies), 60.0);
+        assert_eq!(optimizer.overall_score(&[], &strategies), 0.0);
+    }
+
+    #[test]
+    fn test_custom_rules_merge_with_defaults() {
+        let optimizer = AEOOptimizer::default();
+        let body = "Intro is very short.\n\n\n\nMore text.\n";
+        let mut custom = strategy("chatgpt", 8, true);
+        custom.custom_rules = vec![
+            // Replaces the default of the same name, switching it off
+            regex_rule("collapse_blank_lines", r"\n{3,}", "\n\n", false),
+            regex_rule("drop_very", r"very ", "", true),
+        ];
+
+        let result = optimizer
+            .apply_optimizations(body, chatgpt(), Some(&custom))
+            .unwrap();
+        assert_eq!(result.body, "Intro is short.\n\n\n\nMore text.\n");
+        let applied: Vec<_> = result
+            .applied
+            .iter()
+            .map(|rule| rule.name.as_str())
+            .collect();
+        assert_eq!(applied, vec!["drop_very"]);
+
+        // Custom rules only count for an active strategy on the same platform
+        let mut inactive = custom.clone();
+        inactive.active = false;
+        let mut elsewhere = custom.clone();
+        elsewhere.platform = "perplexity".to_string();
+        for strategy in [None, Some(&inactive), Some(&elsewhere)] {
+            let result = optimizer
+                .apply_optimizations(body, chatgpt(), strategy)
+                .unwrap();
+            assert_eq!(result.body, "Intro is very short.\n\nMore text.\n");
+            assert_eq!(result.applied.len(), 1);
+            assert_eq!(result.applied[0].name, "collapse_blank_lines");
+        }
+    }
+
+    #[test]
+    fn test_summarize_strengths_and_weaknesses() {
+        let breakdowns = [
+            platform(
+                "ChatGPT",
+                vec![
+                    rule(
+                        "structure.lists",
+                        "structure",
+                        6.0,
+                        6.0,
+                        Some("Pick a question"),
+                    ),
+                    rule("structure.headings", "structure", 9.0, 9.0, None),
+                    rule("chatgpt.code", "platform", 0.0, 10.0, None),
+                    rule("quality.length", "quality", 2.0, 10.0, None),
+                    rule("quality.ignored", "quality", 0.0, 0.0, None),
+                ],
+            ),
+            platform(
+                "Perplexity",
+                vec![
+                    // Already reported for ChatGPT
+                    rule("structure.headings", "structure", 9.0, 9.0, None),
+                    rule("quality.length", "quality", 0.0, 10.0, None),
+                    rule("perplexity.citations", "platform", 0.0, 4.0, None),
+                ],
+            ),
+        ];
+
+        let (strengths, weaknesses) = AEOOptimizer::default().summarize(&breakdowns);
+        // Biggest rules first, quoting the first piece of evidence
+        assert_eq!(
+            strengths,
+            vec![
+                "structure.headings passes",
+                "structure.lists passes: \"Pick a question\"",
+            ]
+        );
+        // Most points missed first; platform rules name their platform
+        assert_eq!(
+            weaknesses,
+            vec![
+                "Fix chatgpt.code (ChatGPT)",
+                "Fix quality.length",
+                "Fix perplexity.citations (Perplexity)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summarize_keeps_the_top_items() {
+        let rules = (0..8)
+            .map(|i| {
+                rule(
+                    &format!("rule.{}", i),
+                    "structure",
+                    0.0,
+                    i as f64 + 1.0,
+                    None,
+                )
+            })
+            .collect();
+        let (strengths, weaknesses) =
+            AEOOptimizer::default().summarize(&[platform("Claude", rules)]);
+        assert!(strengths.is_empty());
+        assert_eq!(weaknesses.len(), MAX_SUMMARY_ITEMS);
+        assert_eq!(weaknesses[0], "Fix rule.7");
     }
 }
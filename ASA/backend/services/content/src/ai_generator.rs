@@ -1,298 +1,681 @@
-use anyhow::Result;
-use serde_json::json;
+use asa_models::aeo::profile::PlatformProfile;
+use asa_models::ContentType;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::article::{
+    parse_outline, section_body, ArticleError, GeneratedArticle, OUTLINE_SHAPE,
+};
+use crate::document::Document;
+use crate::llm::{
+    CompletionRequest, GenerationError, LlmProvider, LlmProviders, TokenStream, TokenUsage,
+};
+use crate::models::{GenerateArticleRequest, GenerateContentRequest, GenerateOutlineRequest};
+use crate::prompts::{PromptContext, PromptTemplate, SectionContext, TemplateRef, WrittenSection};
+
+/// How many of the latest sections are sent in full when writing the next
+/// one; earlier sections are passed by heading only
+const RECENT_SECTIONS: usize = 2;
+
+/// Which backend produced a generation, from what prompt, at what cost
+#[derive(Debug, Clone)]
+pub struct Provenance {
+    pub provider: String,
+    pub model: String,
+    /// Produced by the fixture provider
+    pub mock: bool,
+    pub system_prompt: String,
+    pub prompt: String,
+    /// None only for jobs recorded before templates were versioned
+    pub template: Option<TemplateRef>,
+    /// Summed over repair round-trips; None when the backend reports nothing
+    pub usage: Option<TokenUsage>,
+    pub latency: Duration,
+    /// USD, None when the model has no configured price
+    pub estimated_cost: Option<f64>,
+}
 
-pub struct AIGenerator {
-    openai_api_key: String,
-    anthropic_api_key: String,
-    client: reqwest::Client,
+/// A generated article and the backend that wrote it
+pub struct Generation {
+    pub title: String,
+    pub body: String,
+    pub outline: Vec<String>,
+    pub provenance: Provenance,
 }
 
-impl AIGenerator {
-    pub fn new(openai_api_key: String, anthropic_api_key: String) -> Self {
+pub struct OutlineGeneration {
+    pub outline: Vec<String>,
+    pub provenance: Provenance,
+}
+
+/// What an article should be about
+pub struct ContentBrief<'a> {
+    pub topic: &'a str,
+    pub content_type: ContentType,
+    /// Resolved from the service's registry, so loaded profiles work too
+    pub target_platform: &'a PlatformProfile,
+    pub tone: Option<&'a str>,
+    pub length: Option<&'a str>,
+    pub keywords: Option<&'a [String]>,
+}
+
+impl<'a> From<(&'a GenerateOutlineRequest, &'a PlatformProfile)> for ContentBrief<'a> {
+    fn from(
+        (request, target_platform): (&'a GenerateOutlineRequest, &'a PlatformProfile),
+    ) -> Self {
         Self {
-            openai_api_key,
-            anthropic_api_key,
-            client: reqwest::Client::new(),
+            topic: &request.topic,
+            content_type: request.content_type,
+            target_platform,
+            tone: None,
+            length: None,
+            keywords: None,
         }
     }
+}
 
-    pub async fn generate_content(
-        &self,
-        topic: &str,
-        content_type: &str,
-        target_platform: &str,
-        tone: Option<&str>,
-        length: Option<&str>,
-        keywords: Option<&[String]>,
-    ) -> Result<(String, String, Vec<String>)> {
-        let platform_guidance = self.get_platform_guidance(target_platform);
-
-        let keywords_str = keywords
-            .map(|kw| format!("Keywords to include: {}", kw.join(", ")))
-            .unwrap_or_default();
+impl<'a> From<(&'a GenerateContentRequest, &'a PlatformProfile)> for ContentBrief<'a> {
+    fn from(
+        (request, target_platform): (&'a GenerateContentRequest, &'a PlatformProfile),
+    ) -> Self {
+        Self {
+            topic: &request.topic,
+            content_type: request.content_type,
+            target_platform,
+            tone: request.tone.as_deref(),
+            length: request.length.as_deref(),
+            keywords: request.keywords.as_deref(),
+        }
+    }
+}
 
-        let prompt = format!(
-            r#"Generate comprehensive content optimized for {} with the following specifications:
+impl<'a> From<(&'a GenerateArticleRequest, &'a PlatformProfile)> for ContentBrief<'a> {
+    fn from(
+        (request, target_platform): (&'a GenerateArticleRequest, &'a PlatformProfile),
+    ) -> Self {
+        Self {
+            topic: &request.topic,
+            content_type: request.content_type,
+            target_platform,
+            tone: request.tone.as_deref(),
+            length: request.length.as_deref(),
+            keywords: request.keywords.as_deref(),
+        }
+    }
+}
 
-Topic: {}
-Content Type: {}
-Tone: {}
-Length: {}
-{}
+/// An article still being written by the provider; usage, latency and cost
+/// are filled in by `AIGenerator::finish`
+pub struct ArticleStream {
+    pub provenance: Provenance,
+    pub started: Instant,
+    /// Markdown deltas, title first as an H1
+    pub tokens: TokenStream,
+}
 
-Platform Optimization Guidelines:
-{}
+impl Generation {
+    /// Title and outline read back from a streamed markdown article
+    pub fn from_markdown(body: String, provenance: Provenance) -> Self {
+        let doc = Document::parse_markdown(&body);
+        let title = doc
+            .headings_at(1)
+            .next()
+            .map_or("Generated Content", |(text, _)| text)
+            .to_string();
+        let outline = doc.headings_at(2).map(|(text, _)| text.to_string()).collect();
 
-Please provide:
-1. An engaging, SEO-optimized title
-2. Well-structured content with clear sections
-3. Proper headings (H2, H3)
-4. Actionable insights and examples
-5. Natural keyword integration
-6. Clear, concise language
+        Self {
+            title,
+            body,
+            outline,
+            provenance,
+        }
+    }
+}
 
+#[derive(Clone)]
+pub struct AIGenerator {
+    providers: LlmProviders,
+}
+
+impl AIGenerator {
+    pub fn new(providers: LlmProviders) -> Self {
+        Self { providers }
+    }
+
+    pub fn providers(&self) -> &LlmProviders {
+        &self.providers
+    }
+
+    pub async fn generate_content(
+        &self,
+        brief: &ContentBrief<'_>,
+        template: &PromptTemplate,
+        provider: Option<&str>,
+    ) -> Result<Generation, GenerationError> {
+        let prompt = format!(
+            r#"{}
 Format the response as JSON with:
 - title: The optimized title
 - body: The full content in markdown
 - outline: Array of main section headings
 "#,
-            target_platform,
-            topic,
-            content_type,
-            tone.unwrap_or("professional"),
-            length.unwrap_or("medium"),
-            keywords_str,
-            platform_guidance
+            self.render(template, &self.context(brief))?
         );
 
-        // Use OpenAI GPT-4 or Claude depending on target platform
-        let result = if target_platform.to_lowercase().contains("claude") {
-            self.generate_with_claude(&prompt).await?
-        } else {
-            self.generate_with_openai(&prompt).await?
-        };
-
-        Ok(result)
+        let provider = self.providers.select(provider, &brief.target_platform.id)?;
+        self.complete_article(provider.as_ref(), template, prompt).await
     }
 
-    pub async fn generate_outline(
+    /// Same brief as `generate_content`, answered in plain markdown so it
+    /// can be shown while it is written
+    pub async fn stream_content(
         &self,
-        topic: &str,
-        content_type: &str,
-        target_platform: &str,
-    ) -> Result<Vec<String>> {
-        let platform_guidance = self.get_platform_guidance(target_platform);
-
+        brief: &ContentBrief<'_>,
+        template: &PromptTemplate,
+        provider: Option<&str>,
+    ) -> Result<ArticleStream, GenerationError> {
         let prompt = format!(
-            r#"Create a detailed outline for {} content about "{}".
+            "{}\nRespond with the article in markdown only, starting with the title as an H1 heading.\n",
+            self.render(template, &self.context(brief))?
+        );
 
-Platform: {}
-Optimization Guidelines:
-{}
+        let provider = self.providers.select(provider, &brief.target_platform.id)?;
+        let started = Instant::now();
+        let request = CompletionRequest::new(&prompt).system(&template.system_prompt);
+        let tokens = self.providers.stream(provider.as_ref(), &request).await?;
 
-Provide a structured outline with main sections and subsections.
-Format as a JSON array of strings.
-"#,
-            content_type, topic, target_platform, platform_guidance
-        );
+        Ok(ArticleStream {
+            provenance: provenance(provider.as_ref(), template, prompt),
+            started,
+            tokens,
+        })
+    }
 
-        let (_, _, outline) = self.generate_with_openai(&prompt).await?;
-        Ok(outline)
+    /// Record what a finished request or stream used
+    pub fn finish(
+        &self,
+        mut provenance: Provenance,
+        started: Instant,
+        usage: Option<TokenUsage>,
+    ) -> Provenance {
+        provenance.latency = started.elapsed();
+        provenance.estimated_cost = self.estimate_cost(&provenance, usage.as_ref());
+        provenance.usage = usage;
+        provenance
     }
 
-    async fn generate_with_openai(&self, prompt: &str) -> Result<(String, String, Vec<String>)> {
-        if self.openai_api_key.is_empty() {
-            return self.generate_mock_content(prompt);
+    /// Write an article one outline section at a time. Each section is
+    /// prompted with the whole outline and the sections written before it,
+    /// so long pieces are neither cut off nor repeat themselves.
+    pub async fn generate_sections(
+        &self,
+        brief: &ContentBrief<'_>,
+        template: &PromptTemplate,
+        title: &str,
+        outline: &[String],
+        provider: Option<&str>,
+    ) -> Result<Generation, GenerationError> {
+        let provider = self.providers.select(provider, &brief.target_platform.id)?;
+        let started = Instant::now();
+        let mut sections: Vec<String> = Vec::with_capacity(outline.len());
+        let mut prompts = Vec::with_capacity(outline.len());
+        let mut usage = None;
+
+        for (index, heading) in outline.iter().enumerate() {
+            let recent = index.saturating_sub(RECENT_SECTIONS);
+            let context = SectionContext {
+                brief: self.context(brief),
+                title,
+                outline,
+                section: heading,
+                position: index + 1,
+                previous: outline
+                    .iter()
+                    .zip(&sections)
+                    .enumerate()
+                    .map(|(i, (heading, body))| WrittenSection {
+                        heading,
+                        body: (i >= recent).then_some(body.as_str()),
+                    })
+                    .collect(),
+            };
+            let prompt = format!(
+                "{}\nRespond with the section in markdown only, without the \"{}\" heading itself.\n",
+                self.render(template, &context)?,
+                heading
+            );
+
+            let request = CompletionRequest::new(&prompt).system(&template.system_prompt);
+            let completion = self.providers.complete(provider.as_ref(), &request).await?;
+            usage = add_usage(usage, completion.usage);
+
+            let body = section_body(&completion.text, heading).map_err(|err| {
+                GenerationError::MalformedOutput {
+                    provider: provider.name().to_string(),
+                    reason: err.to_string(),
+                }
+            })?;
+            tracing::debug!("Section {}/{} written: {}", index + 1, outline.len(), heading);
+
+            sections.push(body);
+            prompts.push(prompt);
         }
 
-        let response = self
-            .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.openai_api_key))
-            .json(&json!({
-                "model": "gpt-4-turbo-preview",
-                "messages": [
-                    {
-                        "role": "system",
-                        "content": "You are an expert content creator specializing in AEO (Answer Engine Optimization). Create content that is optimized for AI platforms while remaining valuable for human readers."
-                    },
-                    {
-                        "role": "user",
-                        "content": prompt
-                    }
-                ],
-                "response_format": { "type": "json_object" },
-                "temperature": 0.7
-            }))
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return self.generate_mock_content(prompt);
+        let mut body = format!("# {}\n", title);
+        for (heading, section) in outline.iter().zip(&sections) {
+            body.push_str(&format!("\n## {}\n\n{}\n", heading, section));
         }
 
-        let data: serde_json::Value = response.json().await?;
-        let content_str = data["choices"][0]["message"]["content"]
-            .as_str()
-            .unwrap_or("{}");
-
-        let content: serde_json::Value = serde_json::from_str(content_str)?;
-
-        Ok((
-            content["title"].as_str().unwrap_or("Generated Content").to_string(),
-            content["body"].as_str().unwrap_or("").to_string(),
-            content["outline"]
-                .as_array()
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|v| v.as_str().map(String::from))
-                        .collect()
-                })
-                .unwrap_or_default(),
-        ))
+        let provenance = provenance(provider.as_ref(), template, prompts.join("\n---\n\n"));
+        Ok(Generation {
+            title: title.to_string(),
+            body,
+            outline: outline.to_vec(),
+            provenance: self.finish(provenance, started, usage),
+        })
     }
 
-    async fn generate_with_claude(&self, prompt: &str) -> Result<(String, String, Vec<String>)> {
-        if self.anthropic_api_key.is_empty() {
-            return self.generate_mock_content(prompt);
+    fn context<'a>(&'a self, brief: &ContentBrief<'a>) -> PromptContext<'a> {
+        PromptContext {
+            topic: brief.topic,
+            content_type: brief.content_type.id(),
+            platform: &brief.target_platform.name,
+            platform_id: &brief.target_platform.id,
+            tone: brief.tone.unwrap_or("professional"),
+            length: brief.length.unwrap_or("medium"),
+            keywords: brief.keywords.unwrap_or_default(),
+            guidance: brief.target_platform.guidance.trim(),
         }
+    }
+
+    fn render(
+        &self,
+        template: &PromptTemplate,
+        context: &impl Serialize,
+    ) -> Result<String, GenerationError> {
+        template
+            .render(context)
+            .map_err(|err| GenerationError::Template {
+                name: template.name.clone(),
+                version: template.version,
+                reason: err.to_string(),
+            })
+    }
+
+    pub async fn generate_outline(
+        &self,
+        brief: &ContentBrief<'_>,
+        template: &PromptTemplate,
+        provider: Option<&str>,
+    ) -> Result<OutlineGeneration, GenerationError> {
+        let prompt = format!(
+            "{}\nFormat the response as a JSON object with an \"outline\" array of strings.\n",
+            self.render(template, &self.context(brief))?
+        );
 
-        let response = self
-            .client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", &self.anthropic_api_key)
-            .header("anthropic-version", "2023-06-01")
-            .json(&json!({
-                "model": "claude-3-sonnet-20240229",
-                "max_tokens": 4096,
-                "messages": [
-                    {
-                        "role": "user",
-                        "content": prompt
-                    }
-                ]
-            }))
-            .send()
+        let provider = self.providers.select(provider, &brief.target_platform.id)?;
+        let (outline, provenance) = self
+            .complete_validated(provider.as_ref(), template, prompt, OUTLINE_SHAPE, parse_outline)
             .await?;
 
-        if !response.status().is_success() {
-            return self.generate_mock_content(prompt);
-        }
+        Ok(OutlineGeneration {
+            outline,
+            provenance,
+        })
+    }
 
-        let data: serde_json::Value = response.json().await?;
-        let content_str = data["content"][0]["text"]
-            .as_str()
-            .unwrap_or("{}");
-
-        let content: serde_json::Value = serde_json::from_str(content_str)?;
-
-        Ok((
-            content["title"].as_str().unwrap_or("Generated Content").to_string(),
-            content["body"].as_str().unwrap_or("").to_string(),
-            content["outline"]
-                .as_array()
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|v| v.as_str().map(String::from))
-                        .collect()
-                })
-                .unwrap_or_default(),
-        ))
+    async fn complete_article(
+        &self,
+        provider: &dyn LlmProvider,
+        template: &PromptTemplate,
+        prompt: String,
+    ) -> Result<Generation, GenerationError> {
+        let (article, provenance) = self
+            .complete_validated(
+                provider,
+                template,
+                prompt,
+                GeneratedArticle::SHAPE,
+                GeneratedArticle::parse,
+            )
+            .await?;
+
+        Ok(Generation {
+            title: article.title,
+            body: article.body,
+            outline: article.outline,
+            provenance,
+        })
     }
 
-    fn generate_mock_content(&self, _prompt: &str) -> Result<(String, String, Vec<String>)> {
-        // Mock content for testing when API keys are not available
-        Ok((
-            "AI-Generated Content: Understanding AEO".to_string(),
-            r#"# Understanding Answer Engine Optimization (AEO)
+    /// Ask for JSON and parse it; when the output fails `parse`, send it back
+    /// once with the problems listed and ask the model to correct it
+    async fn complete_validated<T>(
+        &self,
+        provider: &dyn LlmProvider,
+        template: &PromptTemplate,
+        prompt: String,
+        shape: &str,
+        parse: fn(&str) -> Result<T, ArticleError>,
+    ) -> Result<(T, Provenance), GenerationError> {
+        let started = Instant::now();
+        let provenance = provenance(provider, template, prompt);
+        let request = CompletionRequest::new(&provenance.prompt)
+            .system(&template.system_prompt)
+            .json();
+        let completion = self.providers.complete(provider, &request).await?;
+        let mut usage = completion.usage;
+
+        let err = match parse(&completion.text) {
+            Ok(parsed) => return Ok((parsed, self.finish(provenance, started, usage))),
+            Err(err) => err,
+        };
+        tracing::warn!(
+            "{} returned unusable output, requesting a repair: {}",
+            provider.name(),
+            err
+        );
 
-## What is AEO?
+        let repair = CompletionRequest::new(format!(
+            r#"Your previous response could not be used: {}
 
-Answer Engine Optimization (AEO) is the practice of optimizing content to appear in AI-powered answer engines like ChatGPT, Claude, Perplexity, Google Gemini, and Bing Chat.
+Previous response:
+{}
 
-## Key Principles
+Return the corrected response as JSON of the form {}, with no other text."#,
+            err, completion.text, shape
+        ))
+        .system(&template.system_prompt)
+        .json();
+        let completion = self.providers.complete(provider, &repair).await?;
+        usage = add_usage(usage, completion.usage);
+
+        let parsed = parse(&completion.text).map_err(|err| GenerationError::MalformedOutput {
+            provider: provider.name().to_string(),
+            reason: err.to_string(),
+        })?;
+        Ok((parsed, self.finish(provenance, started, usage)))
+    }
 
-### 1. Structured Content
-Content should be well-organized with clear headings and subheadings that guide both AI and human readers.
+    fn estimate_cost(&self, provenance: &Provenance, usage: Option<&TokenUsage>) -> Option<f64> {
+        if provenance.mock {
+            return Some(0.0);
+        }
+        self.providers.estimate_cost(&provenance.model, usage?)
+    }
 
-### 2. Direct Answers
-Provide concise, direct answers to common questions at the beginning of sections.
+}
 
-### 3. Citations and Sources
-Include authoritative sources and citations to build credibility with AI platforms.
+/// Provenance of a request about to be sent; usage and cost come later
+fn provenance(provider: &dyn LlmProvider, template: &PromptTemplate, prompt: String) -> Provenance {
+    Provenance {
+        provider: provider.name().to_string(),
+        model: provider.model().to_string(),
+        mock: provider.is_mock(),
+        system_prompt: template.system_prompt.clone(),
+        prompt,
+        template: Some(template.reference()),
+        usage: None,
+        latency: Duration::ZERO,
+        estimated_cost: None,
+    }
+}
 
-## Implementation Strategies
+/// Usage summed over several requests; None only if none reported any
+fn add_usage(total: Option<TokenUsage>, more: Option<TokenUsage>) -> Option<TokenUsage> {
+    match (total, more) {
+        (Some(mut total), Some(more)) => {
+            total += more;
+            Some(total)
+        }
+        (total, more) => total.or(more),
+    }
+}
 
-1. **Use Schema.org markup** for structured data
-2. **Create FAQ sections** for common queries
-3. **Optimize for featured snippets** with concise answers
-4. **Build topical authority** through comprehensive coverage
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{FixtureProvider, StreamDelta};
+    use crate::prompts::{ARTICLE, OUTLINE, SECTION};
+    use crate::testing::{llm_config, StubResponse, StubServer};
+    use asa_models::aeo::profile::PlatformRegistry;
+    use futures::StreamExt;
+    use serde_json::json;
+
+    fn generator(default_provider: &str, stub: Option<&StubServer>) -> AIGenerator {
+        let providers = LlmProviders::from_config(
+            &llm_config(default_provider, stub),
+            PlatformRegistry::builtin(),
+            "sk-test",
+            "sk-ant-test",
+        )
+        .unwrap();
+        AIGenerator::new(providers)
+    }
 
-## Platform-Specific Optimization
+    fn brief() -> ContentBrief<'static> {
+        ContentBrief {
+            topic: "Answer engine optimization",
+            content_type: ContentType::Article,
+            target_platform: PlatformRegistry::builtin().get("perplexity").unwrap(),
+            tone: None,
+            length: None,
+            keywords: None,
+        }
+    }
 
-Different AI platforms have different preferences:
-- ChatGPT prefers clear structure and examples
-- Claude values depth and nuance
-- Perplexity prioritizes citations
-- Gemini favors multimedia integration
+    fn template(name: &str) -> PromptTemplate {
+        PromptTemplate::builtin(name).unwrap()
+    }
 
-## Conclusion
+    #[tokio::test]
+    async fn test_generate_content_with_fixture() {
+        let generation = generator("fixture", None)
+            .generate_content(&brief(), &template(ARTICLE), None)
+            .await
+            .unwrap();
+
+        assert_eq!(generation.title, "AI-Generated Content: Understanding AEO");
+        assert_eq!(generation.outline.len(), 5);
+        assert!(generation.body.contains("## What is AEO?"));
+
+        let provenance = generation.provenance;
+        assert_eq!(provenance.provider, "fixture");
+        assert!(provenance.mock);
+        assert_eq!(provenance.estimated_cost, Some(0.0));
+        assert!(provenance.prompt.contains("Answer engine optimization"));
+        assert_eq!(provenance.template.unwrap().name, ARTICLE);
+    }
 
-AEO is becoming essential for content visibility in the age of AI. By following these principles, you can ensure your content is discovered and cited by AI platforms.
-"#.to_string(),
-            vec![
-                "What is AEO?".to_string(),
-                "Key Principles".to_string(),
-                "Implementation Strategies".to_string(),
-                "Platform-Specific Optimization".to_string(),
-                "Conclusion".to_string(),
-            ],
-        ))
+    #[tokio::test]
+    async fn test_generate_outline_with_fixture() {
+        let generation = generator("fixture", None)
+            .generate_outline(&brief(), &template(OUTLINE), None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            generation.outline.first().map(String::as_str),
+            Some("What is AEO?")
+        );
+        assert_eq!(generation.outline.len(), 5);
     }
 
-    fn get_platform_guidance(&self, platform: &str) -> &str {
-        match platform.to_lowercase().as_str() {
-            "chatgpt" => {
-                "ChatGPT Optimization:
-- Use clear structure with numbered lists and bullet points
-- Include concrete examples and code snippets
-- Provide step-by-step instructions
-- Use descriptive headings
-- Include practical use cases"
-            }
-            "claude" => {
-                "Claude Optimization:
-- Provide comprehensive, nuanced content
-- Include context and background information
-- Use well-structured arguments
-- Incorporate multiple perspectives
-- Focus on depth over brevity"
-            }
-            "perplexity" => {
-                "Perplexity Optimization:
-- Include authoritative citations and sources
-- Use data and statistics
-- Reference recent research
-- Provide verifiable facts
-- Link to credible external sources"
-            }
-            "gemini" => {
-                "Google Gemini Optimization:
-- Integrate multimedia elements
-- Include local and geographic relevance
-- Use conversational language
-- Provide visual descriptions
-- Incorporate multimodal content"
-            }
-            "bing" => {
-                "Bing Chat Optimization:
-- Use clear, concise language
-- Include recent, fresh content
-- Provide authoritative information
-- Use proper schema markup
-- Optimize for featured snippets"
+    #[tokio::test]
+    async fn test_generate_sections_with_fixture() {
+        let outline = vec!["Basics".to_string(), "Next steps".to_string()];
+        let generation = generator("fixture", None)
+            .generate_sections(&brief(), &template(SECTION), "AEO guide", &outline, None)
+            .await
+            .unwrap();
+
+        let doc = Document::parse_markdown(&generation.body);
+        let h2: Vec<_> = doc.headings_at(2).map(|(text, _)| text).collect();
+        assert_eq!(h2, vec!["Basics", "Next steps"]);
+        assert_eq!(doc.headings_at(1).count(), 1);
+        assert_eq!(generation.provenance.prompt.matches("\n---\n\n").count(), 1);
+
+        // Usage is summed over one request per section
+        let section = FixtureProvider::new()
+            .complete(&CompletionRequest::new("section"))
+            .await
+            .unwrap();
+        let usage = generation.provenance.usage.unwrap();
+        assert_eq!(
+            usage.completion_tokens,
+            2 * section.usage.unwrap().completion_tokens
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_content_with_fixture() {
+        let generator = generator("fixture", None);
+        let stream = generator
+            .stream_content(&brief(), &template(ARTICLE), None)
+            .await
+            .unwrap();
+
+        let mut body = String::new();
+        let mut tokens = stream.tokens;
+        while let Some(delta) = tokens.next().await {
+            if let StreamDelta::Text(text) = delta.unwrap() {
+                body.push_str(&text);
             }
-            _ => "General AEO best practices",
         }
+
+        let generation = Generation::from_markdown(body, stream.provenance);
+        assert_eq!(
+            generation.title,
+            "Understanding Answer Engine Optimization (AEO)"
+        );
+        assert_eq!(generation.outline.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_generate_content_over_http() {
+        let article = json!({
+            "title": "AEO",
+            "body": "# AEO\n\n## Why\n\nBecause.\n\n## How\n\nLike this.",
+            "outline": ["Why", "How"],
+        });
+        let stub = StubServer::start([StubResponse::json(
+            200,
+            json!({
+                "choices": [{
+                    "message": { "content": format!("Sure!\n```json\n{}\n```", article) },
+                    "finish_reason": "stop",
+                }],
+                "usage": { "prompt_tokens": 100, "completion_tokens": 50 },
+            }),
+        )])
+        .await;
+
+        let generation = generator("openai", Some(&stub))
+            .generate_content(&brief(), &template(ARTICLE), None)
+            .await
+            .unwrap();
+
+        assert_eq!(generation.title, "AEO");
+        assert_eq!(generation.outline, vec!["Why", "How"]);
+        let provenance = generation.provenance;
+        assert_eq!(
+            (provenance.provider.as_str(), provenance.model.as_str()),
+            ("openai", "gpt-test")
+        );
+        assert!(!provenance.mock);
+        // 100 prompt tokens at $1 and 50 completion tokens at $2 per million
+        assert_eq!(provenance.estimated_cost, Some(200.0 / 1_000_000.0));
+
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].body["messages"][1]["content"],
+            provenance.prompt
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generation_errors_come_back_typed() {
+        let stub = StubServer::start([StubResponse::text(401, "no")]).await;
+
+        let result = generator("anthropic", Some(&stub))
+            .generate_content(&brief(), &template(ARTICLE), None)
+            .await;
+        assert!(matches!(result, Err(GenerationError::Auth { .. })));
+    }
+
+    fn reply(text: &str, prompt_tokens: u32, completion_tokens: u32) -> StubResponse {
+        StubResponse::json(
+            200,
+            json!({
+                "choices": [{ "message": { "content": text }, "finish_reason": "stop" }],
+                "usage": { "prompt_tokens": prompt_tokens, "completion_tokens": completion_tokens },
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_complete_validated_repairs_once_and_sums_usage() {
+        let stub = StubServer::start([
+            reply("I can't do JSON today", 10, 20),
+            reply(r#"{"outline": ["Why", "How"]}"#, 30, 40),
+        ])
+        .await;
+        let generator = generator("openai", Some(&stub));
+        let provider = generator.providers.select_default(None).unwrap();
+
+        let (outline, provenance) = generator
+            .complete_validated(
+                provider.as_ref(),
+                &template(OUTLINE),
+                "Outline AEO".to_string(),
+                OUTLINE_SHAPE,
+                parse_outline,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(outline, vec!["Why", "How"]);
+        let usage = provenance.usage.unwrap();
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens), (40, 60));
+        // The job records the original prompt, not the repair
+        assert_eq!(provenance.prompt, "Outline AEO");
+
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 2);
+        let repair = requests[1].body["messages"][1]["content"].as_str().unwrap();
+        assert!(repair.contains("no JSON found in the response"));
+        assert!(repair.contains("I can't do JSON today"));
+        assert!(repair.contains(OUTLINE_SHAPE));
+    }
+
+    #[tokio::test]
+    async fn test_complete_validated_gives_up_after_one_repair() {
+        let stub = StubServer::start([
+            reply("nope", 1, 1),
+            reply(r#"{"outline": []}"#, 1, 1),
+            reply(r#"{"outline": ["Never sent"]}"#, 1, 1),
+        ])
+        .await;
+        let generator = generator("openai", Some(&stub));
+        let provider = generator.providers.select_default(None).unwrap();
+
+        let result = generator
+            .complete_validated(
+                provider.as_ref(),
+                &template(OUTLINE),
+                "Outline AEO".to_string(),
+                OUTLINE_SHAPE,
+                parse_outline,
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(GenerationError::MalformedOutput { .. })
+        ));
+        assert_eq!(stub.requests().len(), 2);
     }
 }
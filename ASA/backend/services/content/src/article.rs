@@ -0,0 +1,343 @@
+use serde::{Deserialize, Serialize};
+
+use crate::document::Document;
+
+/// Why model output could not be used as an article
+#[derive(Debug, thiserror::Error)]
+pub enum ArticleError {
+    #[error("no JSON found in the response")]
+    NoJson,
+    #[error("response does not match the expected JSON shape: {0}")]
+    Schema(#[from] serde_json::Error),
+    #[error("{}", .0.join("; "))]
+    Invalid(Vec<String>),
+}
+
+/// The JSON shape generation prompts ask for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedArticle {
+    pub title: String,
+    /// Markdown
+    pub body: String,
+    /// H2 headings of the body, in order
+    #[serde(default)]
+    pub outline: Vec<String>,
+}
+
+impl GeneratedArticle {
+    /// Shown to the model when asking it to repair its output
+    pub const SHAPE: &'static str =
+        r#"{"title": string, "body": markdown string, "outline": [the body's H2 headings, in order]}"#;
+
+    /// Extract, deserialize and validate an article from model output. A
+    /// missing outline is filled in from the body's H2 headings.
+    pub fn parse(text: &str) -> Result<Self, ArticleError> {
+        let json = extract_json(text).ok_or(ArticleError::NoJson)?;
+        let mut article: GeneratedArticle = serde_json::from_str(json)?;
+        article.title = article.title.trim().to_string();
+
+        let headings: Vec<String> = Document::parse_markdown(&article.body)
+            .headings_at(2)
+            .map(|(text, _)| text.to_string())
+            .collect();
+        if article.outline.is_empty() {
+            article.outline = headings.clone();
+        }
+
+        let mut problems = Vec::new();
+        if article.title.is_empty() {
+            problems.push("title is empty".to_string());
+        }
+        if article.body.trim().is_empty() {
+            problems.push("body is empty".to_string());
+        } else if headings.is_empty() {
+            problems.push("body has no H2 (##) section headings".to_string());
+        } else if !same_headings(&article.outline, &headings) {
+            problems.push(format!(
+                "outline {:?} does not match the body's H2 headings {:?}",
+                article.outline, headings
+            ));
+        }
+
+        if problems.is_empty() {
+            Ok(article)
+        } else {
+            Err(ArticleError::Invalid(problems))
+        }
+    }
+}
+
+/// Section headings from an outline response: a bare JSON array or an
+/// object with an `outline` array
+pub fn parse_outline(text: &str) -> Result<Vec<String>, ArticleError> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Outline {
+        List(Vec<String>),
+        Object { outline: Vec<String> },
+    }
+
+    let json = extract_json(text).ok_or(ArticleError::NoJson)?;
+    let outline = match serde_json::from_str(json)? {
+        Outline::List(outline) | Outline::Object { outline } => outline,
+    };
+    let outline: Vec<String> = outline
+        .into_iter()
+        .map(|section| section.trim().to_string())
+        .filter(|section| !section.is_empty())
+        .collect();
+
+    if outline.is_empty() {
+        return Err(ArticleError::Invalid(vec!["outline is empty".to_string()]));
+    }
+    Ok(outline)
+}
+
+/// The markdown of one section, ready to go under its `##` heading. An
+/// echoed heading at the start is dropped and any H1/H2 the model added is
+/// demoted to H3 so the assembled article keeps the outline's structure.
+pub fn section_body(text: &str, heading: &str) -> Result<String, ArticleError> {
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+
+    for line in text.trim().lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if in_fence || !(1..=2).contains(&level) || !trimmed[level..].starts_with(' ') {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let text = trimmed[level..].trim();
+        let echoed = lines.iter().all(|line: &String| line.trim().is_empty())
+            && same_headings(&[text.to_string()], &[heading.to_string()]);
+        if !echoed {
+            lines.push(format!("### {}", text));
+        }
+    }
+
+    let body = lines.join("\n").trim().to_string();
+    if body.is_empty() {
+        return Err(ArticleError::Invalid(vec![format!(
+            "section \"{}\" is empty",
+            heading
+        )]));
+    }
+    Ok(body)
+}
+
+pub const OUTLINE_SHAPE: &str = r#"{"outline": [string]}"#;
+
+/// The JSON document in model output, which may be wrapped in prose or
+/// code fences
+pub fn extract_json(text: &str) -> Option<&str> {
+    let is_json = |candidate: &str| serde_json::from_str::<serde::de::IgnoredAny>(candidate).is_ok();
+
+    let trimmed = text.trim();
+    if is_json(trimmed) {
+        return Some(trimmed);
+    }
+
+    // Fenced blocks, with or without a language tag
+    let mut rest = text;
+    while let Some(start) = rest.find("```") {
+        let after = &rest[start + 3..];
+        let Some(newline) = after.find('\n') else {
+            break;
+        };
+        let Some(end) = after[newline..].find("```") else {
+            break;
+        };
+        let candidate = after[newline..newline + end].trim();
+        if is_json(candidate) {
+            return Some(candidate);
+        }
+        rest = &after[newline + end + 3..];
+    }
+
+    // First balanced object or array embedded in prose
+    text.char_indices()
+        .filter(|(_, c)| *c == '{' || *c == '[')
+        .filter_map(|(start, _)| balanced(&text[start..]))
+        .find(|candidate| is_json(candidate))
+}
+
+/// The prefix of `text` up to the bracket closing its first character
+fn balanced(text: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(&text[..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Headings compared ignoring case, punctuation and list numbering
+fn same_headings(outline: &[String], headings: &[String]) -> bool {
+    fn normalize(heading: &str) -> String {
+        heading
+            .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.' || c.is_whitespace())
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    outline.len() == headings.len()
+        && outline
+            .iter()
+            .zip(headings)
+            .all(|(a, b)| normalize(a) == normalize(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r##"{"title": " AEO ", "body": "# AEO\n\n## Why it matters\n\nText.\n\n## How to start\n\nMore.", "outline": ["1. Why it matters", "How to start"]}"##;
+
+    #[test]
+    fn test_extract_json_bare_fenced_and_embedded() {
+        assert_eq!(extract_json("  {\"a\": 1}\n"), Some("{\"a\": 1}"));
+        assert_eq!(
+            extract_json("Here:\n```json\n[1, 2]\n```\nDone"),
+            Some("[1, 2]")
+        );
+        assert_eq!(extract_json("```\n{\"a\": 1}\n```"), Some("{\"a\": 1}"));
+        assert_eq!(
+            extract_json("Sure! {\"a\": \"} [\"} is the answer."),
+            Some("{\"a\": \"} [\"}")
+        );
+        // A fence that isn't JSON is skipped for the next candidate
+        assert_eq!(
+            extract_json("```md\n# Hi\n```\n{\"b\": 2}"),
+            Some("{\"b\": 2}")
+        );
+        assert_eq!(extract_json("no json {here"), None);
+    }
+
+    #[test]
+    fn test_balanced() {
+        assert_eq!(balanced("{\"a\": [1, {}]} tail"), Some("{\"a\": [1, {}]}"));
+        assert_eq!(balanced(r#"{"a": "\"}"} tail"#), Some(r#"{"a": "\"}"}"#));
+        assert_eq!(balanced("[[]"), None);
+        assert_eq!(balanced("}"), None);
+    }
+
+    #[test]
+    fn test_parse_article() {
+        let article = GeneratedArticle::parse(&format!("```json\n{}\n```", ARTICLE)).unwrap();
+        assert_eq!(article.title, "AEO");
+        assert_eq!(article.outline, vec!["1. Why it matters", "How to start"]);
+    }
+
+    #[test]
+    fn test_parse_article_fills_in_missing_outline() {
+        let article = GeneratedArticle::parse(
+            r###"{"title": "AEO", "body": "## One\n\nA.\n\n## Two\n\nB."}"###,
+        )
+        .unwrap();
+        assert_eq!(article.outline, vec!["One", "Two"]);
+    }
+
+    #[test]
+    fn test_parse_article_reports_problems() {
+        assert!(matches!(
+            GeneratedArticle::parse("just prose"),
+            Err(ArticleError::NoJson)
+        ));
+        assert!(matches!(
+            GeneratedArticle::parse(r#"{"title": 1}"#),
+            Err(ArticleError::Schema(_))
+        ));
+
+        let err = GeneratedArticle::parse(r#"{"title": "", "body": "No headings."}"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "title is empty; body has no H2 (##) section headings"
+        );
+
+        let err = GeneratedArticle::parse(
+            r###"{"title": "T", "body": "## One\n\nA.", "outline": ["One", "Two"]}"###,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("does not match the body's H2 headings"));
+    }
+
+    #[test]
+    fn test_parse_outline() {
+        assert_eq!(
+            parse_outline(r#"[" Intro ", "", "Steps"]"#).unwrap(),
+            vec!["Intro", "Steps"]
+        );
+        assert_eq!(
+            parse_outline("Outline:\n```json\n{\"outline\": [\"A\", \"B\"]}\n```").unwrap(),
+            vec!["A", "B"]
+        );
+        assert!(matches!(
+            parse_outline("[\" \"]"),
+            Err(ArticleError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse_outline(r#"{"sections": []}"#),
+            Err(ArticleError::Schema(_))
+        ));
+        assert!(matches!(parse_outline("none"), Err(ArticleError::NoJson)));
+    }
+
+    #[test]
+    fn test_same_headings() {
+        let outline = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(same_headings(
+            &outline(&["1. What is AEO?", "Next Steps"]),
+            &outline(&["What is AEO", "next steps"])
+        ));
+        assert!(!same_headings(
+            &outline(&["One"]),
+            &outline(&["One", "Two"])
+        ));
+        assert!(!same_headings(
+            &outline(&["One", "Two"]),
+            &outline(&["Two", "One"])
+        ));
+    }
+
+    #[test]
+    fn test_section_body() {
+        let body = section_body(
+            "## Basics\n\nText.\n\n# Aside\n\n```\n# not a heading\n```",
+            "Basics",
+        )
+        .unwrap();
+        assert_eq!(body, "Text.\n\n### Aside\n\n```\n# not a heading\n```");
+        assert!(section_body("## Basics\n", "Basics").is_err());
+    }
+}
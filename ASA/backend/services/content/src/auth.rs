@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::handlers::{AppError, AppState};
+
+/// The subset of the auth service's access token claims this service uses
+#[derive(Debug, Deserialize)]
+struct Claims {
+    sub: String,        // user id
+    token_type: String, // "access" or "refresh"
+}
+
+/// The user a request acts for, from a verified `Authorization: Bearer`
+/// access token. Handlers that take an `Actor` reject anonymous requests.
+#[derive(Debug, Clone, Copy)]
+pub struct Actor {
+    pub user_id: Uuid,
+}
+
+impl Actor {
+    pub fn from_token(token: &str, secret: &str) -> Result<Self, AppError> {
+        let claims = decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map_err(|err| AppError::Unauthorized(format!("Invalid token: {}", err)))?
+        .claims;
+
+        if claims.token_type != "access" {
+            return Err(AppError::Unauthorized(
+                "An access token is required".to_string(),
+            ));
+        }
+        let user_id = claims
+            .sub
+            .parse()
+            .map_err(|_| AppError::Unauthorized("Invalid token subject".to_string()))?;
+
+        Ok(Self { user_id })
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Actor {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, AppError> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string()))?;
+
+        Self::from_token(token.trim(), &state.config.jwt_secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    fn token(secret: &str, sub: &str, token_type: &str, exp: i64) -> String {
+        encode(
+            &Header::default(),
+            &json!({
+                "sub": sub,
+                "email": "editor@example.com",
+                "username": "editor",
+                "role": "editor",
+                "exp": exp,
+                "iat": 0,
+                "token_type": token_type,
+            }),
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_actor_from_access_token() {
+        let user_id = Uuid::new_v4();
+        let exp = chrono::Utc::now().timestamp() + 60;
+
+        let actor = Actor::from_token(
+            &token("s3cret", &user_id.to_string(), "access", exp),
+            "s3cret",
+        );
+        assert_eq!(actor.unwrap().user_id, user_id);
+    }
+
+    #[test]
+    fn test_actor_rejects_bad_tokens() {
+        let sub = Uuid::new_v4().to_string();
+        let exp = chrono::Utc::now().timestamp() + 60;
+
+        for token in [
+            token("other", &sub, "access", exp),
+            token("s3cret", &sub, "refresh", exp),
+            token("s3cret", &sub, "access", exp - 3600),
+            token("s3cret", "not-a-uuid", "access", exp),
+            "garbage".to_string(),
+        ] {
+            assert!(matches!(
+                Actor::from_token(&token, "s3cret"),
+                Err(AppError::Unauthorized(_))
+            ));
+        }
+    }
+}
@@ -1,4 +1,8 @@
+use anyhow::Context;
 use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::llm::ModelPrice;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
@@ -7,6 +11,42 @@ pub struct Config {
     pub redis: RedisConfig,
     pub openai_api_key: String,
     pub anthropic_api_key: String,
+    pub jwt_secret: String, // verifies the auth service's access tokens
+    pub platform_profiles: Option<String>, // extra/override platform profiles (TOML or JSON)
+    pub llm: LlmConfig,
+    pub schema: SchemaConfig,
+    pub scheduler: SchedulerConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LlmConfig {
+    pub default_provider: String,
+    pub platform_providers: HashMap<String, String>, // platform name or alias -> provider
+    pub openai_model: String,
+    pub anthropic_model: String,
+    pub openai_base_url: Option<String>, // proxies or stubs in front of the hosted APIs
+    pub anthropic_base_url: Option<String>,
+    pub compatible_base_url: Option<String>, // Ollama, vLLM, llama.cpp, ...
+    pub compatible_api_key: Option<String>,
+    pub compatible_model: String,
+    pub timeout_secs: u64,
+    pub max_retries: u32, // for rate limits, timeouts and 5xx
+    pub pricing: HashMap<String, ModelPrice>, // by model name, for generation job cost estimates
+}
+
+/// Site details referenced by generated schema.org markup
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchemaConfig {
+    pub site_url: String, // content pages live at {site_url}/{slug}
+    pub publisher_name: String,
+    pub publisher_logo: String,
+}
+
+/// Scheduled publish/unpublish polling
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchedulerConfig {
+    pub interval_secs: u64, // 0 turns the scheduler off on this replica
+    pub batch_size: i64,    // most transitions claimed per poll
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -26,6 +66,9 @@ pub struct RedisConfig {
 
 impl Config {
     pub fn from_env() -> anyhow::Result<Self> {
+        let site_url = std::env::var("SITE_URL")
+            .unwrap_or_else(|_| "http://localhost:3000".to_string());
+
         Ok(Self {
             server: ServerConfig {
                 address: std::env::var("CONTENT_ADDRESS")
@@ -43,6 +86,93 @@ impl Config {
                 .unwrap_or_default(),
             anthropic_api_key: std::env::var("ANTHROPIC_API_KEY")
                 .unwrap_or_default(),
+            jwt_secret: std::env::var("JWT_SECRET")
+                .context("JWT_SECRET must be set")?,
+            platform_profiles: std::env::var("PLATFORM_PROFILES").ok(),
+            llm: LlmConfig {
+                default_provider: std::env::var("LLM_DEFAULT_PROVIDER")
+                    .unwrap_or_else(|_| "openai".to_string()),
+                platform_providers: parse_platform_providers(
+                    &std::env::var("LLM_PLATFORM_PROVIDERS")
+                        .unwrap_or_else(|_| "claude=anthropic".to_string()),
+                )?,
+                openai_model: std::env::var("OPENAI_MODEL")
+                    .unwrap_or_else(|_| "gpt-4-turbo-preview".to_string()),
+                anthropic_model: std::env::var("ANTHROPIC_MODEL")
+                    .unwrap_or_else(|_| "claude-3-sonnet-20240229".to_string()),
+                openai_base_url: std::env::var("OPENAI_BASE_URL").ok(),
+                anthropic_base_url: std::env::var("ANTHROPIC_BASE_URL").ok(),
+                compatible_base_url: std::env::var("LLM_BASE_URL").ok(),
+                compatible_api_key: std::env::var("LLM_API_KEY").ok(),
+                compatible_model: std::env::var("LLM_MODEL")
+                    .unwrap_or_else(|_| "llama3".to_string()),
+                timeout_secs: std::env::var("LLM_TIMEOUT_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(120),
+                max_retries: std::env::var("LLM_MAX_RETRIES")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(2),
+                pricing: parse_pricing(
+                    &std::env::var("LLM_PRICING").unwrap_or_else(|_| {
+                        "gpt-4-turbo-preview=10/30,claude-3-sonnet-20240229=3/15".to_string()
+                    }),
+                )?,
+            },
+            schema: SchemaConfig {
+                publisher_name: std::env::var("SCHEMA_PUBLISHER_NAME")
+                    .unwrap_or_else(|_| "ASA Platform".to_string()),
+                publisher_logo: std::env::var("SCHEMA_PUBLISHER_LOGO")
+                    .unwrap_or_else(|_| format!("{}/logo.png", site_url.trim_end_matches('/'))),
+                site_url,
+            },
+            scheduler: SchedulerConfig {
+                interval_secs: std::env::var("SCHEDULER_INTERVAL_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(30),
+                batch_size: std::env::var("SCHEDULER_BATCH_SIZE")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(50),
+            },
         })
     }
 }
+
+/// Parse `platform=provider` pairs, e.g. "claude=anthropic,gemini=openai_compatible"
+fn parse_platform_providers(value: &str) -> anyhow::Result<HashMap<String, String>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (platform, provider) = pair
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("expected platform=provider, got '{}'", pair))?;
+            Ok((platform.trim().to_string(), provider.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Parse `model=prompt/completion` USD prices per million tokens,
+/// e.g. "gpt-4o=2.5/10,llama3=0/0"
+fn parse_pricing(value: &str) -> anyhow::Result<HashMap<String, ModelPrice>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let parsed = pair.split_once('=').and_then(|(model, prices)| {
+                let (prompt, completion) = prices.split_once('/')?;
+                let price = ModelPrice {
+                    prompt: prompt.trim().parse().ok()?,
+                    completion: completion.trim().parse().ok()?,
+                };
+                Some((model.trim().to_string(), price))
+            });
+            parsed.ok_or_else(|| anyhow::anyhow!("expected model=prompt/completion, got '{}'", pair))
+        })
+        .collect()
+}
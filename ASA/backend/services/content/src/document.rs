@@ -0,0 +1,773 @@
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag};
+use scraper::{ElementRef, Html, Selector};
+use std::ops::Range;
+
+/// Byte range into the original content body
+pub type Span = Range<usize>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    Markdown,
+    Html,
+}
+
+/// Block-level node of a parsed content body
+#[derive(Debug, Clone)]
+pub enum Block {
+    Heading {
+        level: u8,
+        text: String,
+        span: Span,
+    },
+    Paragraph {
+        text: String,
+        span: Span,
+    },
+    List {
+        ordered: bool,
+        items: Vec<String>,
+        span: Span,
+    },
+    Code {
+        language: Option<String>,
+        code: String,
+        span: Span,
+    },
+    Table {
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+        span: Span,
+    },
+    Quote {
+        text: String,
+        span: Span,
+    },
+}
+
+impl Block {
+    pub fn span(&self) -> &Span {
+        match self {
+            Block::Heading { span, .. }
+            | Block::Paragraph { span, .. }
+            | Block::List { span, .. }
+            | Block::Code { span, .. }
+            | Block::Table { span, .. }
+            | Block::Quote { span, .. } => span,
+        }
+    }
+
+    /// Human-readable text of the block (code blocks have none)
+    pub fn text(&self) -> String {
+        match self {
+            Block::Heading { text, .. }
+            | Block::Paragraph { text, .. }
+            | Block::Quote { text, .. } => text.clone(),
+            Block::List { items, .. } => items.join("\n"),
+            Block::Table { header, rows, .. } => std::iter::once(header)
+                .chain(rows.iter())
+                .map(|row| row.join(" "))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Block::Code { .. } => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Link {
+    /// Anchor text
+    pub text: String,
+    pub url: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    /// Alt text, empty when missing
+    pub alt: String,
+    pub src: String,
+    pub span: Span,
+}
+
+impl Link {
+    /// The anchor text and where it points, for evidence
+    pub fn describe(&self) -> String {
+        if self.text.is_empty() || self.text == self.url {
+            self.url.clone()
+        } else {
+            format!("{} ({})", self.text, self.url)
+        }
+    }
+}
+
+impl Image {
+    /// The alt text, or the source when there is none
+    pub fn describe(&self) -> &str {
+        if self.alt.is_empty() {
+            &self.src
+        } else {
+            &self.alt
+        }
+    }
+}
+
+/// Parsed document tree used by the AEO scoring functions.
+///
+/// Markdown spans are exact parser offsets. HTML spans are located by
+/// searching the element text in the source, so they point at the text
+/// rather than the surrounding tags.
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub format: SourceFormat,
+    pub blocks: Vec<Block>,
+    pub links: Vec<Link>,
+    pub images: Vec<Image>,
+}
+
+impl Document {
+    pub fn parse(body: &str) -> Self {
+        if looks_like_html(body) {
+            Self::parse_html(body)
+        } else {
+            Self::parse_markdown(body)
+        }
+    }
+
+    pub fn parse_markdown(body: &str) -> Self {
+        MarkdownBuilder::default().build(body)
+    }
+
+    pub fn parse_html(body: &str) -> Self {
+        HtmlBuilder::new(body).build()
+    }
+
+    pub fn headings(&self) -> impl Iterator<Item = (u8, &str, &Span)> {
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Heading { level, text, span } => Some((*level, text.as_str(), span)),
+            _ => None,
+        })
+    }
+
+    pub fn headings_at(&self, level: u8) -> impl Iterator<Item = (&str, &Span)> {
+        self.headings()
+            .filter(move |(l, _, _)| *l == level)
+            .map(|(_, text, span)| (text, span))
+    }
+
+    pub fn paragraphs(&self) -> impl Iterator<Item = (&str, &Span)> {
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Paragraph { text, span } => Some((text.as_str(), span)),
+            _ => None,
+        })
+    }
+
+    pub fn lists(&self, ordered: bool) -> impl Iterator<Item = (&[String], &Span)> {
+        self.blocks.iter().filter_map(move |block| match block {
+            Block::List { ordered: o, items, span } if *o == ordered => {
+                Some((items.as_slice(), span))
+            }
+            _ => None,
+        })
+    }
+
+    /// Code blocks with something in them, by language
+    pub fn code_blocks(&self) -> impl Iterator<Item = (Option<&str>, &Span)> {
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Code {
+                language,
+                code,
+                span,
+            } if !code.trim().is_empty() => Some((language.as_deref(), span)),
+            _ => None,
+        })
+    }
+
+    pub fn tables(&self) -> impl Iterator<Item = &Span> {
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Table { span, .. } => Some(span),
+            _ => None,
+        })
+    }
+
+    /// Links pointing at other sites, which is what answer engines treat as citations
+    pub fn external_links(&self) -> impl Iterator<Item = &Link> {
+        self.links
+            .iter()
+            .filter(|link| link.url.starts_with("http://") || link.url.starts_with("https://"))
+    }
+
+    /// All human-readable text, excluding code and markup
+    pub fn prose(&self) -> String {
+        self.blocks
+            .iter()
+            .map(Block::text)
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.blocks
+            .iter()
+            .map(|block| block.text().split_whitespace().count())
+            .sum()
+    }
+
+    /// First block whose text contains `term` as a whole word (case-insensitive)
+    pub fn find_term(&self, term: &str) -> Option<&Block> {
+        self.blocks
+            .iter()
+            .find(|block| contains_word(&block.text(), term))
+    }
+
+    pub fn mentions(&self, term: &str) -> bool {
+        self.find_term(term).is_some()
+    }
+}
+
+/// Case-insensitive whole-word (or whole-phrase) match
+pub fn contains_word(haystack: &str, term: &str) -> bool {
+    let haystack = haystack.to_lowercase();
+    let term = term.to_lowercase();
+
+    haystack.match_indices(&term).any(|(pos, matched)| {
+        let before = haystack[..pos].chars().next_back();
+        let after = haystack[pos + matched.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+fn looks_like_html(body: &str) -> bool {
+    let trimmed = body.trim_start();
+    let mut chars = trimmed.chars();
+    chars.next() == Some('<')
+        && chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '!')
+}
+
+fn heading_level(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Default)]
+struct MarkdownBuilder {
+    blocks: Vec<Block>,
+    links: Vec<Link>,
+    images: Vec<Image>,
+    text: String,
+    // Index into `blocks` of every open list, innermost last
+    open_lists: Vec<usize>,
+    quote_depth: usize,
+    quote_start: usize,
+    code: Option<(Option<String>, String)>,
+    link: Option<(String, usize, usize)>,
+    image: Option<(String, String, usize)>,
+    table: Option<(Vec<String>, Vec<Vec<String>>)>,
+    row: Vec<String>,
+}
+
+impl MarkdownBuilder {
+    fn build(mut self, body: &str) -> Document {
+        let options = Options::ENABLE_TABLES
+            | Options::ENABLE_FOOTNOTES
+            | Options::ENABLE_STRIKETHROUGH;
+
+        for (event, range) in Parser::new_ext(body, options).into_offset_iter() {
+            match event {
+                Event::Start(tag) => self.start(tag, range),
+                Event::End(tag) => self.end(tag, range),
+                Event::Text(text) | Event::Code(text) => self.push_text(&text),
+                Event::SoftBreak | Event::HardBreak => self.push_text(" "),
+                _ => {}
+            }
+        }
+
+        Document {
+            format: SourceFormat::Markdown,
+            blocks: self.blocks,
+            links: self.links,
+            images: self.images,
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if let Some((_, code)) = self.code.as_mut() {
+            code.push_str(text);
+        } else if let Some((_, alt, _)) = self.image.as_mut() {
+            alt.push_str(text);
+        } else {
+            self.text.push_str(text);
+        }
+    }
+
+    fn take_text(&mut self) -> String {
+        normalize(&std::mem::take(&mut self.text))
+    }
+
+    fn start(&mut self, tag: Tag, range: Span) {
+        match tag {
+            Tag::Heading(..) | Tag::Paragraph | Tag::Item | Tag::TableCell => {}
+            Tag::BlockQuote => {
+                if self.quote_depth == 0 {
+                    self.quote_start = range.start;
+                }
+                self.quote_depth += 1;
+            }
+            Tag::List(start) => {
+                // Text already collected belongs to the parent item
+                if let Some(&parent) = self.open_lists.last() {
+                    let text = self.take_text();
+                    if let Block::List { items, .. } = &mut self.blocks[parent] {
+                        if !text.is_empty() {
+                            items.push(text);
+                        }
+                    }
+                }
+                self.blocks.push(Block::List {
+                    ordered: start.is_some(),
+                    items: Vec::new(),
+                    span: range,
+                });
+                self.open_lists.push(self.blocks.len() - 1);
+            }
+            Tag::CodeBlock(kind) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split_whitespace()
+                        .next()
+                        .map(str::to_string),
+                    CodeBlockKind::Indented => None,
+                };
+                self.code = Some((language, String::new()));
+            }
+            Tag::Table(_) => self.table = Some((Vec::new(), Vec::new())),
+            Tag::TableHead | Tag::TableRow => self.row.clear(),
+            Tag::Link(_, url, _) => {
+                self.link = Some((url.to_string(), self.text.len(), range.start));
+            }
+            Tag::Image(_, src, _) => {
+                self.image = Some((src.to_string(), String::new(), range.start));
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: Tag, range: Span) {
+        match tag {
+            Tag::Heading(level, ..) => {
+                let text = self.take_text();
+                self.blocks.push(Block::Heading {
+                    level: heading_level(level),
+                    text,
+                    span: range,
+                });
+            }
+            Tag::Paragraph => {
+                // Paragraphs inside list items and quotes belong to those blocks
+                if self.open_lists.is_empty() && self.quote_depth == 0 {
+                    let text = self.take_text();
+                    if !text.is_empty() {
+                        self.blocks.push(Block::Paragraph { text, span: range });
+                    }
+                } else {
+                    self.text.push(' ');
+                }
+            }
+            Tag::Item => {
+                let text = self.take_text();
+                if let Some(&list) = self.open_lists.last() {
+                    if let Block::List { items, .. } = &mut self.blocks[list] {
+                        if !text.is_empty() {
+                            items.push(text);
+                        }
+                    }
+                }
+            }
+            Tag::List(_) => {
+                self.open_lists.pop();
+            }
+            Tag::BlockQuote => {
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+                if self.quote_depth == 0 {
+                    let text = self.take_text();
+                    self.blocks.push(Block::Quote {
+                        text,
+                        span: self.quote_start..range.end,
+                    });
+                }
+            }
+            Tag::CodeBlock(_) => {
+                if let Some((language, code)) = self.code.take() {
+                    self.blocks.push(Block::Code {
+                        language,
+                        code,
+                        span: range,
+                    });
+                }
+            }
+            Tag::TableCell => {
+                let text = self.take_text();
+                self.row.push(text);
+            }
+            Tag::TableHead => {
+                if let Some((header, _)) = self.table.as_mut() {
+                    *header = std::mem::take(&mut self.row);
+                }
+            }
+            Tag::TableRow => {
+                if let Some((_, rows)) = self.table.as_mut() {
+                    rows.push(std::mem::take(&mut self.row));
+                }
+            }
+            Tag::Table(_) => {
+                if let Some((header, rows)) = self.table.take() {
+                    self.blocks.push(Block::Table {
+                        header,
+                        rows,
+                        span: range,
+                    });
+                }
+            }
+            Tag::Link(..) => {
+                if let Some((url, text_start, start)) = self.link.take() {
+                    let text = normalize(self.text.get(text_start..).unwrap_or_default());
+                    self.links.push(Link {
+                        text,
+                        url,
+                        span: start..range.end,
+                    });
+                }
+            }
+            Tag::Image(..) => {
+                if let Some((src, alt, start)) = self.image.take() {
+                    self.images.push(Image {
+                        alt: normalize(&alt),
+                        src,
+                        span: start..range.end,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+struct HtmlBuilder<'a> {
+    source: &'a str,
+    cursor: usize,
+    blocks: Vec<Block>,
+}
+
+impl<'a> HtmlBuilder<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            cursor: 0,
+            blocks: Vec::new(),
+        }
+    }
+
+    fn build(mut self) -> Document {
+        let html = Html::parse_fragment(self.source);
+        self.walk(html.root_element());
+
+        let links = html
+            .select(&Selector::parse("a[href]").expect("valid selector"))
+            .map(|a| {
+                let text = element_text(a);
+                Link {
+                    span: self.locate_anywhere(&text),
+                    text,
+                    url: a.value().attr("href").unwrap_or_default().to_string(),
+                }
+            })
+            .collect();
+
+        let images = html
+            .select(&Selector::parse("img").expect("valid selector"))
+            .map(|img| {
+                let src = img.value().attr("src").unwrap_or_default().to_string();
+                Image {
+                    span: self.locate_anywhere(&src),
+                    alt: img.value().attr("alt").unwrap_or_default().to_string(),
+                    src,
+                }
+            })
+            .collect();
+
+        Document {
+            format: SourceFormat::Html,
+            blocks: self.blocks,
+            links,
+            images,
+        }
+    }
+
+    fn walk(&mut self, element: ElementRef) {
+        for child in element.children().filter_map(ElementRef::wrap) {
+            let name = child.value().name();
+            match name {
+                "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                    let text = element_text(child);
+                    let span = self.locate(&text);
+                    self.blocks.push(Block::Heading {
+                        level: name[1..].parse().unwrap_or(1),
+                        text,
+                        span,
+                    });
+                }
+                "p" => {
+                    let text = element_text(child);
+                    if !text.is_empty() {
+                        let span = self.locate(&text);
+                        self.blocks.push(Block::Paragraph { text, span });
+                    }
+                }
+                "ul" | "ol" => {
+                    let items: Vec<String> = child
+                        .children()
+                        .filter_map(ElementRef::wrap)
+                        .filter(|li| li.value().name() == "li")
+                        .map(element_text)
+                        .collect();
+                    let span = self.locate(items.first().map(String::as_str).unwrap_or_default());
+                    self.blocks.push(Block::List {
+                        ordered: name == "ol",
+                        items,
+                        span,
+                    });
+                }
+                "pre" => {
+                    let code: String = child.text().collect();
+                    let language = child
+                        .select(&Selector::parse("code").expect("valid selector"))
+                        .next()
+                        .and_then(|code| code.value().attr("class"))
+                        .and_then(|class| {
+                            class
+                                .split_whitespace()
+                                .find_map(|c| c.strip_prefix("language-"))
+                        })
+                        .map(str::to_string);
+                    let span = self.locate(code.lines().next().unwrap_or_default().trim());
+                    self.blocks.push(Block::Code {
+                        language,
+                        code,
+                        span,
+                    });
+                }
+                "table" => {
+                    let row_selector = Selector::parse("tr").expect("valid selector");
+                    let cell_selector = Selector::parse("th, td").expect("valid selector");
+                    let mut header = Vec::new();
+                    let mut rows = Vec::new();
+                    for tr in child.select(&row_selector) {
+                        let cells: Vec<String> =
+                            tr.select(&cell_selector).map(element_text).collect();
+                        let is_header = tr
+                            .children()
+                            .filter_map(ElementRef::wrap)
+                            .all(|cell| cell.value().name() == "th");
+                        if header.is_empty() && rows.is_empty() && is_header {
+                            header = cells;
+                        } else {
+                            rows.push(cells);
+                        }
+                    }
+                    let span = self.locate(header.first().map(String::as_str).unwrap_or_default());
+                    self.blocks.push(Block::Table { header, rows, span });
+                }
+                "blockquote" => {
+                    let text = element_text(child);
+                    let span = self.locate(&text);
+                    self.blocks.push(Block::Quote { text, span });
+                }
+                "script" | "style" | "template" => {}
+                _ => self.walk(child),
+            }
+        }
+    }
+
+    /// Find `text` at or after the cursor and advance past it
+    fn locate(&mut self, text: &str) -> Span {
+        let needle = text.split_whitespace().next().unwrap_or_default();
+        if needle.is_empty() {
+            return self.cursor..self.cursor;
+        }
+        match self.source[self.cursor..].find(needle) {
+            Some(offset) => {
+                let start = self.cursor + offset;
+                let mut end = (start + text.len()).min(self.source.len());
+                while !self.source.is_char_boundary(end) {
+                    end -= 1;
+                }
+                self.cursor = start + needle.len();
+                start..end
+            }
+            None => self.cursor..self.cursor,
+        }
+    }
+
+    fn locate_anywhere(&self, text: &str) -> Span {
+        match self.source.find(text).filter(|_| !text.is_empty()) {
+            Some(start) => start..start + text.len(),
+            None => 0..0,
+        }
+    }
+}
+
+fn element_text(element: ElementRef) -> String {
+    normalize(&element.text().collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_headings_and_paragraphs() {
+        let body = "# Title\n\nIntro with **bold** text.\n\n## Section\n\nBody.\n";
+        let doc = Document::parse(body);
+
+        assert_eq!(doc.format, SourceFormat::Markdown);
+        let headings: Vec<_> = doc
+            .headings()
+            .map(|(level, text, _)| (level, text))
+            .collect();
+        assert_eq!(headings, vec![(1, "Title"), (2, "Section")]);
+
+        let (text, span) = doc.paragraphs().next().unwrap();
+        assert_eq!(text, "Intro with bold text.");
+        assert_eq!(&body[span.clone()], "Intro with **bold** text.\n");
+        assert_eq!(doc.headings_at(2).count(), 1);
+    }
+
+    #[test]
+    fn test_markdown_lists_keep_nested_items_separate() {
+        let doc = Document::parse("- one\n- two\n  - nested\n\n1. first\n2. second\n");
+
+        let unordered: Vec<_> = doc.lists(false).map(|(items, _)| items.to_vec()).collect();
+        assert_eq!(
+            unordered,
+            vec![
+                vec!["one".to_string(), "two".to_string()],
+                vec!["nested".to_string()]
+            ]
+        );
+
+        let (ordered, _) = doc.lists(true).next().unwrap();
+        assert_eq!(ordered, ["first", "second"]);
+    }
+
+    #[test]
+    fn test_markdown_table() {
+        let doc = Document::parse("| Plan | Price |\n|------|-------|\n| Pro | $10 |\n");
+
+        assert_eq!(doc.tables().count(), 1);
+        match &doc.blocks[0] {
+            Block::Table { header, rows, .. } => {
+                assert_eq!(header, &["Plan", "Price"]);
+                assert_eq!(rows, &[vec!["Pro".to_string(), "$10".to_string()]]);
+            }
+            other => panic!("expected table, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_markdown_code_is_not_prose() {
+        let doc = Document::parse("Run it:\n\n```rust\nfn main() {}\n```\n\n```\n\n```\n");
+
+        let code: Vec<_> = doc.code_blocks().map(|(language, _)| language).collect();
+        assert_eq!(code, vec![Some("rust")]);
+        assert_eq!(doc.prose(), "Run it:");
+        assert_eq!(doc.word_count(), 2);
+    }
+
+    #[test]
+    fn test_markdown_links_and_images() {
+        let body =
+            "See [the docs](https://example.com/docs) and [home](/).\n\n![A chart](chart.png)\n";
+        let doc = Document::parse(body);
+
+        assert_eq!(doc.links.len(), 2);
+        assert_eq!(doc.links[0].text, "the docs");
+        assert_eq!(
+            &body[doc.links[0].span.clone()],
+            "[the docs](https://example.com/docs)"
+        );
+        assert_eq!(
+            doc.links[0].describe(),
+            "the docs (https://example.com/docs)"
+        );
+
+        let external: Vec<_> = doc.external_links().map(|link| link.url.as_str()).collect();
+        assert_eq!(external, vec!["https://example.com/docs"]);
+
+        assert_eq!(doc.images.len(), 1);
+        assert_eq!(doc.images[0].alt, "A chart");
+        assert_eq!(doc.images[0].src, "chart.png");
+        assert_eq!(&body[doc.images[0].span.clone()], "![A chart](chart.png)");
+    }
+
+    #[test]
+    fn test_markdown_quote() {
+        let doc = Document::parse("> Quoted\n> text\n");
+
+        match &doc.blocks[..] {
+            [Block::Quote { text, .. }] => assert_eq!(text, "Quoted text"),
+            other => panic!("expected one quote, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_html_document() {
+        let body = concat!(
+            "<h1>Guide</h1><p>Read <a href=\"https://example.com\">this</a>.</p>",
+            "<ul><li>one</li><li>two</li></ul>",
+            "<pre><code class=\"language-python\">print(1)</code></pre>",
+            "<table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>",
+            "<blockquote>Said</blockquote><img src=\"x.png\" alt=\"\">",
+            "<script>ignored()</script>",
+        );
+        let doc = Document::parse(body);
+
+        assert_eq!(doc.format, SourceFormat::Html);
+        let (_, text, span) = doc.headings().next().unwrap();
+        assert_eq!(text, "Guide");
+        assert_eq!(&body[span.clone()], "Guide");
+        assert_eq!(doc.paragraphs().next().unwrap().0, "Read this.");
+        assert_eq!(doc.lists(false).next().unwrap().0, ["one", "two"]);
+        assert_eq!(doc.code_blocks().next().unwrap().0, Some("python"));
+        assert_eq!(doc.tables().count(), 1);
+        assert!(doc
+            .blocks
+            .iter()
+            .any(|block| matches!(block, Block::Quote { text, .. } if text == "Said")));
+        assert!(!doc.mentions("ignored"));
+
+        assert_eq!(doc.links[0].url, "https://example.com");
+        assert_eq!(doc.links[0].text, "this");
+        assert_eq!(doc.images[0].describe(), "x.png");
+    }
+
+    #[test]
+    fn test_contains_word_matches_whole_words() {
+        assert!(contains_word("Rust is fast", "rust"));
+        assert!(contains_word("use the rule engine.", "Rule Engine"));
+        assert!(!contains_word("trusted", "rust"));
+        assert!(!looks_like_html("a < b"));
+        assert!(looks_like_html("  <!doctype html>"));
+    }
+}
@@ -0,0 +1,690 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use asa_models::aeo::profile::PlatformRegistry;
+use serde::{Deserialize, Serialize};
+
+use crate::article::extract_json;
+use crate::document::{Block, Document};
+use crate::llm::{CompletionRequest, GenerationError, LlmProvider, LlmProviders, TokenUsage};
+
+/// Most entities kept per content item, by salience
+const MAX_ENTITIES: usize = 50;
+
+/// Most co-occurrence relationships kept per content item, by weight
+const MAX_RELATIONSHIPS: usize = 100;
+
+/// Relationship recorded between entities mentioned in the same block
+pub const CO_OCCURS: &str = "co_occurs";
+
+/// Terms every deployment knows about besides the platforms:
+/// (name, schema.org type, aliases)
+const VOCABULARY: &[(&str, &str, &[&str])] = &[
+    ("Answer Engine Optimization", "DefinedTerm", &["AEO"]),
+    ("Search Engine Optimization", "DefinedTerm", &["SEO"]),
+    ("Generative Engine Optimization", "DefinedTerm", &["GEO"]),
+    ("Large Language Model", "DefinedTerm", &["LLM", "LLMs", "large language models"]),
+    ("Retrieval-Augmented Generation", "DefinedTerm", &["RAG"]),
+    ("Structured Data", "DefinedTerm", &[]),
+    ("Knowledge Graph", "DefinedTerm", &[]),
+    ("Featured Snippet", "DefinedTerm", &["featured snippets"]),
+    ("Schema.org", "DefinedTerm", &[]),
+    ("JSON-LD", "DefinedTerm", &[]),
+    ("OpenAI", "Organization", &[]),
+    ("Anthropic", "Organization", &[]),
+    ("Google", "Organization", &[]),
+    ("Microsoft", "Organization", &[]),
+];
+
+/// Words that start a capitalized run without naming anything
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "how", "if", "in", "is", "it",
+    "its", "my", "no", "not", "of", "on", "or", "our", "so", "that", "the", "their", "then",
+    "there", "these", "this", "those", "to", "we", "what", "when", "where", "which", "while",
+    "who", "why", "with", "you", "your", "yes", "step", "note", "tip", "example",
+];
+
+/// Suffixes that mark a capitalized run as an organization
+const ORGANIZATION_SUFFIXES: &[&str] = &[
+    "Inc", "Inc.", "Ltd", "Ltd.", "LLC", "Corp", "Corp.", "GmbH", "Company", "Foundation",
+    "University", "Institute", "Agency",
+];
+
+/// Honorifics that mark a capitalized run as a person
+const HONORIFICS: &[&str] = &["Dr.", "Dr", "Mr.", "Mr", "Ms.", "Ms", "Mrs.", "Mrs", "Prof.", "Prof"];
+
+/// How an entity was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractionSource {
+    /// A known name or alias
+    Dictionary,
+    /// A proper-noun phrase or acronym
+    Pattern,
+    /// Named by a language model
+    Llm,
+}
+
+impl ExtractionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dictionary => "dictionary",
+            Self::Pattern => "pattern",
+            Self::Llm => "llm",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractedEntity {
+    pub name: String,
+    /// schema.org type, "Thing" when unknown
+    pub entity_type: String,
+    pub mentions: u32,
+    /// 0-1, relative to the most prominent entity in the content
+    pub salience: f64,
+    pub source: ExtractionSource,
+}
+
+/// A relationship between two extracted entities, by name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractedRelationship {
+    pub from: String,
+    pub to: String,
+    pub relationship_type: String,
+    /// 0-1
+    pub weight: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct Extraction {
+    pub entities: Vec<ExtractedEntity>,
+    pub relationships: Vec<ExtractedRelationship>,
+}
+
+/// A known entity and the other names it goes by
+#[derive(Debug, Clone)]
+pub struct DictionaryEntry {
+    pub name: String,
+    pub entity_type: String,
+    pub aliases: Vec<String>,
+}
+
+/// Names the dictionary stage recognizes
+#[derive(Debug, Clone, Default)]
+pub struct EntityDictionary {
+    entries: Vec<DictionaryEntry>,
+}
+
+impl EntityDictionary {
+    /// The answer engines in the platform registry, with their aliases, and
+    /// the bundled AEO vocabulary
+    pub fn builtin(platforms: &PlatformRegistry) -> Self {
+        let platforms = platforms.profiles().iter().map(|profile| DictionaryEntry {
+            name: profile.name.clone(),
+            entity_type: "SoftwareApplication".to_string(),
+            // Vendor aliases such as "openai" are entities of their own
+            aliases: std::iter::once(&profile.id)
+                .chain(&profile.aliases)
+                .filter(|alias| !VOCABULARY.iter().any(|(name, _, _)| name.eq_ignore_ascii_case(alias)))
+                .cloned()
+                .collect(),
+        });
+        let vocabulary = VOCABULARY.iter().map(|(name, entity_type, aliases)| DictionaryEntry {
+            name: name.to_string(),
+            entity_type: entity_type.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+        });
+
+        Self {
+            entries: platforms.chain(vocabulary).collect(),
+        }
+    }
+
+    /// Add entries, e.g. entities already in the knowledge graph. Names the
+    /// dictionary already knows are skipped.
+    pub fn extend(mut self, entries: impl IntoIterator<Item = DictionaryEntry>) -> Self {
+        for entry in entries {
+            if self.lookup(&entry.name).is_none() {
+                self.entries.push(entry);
+            }
+        }
+        self
+    }
+
+    /// Whether a capitalized run names something the dictionary knows: a
+    /// name or alias, or known names run together ("Google Gemini",
+    /// "Perplexity AI")
+    fn knows(&self, run: &str) -> bool {
+        self.lookup(run).is_some()
+            || run
+                .split_whitespace()
+                .all(|word| word == "AI" || self.lookup(word).is_some())
+    }
+
+    /// The entry a name or alias belongs to
+    fn lookup(&self, name: &str) -> Option<&DictionaryEntry> {
+        self.entries.iter().find(|entry| {
+            entry.name.eq_ignore_ascii_case(name)
+                || entry.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+/// Dictionary and pattern based entity extraction
+pub struct EntityExtractor<'a> {
+    dictionary: &'a EntityDictionary,
+}
+
+/// Counts for one entity while extracting
+struct Found {
+    entity_type: String,
+    source: ExtractionSource,
+    mentions: u32,
+    prominence: u32,
+    /// Indexes of the blocks it appears in
+    blocks: HashSet<usize>,
+}
+
+impl<'a> EntityExtractor<'a> {
+    pub fn new(dictionary: &'a EntityDictionary) -> Self {
+        Self { dictionary }
+    }
+
+    /// Entities in a content item and how they co-occur. Mentions in the
+    /// title and headings make an entity more salient.
+    pub fn extract(&self, title: &str, doc: &Document) -> Extraction {
+        let blocks: Vec<(bool, String)> = doc
+            .blocks
+            .iter()
+            .map(|block| (matches!(block, Block::Heading { .. }), block.text()))
+            .filter(|(_, text)| !text.is_empty())
+            .collect();
+
+        let mut found: BTreeMap<String, Found> = BTreeMap::new();
+
+        for entry in &self.dictionary.entries {
+            let names: Vec<&str> = std::iter::once(entry.name.as_str())
+                .chain(entry.aliases.iter().map(String::as_str))
+                .collect();
+            let count = |text: &str| count_terms(text, &names);
+            record(&mut found, &entry.name, &entry.entity_type, ExtractionSource::Dictionary, title, &blocks, count);
+        }
+
+        for (name, entity_type) in patterns(&blocks) {
+            if self.dictionary.knows(&name)
+                || found.keys().any(|known| known.eq_ignore_ascii_case(&name))
+            {
+                continue;
+            }
+            let count = |text: &str| count_word(text, &name);
+            record(&mut found, &name, &entity_type, ExtractionSource::Pattern, title, &blocks, count);
+        }
+
+        let top = found.values().map(|f| f.mentions + f.prominence).max().unwrap_or(0);
+        let mut entities: Vec<(String, Found)> = found.into_iter().collect();
+        entities.sort_by(|(a_name, a), (b_name, b)| {
+            (b.mentions + b.prominence)
+                .cmp(&(a.mentions + a.prominence))
+                .then_with(|| a_name.cmp(b_name))
+        });
+        entities.truncate(MAX_ENTITIES);
+
+        let relationships = co_occurrences(&entities);
+        Extraction {
+            entities: entities
+                .into_iter()
+                .map(|(name, found)| ExtractedEntity {
+                    name,
+                    entity_type: found.entity_type,
+                    mentions: found.mentions,
+                    salience: round(f64::from(found.mentions + found.prominence) / f64::from(top)),
+                    source: found.source,
+                })
+                .collect(),
+            relationships,
+        }
+    }
+}
+
+/// Proper-noun phrases and acronyms in body text with a guessed type.
+/// Headings are skipped since title case capitalizes everything.
+fn patterns(blocks: &[(bool, String)]) -> Vec<(String, String)> {
+    let mut runs: HashMap<String, (String, u32, bool)> = HashMap::new();
+
+    for (_, text) in blocks.iter().filter(|(heading, _)| !heading) {
+        for line in text.lines() {
+            for (run, sentence_start) in capitalized_runs(line) {
+                let entry = runs
+                    .entry(run.to_lowercase())
+                    .or_insert_with(|| (run.clone(), 0, false));
+                entry.1 += 1;
+                entry.2 |= !sentence_start;
+            }
+        }
+    }
+
+    let mut patterns: Vec<(String, String)> = runs
+        .into_values()
+        .filter(|(run, count, mid_sentence)| {
+            let words = run.split_whitespace().count();
+            let acronym = words == 1 && is_acronym(run);
+            // A lone capitalized word is only a name when it shows up
+            // mid-sentence more than once
+            words >= 2 || (acronym && *count >= 2) || (*mid_sentence && *count >= 2)
+        })
+        .map(|(run, _, _)| guess_type(&run))
+        .collect();
+    patterns.sort();
+    patterns
+}
+
+/// Add an entity's counts across the title and blocks, if it appears at all
+fn record(
+    found: &mut BTreeMap<String, Found>,
+    name: &str,
+    entity_type: &str,
+    source: ExtractionSource,
+    title: &str,
+    blocks: &[(bool, String)],
+    count: impl Fn(&str) -> u32,
+) {
+    let in_title = count(title) > 0;
+    let mut mentions = 0;
+    let mut prominence = if in_title { 3 } else { 0 };
+    let mut seen = HashSet::new();
+    for (i, (heading, text)) in blocks.iter().enumerate() {
+        let n = count(text);
+        if n > 0 {
+            mentions += n;
+            seen.insert(i);
+            if *heading {
+                prominence += 1;
+            }
+        }
+    }
+    if mentions == 0 && !in_title {
+        return;
+    }
+
+    found.insert(
+        name.to_string(),
+        Found {
+            entity_type: entity_type.to_string(),
+            source,
+            mentions: mentions.max(1),
+            prominence,
+            blocks: seen,
+        },
+    );
+}
+
+/// Pairs of entities mentioned in the same block, weighted by how often
+/// they appear together relative to the rarer of the two
+fn co_occurrences(entities: &[(String, Found)]) -> Vec<ExtractedRelationship> {
+    let mut relationships = Vec::new();
+    for (i, (a_name, a)) in entities.iter().enumerate() {
+        for (b_name, b) in &entities[i + 1..] {
+            let shared = a.blocks.intersection(&b.blocks).count();
+            if shared == 0 {
+                continue;
+            }
+            let rarer = a.blocks.len().min(b.blocks.len());
+            let (from, to) = if a_name <= b_name { (a_name, b_name) } else { (b_name, a_name) };
+            relationships.push(ExtractedRelationship {
+                from: from.clone(),
+                to: to.clone(),
+                relationship_type: CO_OCCURS.to_string(),
+                weight: round(shared as f64 / rarer as f64),
+            });
+        }
+    }
+
+    relationships.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+    relationships.truncate(MAX_RELATIONSHIPS);
+    relationships
+}
+
+/// Runs of capitalized words in a line of text, and whether each starts a
+/// sentence. "of", "de" and "&" may join the words of a run; punctuation
+/// ends one, except the period of an honorific ("Dr. Jane Smith").
+fn capitalized_runs(line: &str) -> Vec<(String, bool)> {
+    let mut runs = Vec::new();
+    let mut run: Vec<&str> = Vec::new();
+    let mut run_start = false;
+    let mut sentence_start = true;
+
+    for raw in line.split_whitespace() {
+        let word = raw.trim_matches(|c: char| !c.is_alphanumeric() && c != '&' && c != '.' && c != '-');
+        let honorific = HONORIFICS.contains(&word);
+        let word = word.trim_end_matches('.');
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase)
+            && !STOPWORDS.contains(&word.to_lowercase().as_str());
+        let joiner = !run.is_empty() && matches!(word, "of" | "de" | "&");
+
+        if capitalized || joiner {
+            if run.is_empty() {
+                run_start = sentence_start;
+            }
+            run.push(word);
+        }
+
+        let ends_sentence = raw.ends_with(['.', '!', '?', ':']) && !honorific;
+        let breaks = ends_sentence
+            || raw.ends_with([',', ';', ')', '"'])
+            || !(capitalized || joiner);
+        if breaks && !run.is_empty() {
+            push_run(&mut runs, &mut run, run_start);
+        }
+        sentence_start = ends_sentence;
+    }
+    push_run(&mut runs, &mut run, run_start);
+    runs
+}
+
+fn push_run(runs: &mut Vec<(String, bool)>, run: &mut Vec<&str>, start: bool) {
+    while run.last().is_some_and(|word| matches!(*word, "of" | "de" | "&")) {
+        run.pop();
+    }
+    if !run.is_empty() {
+        runs.push((run.join(" "), start));
+    }
+    run.clear();
+}
+
+fn is_acronym(word: &str) -> bool {
+    (2..=6).contains(&word.len())
+        && word.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        && word.chars().any(|c| c.is_ascii_uppercase())
+}
+
+/// The run with any honorific removed, and its likely schema.org type
+fn guess_type(run: &str) -> (String, String) {
+    let words: Vec<&str> = run.split_whitespace().collect();
+    if words.len() >= 2 && HONORIFICS.contains(&words[0]) {
+        return (words[1..].join(" "), "Person".to_string());
+    }
+    if words.len() >= 2 && words.last().is_some_and(|last| ORGANIZATION_SUFFIXES.contains(last)) {
+        return (run.to_string(), "Organization".to_string());
+    }
+    (run.to_string(), "Thing".to_string())
+}
+
+/// Whole-word, case-insensitive occurrences of `term`
+fn count_word(haystack: &str, term: &str) -> u32 {
+    count_terms(haystack, &[term])
+}
+
+/// Whole-word, case-insensitive occurrences of any of `terms`, a longer
+/// term winning where matches overlap ("Google Gemini" over "Gemini")
+fn count_terms(haystack: &str, terms: &[&str]) -> u32 {
+    let haystack = haystack.to_lowercase();
+    let mut terms: Vec<String> = terms
+        .iter()
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+        .collect();
+    terms.sort_by_key(|term| std::cmp::Reverse(term.len()));
+
+    let mut matched: Vec<std::ops::Range<usize>> = Vec::new();
+    for term in &terms {
+        for (pos, _) in haystack.match_indices(term.as_str()) {
+            let range = pos..pos + term.len();
+            let before = haystack[..range.start].chars().next_back();
+            let after = haystack[range.end..].chars().next();
+            let whole_word =
+                !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric);
+            let overlaps = matched
+                .iter()
+                .any(|other| range.start < other.end && other.start < range.end);
+            if whole_word && !overlaps {
+                matched.push(range);
+            }
+        }
+    }
+    matched.len() as u32
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// The JSON shape the LLM extractor asks for
+#[derive(Debug, Deserialize)]
+struct LlmExtraction {
+    #[serde(default)]
+    entities: Vec<LlmEntity>,
+    #[serde(default)]
+    relationships: Vec<LlmRelationship>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmEntity {
+    name: String,
+    #[serde(rename = "type", default)]
+    entity_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmRelationship {
+    from: String,
+    to: String,
+    #[serde(rename = "type")]
+    relationship_type: String,
+}
+
+const LLM_SYSTEM_PROMPT: &str = "You extract named entities and the relationships between them from articles for a knowledge graph. Answer with JSON only.";
+
+/// Ask a language model for entities and typed relationships, and merge
+/// them into a dictionary/pattern extraction. Entities the text never
+/// mentions are dropped.
+pub async fn extract_with_llm(
+    providers: &LlmProviders,
+    provider: &dyn LlmProvider,
+    title: &str,
+    doc: &Document,
+    extraction: &mut Extraction,
+) -> Result<Option<TokenUsage>, GenerationError> {
+    let prompt = format!(
+        "List the people, organizations, products, places and key concepts in this article, \
+         with schema.org types, and the relationships the article states between them \
+         (e.g. \"develops\", \"competes_with\", \"part_of\") in snake_case.\n\n\
+         Respond with: {{\"entities\": [{{\"name\": string, \"type\": string}}], \
+         \"relationships\": [{{\"from\": name, \"to\": name, \"type\": string}}]}}\n\n\
+         # {}\n\n{}",
+        title,
+        doc.prose()
+    );
+    let request = CompletionRequest::new(prompt).system(LLM_SYSTEM_PROMPT).json();
+    let completion = providers.complete(provider, &request).await?;
+
+    let malformed = |reason: String| GenerationError::MalformedOutput {
+        provider: provider.name().to_string(),
+        reason,
+    };
+    let json = extract_json(&completion.text).ok_or_else(|| malformed("no JSON found in the response".to_string()))?;
+    let found: LlmExtraction = serde_json::from_str(json).map_err(|err| malformed(err.to_string()))?;
+
+    let texts: Vec<String> = std::iter::once(title.to_string())
+        .chain(doc.blocks.iter().map(Block::text))
+        .collect();
+    let top = extraction.entities.iter().map(|entity| entity.mentions).max().unwrap_or(1).max(1);
+
+    for entity in found.entities {
+        let name = entity.name.trim();
+        // Sentences passed off as names don't fit kg_entities.name
+        if name.is_empty() || name.len() > 255 {
+            continue;
+        }
+        let entity_type = entity.entity_type.filter(|t| !t.trim().is_empty());
+        if let Some(existing) = extraction
+            .entities
+            .iter_mut()
+            .find(|existing| existing.name.eq_ignore_ascii_case(name))
+        {
+            // The model knows better than a capitalization heuristic
+            if existing.entity_type == "Thing" {
+                if let Some(entity_type) = entity_type {
+                    existing.entity_type = entity_type;
+                }
+            }
+            continue;
+        }
+
+        let mentions: u32 = texts.iter().map(|text| count_word(text, name)).sum();
+        if mentions == 0 || extraction.entities.len() >= MAX_ENTITIES {
+            continue;
+        }
+        extraction.entities.push(ExtractedEntity {
+            name: name.to_string(),
+            entity_type: entity_type.unwrap_or_else(|| "Thing".to_string()),
+            mentions,
+            salience: round((f64::from(mentions) / f64::from(top)).min(1.0)),
+            source: ExtractionSource::Llm,
+        });
+    }
+
+    for relationship in found.relationships {
+        let known = |name: &str| {
+            extraction
+                .entities
+                .iter()
+                .find(|entity| entity.name.eq_ignore_ascii_case(name.trim()))
+                .map(|entity| entity.name.clone())
+        };
+        let (Some(from), Some(to)) = (known(&relationship.from), known(&relationship.to)) else {
+            continue;
+        };
+        let relationship_type = relationship.relationship_type.trim().to_lowercase().replace([' ', '-'], "_");
+        if from == to || relationship_type.is_empty() || relationship_type.len() > 100 {
+            continue;
+        }
+        let duplicate = extraction.relationships.iter().any(|existing| {
+            existing.from == from && existing.to == to && existing.relationship_type == relationship_type
+        });
+        if !duplicate {
+            extraction.relationships.push(ExtractedRelationship {
+                from,
+                to,
+                relationship_type,
+                weight: 1.0,
+            });
+        }
+    }
+
+    Ok(completion.usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r##"# Field notes
+
+Answer Engine Optimization, or AEO, shapes content for answer engines. Jane Smith runs AEO audits at the Mozilla Foundation.
+
+## Who is Dr. Alan Turing?
+
+Dr. Alan Turing asked whether machines think. The Mozilla Foundation funds research; jane smith agrees.
+
+Perplexity AI cites sources. Results vary. Results improve when Perplexity sees structure.
+"##;
+
+    fn extract() -> Extraction {
+        let dictionary = EntityDictionary::builtin(PlatformRegistry::builtin());
+        EntityExtractor::new(&dictionary).extract("Field notes", &Document::parse_markdown(ARTICLE))
+    }
+
+    fn entity<'a>(extraction: &'a Extraction, name: &str) -> &'a ExtractedEntity {
+        extraction
+            .entities
+            .iter()
+            .find(|entity| entity.name == name)
+            .unwrap_or_else(|| panic!("{} not extracted", name))
+    }
+
+    #[test]
+    fn test_multi_word_names() {
+        let extraction = extract();
+
+        let jane = entity(&extraction, "Jane Smith");
+        assert_eq!(
+            (jane.entity_type.as_str(), jane.source),
+            ("Thing", ExtractionSource::Pattern)
+        );
+        // The honorific is dropped and marks a person
+        let turing = entity(&extraction, "Alan Turing");
+        assert_eq!(turing.entity_type, "Person");
+        // Mentions in headings count, though names are only found in body text
+        assert_eq!(turing.mentions, 2);
+        let mozilla = entity(&extraction, "Mozilla Foundation");
+        assert_eq!(mozilla.entity_type, "Organization");
+
+        assert_eq!(
+            capitalized_runs("Ask Dr. Jane Smith of Acme Corp, then Bank of the West."),
+            vec![
+                ("Ask Dr Jane Smith of Acme Corp".to_string(), true),
+                ("Bank".to_string(), false),
+                ("West".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_mentions_merge() {
+        let extraction = extract();
+        let names: Vec<&str> = extraction
+            .entities
+            .iter()
+            .map(|entity| entity.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "Alan Turing",
+                "Answer Engine Optimization",
+                "Jane Smith",
+                "Mozilla Foundation",
+                "Perplexity",
+            ]
+        );
+
+        // Name and alias are one entity
+        let aeo = entity(&extraction, "Answer Engine Optimization");
+        assert_eq!(
+            (aeo.mentions, aeo.source),
+            (3, ExtractionSource::Dictionary)
+        );
+        // Repeats in another case add mentions rather than entities
+        assert_eq!(entity(&extraction, "Jane Smith").mentions, 2);
+        // A known name followed by "AI" isn't a new entity
+        assert_eq!(entity(&extraction, "Perplexity").mentions, 2);
+
+        assert_eq!(
+            count_terms("Google Gemini beats Gemini", &["Gemini", "Google Gemini"]),
+            2
+        );
+    }
+
+    #[test]
+    fn test_stopwords_do_not_start_names() {
+        assert_eq!(
+            capitalized_runs("The Acme Widget Company and What Comes Next"),
+            vec![
+                ("Acme Widget Company".to_string(), false),
+                ("Comes Next".to_string(), false),
+            ]
+        );
+        assert!(capitalized_runs("When This Is It, Note: You").is_empty());
+
+        // Capitalized only at the start of sentences, so not a name
+        let extraction = extract();
+        assert!(!extraction
+            .entities
+            .iter()
+            .any(|entity| entity.name == "Results"));
+        assert!(!extraction
+            .entities
+            .iter()
+            .any(|entity| entity.name == "The"));
+    }
+}
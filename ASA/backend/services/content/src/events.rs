@@ -0,0 +1,69 @@
+use asa_database::RedisClient;
+use asa_models::{ContentStatus, WorkflowAction};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Redis pub/sub channel content status changes are published on
+pub const CONTENT_EVENTS_CHANNEL: &str = "content.events";
+
+/// A content item moving between workflow statuses, by hand or on schedule
+#[derive(Debug, Serialize)]
+pub struct ContentEvent {
+    #[serde(rename = "type")]
+    pub event_type: &'static str,
+    pub content_id: Uuid,
+    pub slug: String,
+    pub action: WorkflowAction,
+    pub from_status: ContentStatus,
+    pub to_status: ContentStatus,
+    pub user_id: Option<Uuid>,
+    /// When the change was scheduled for, if the scheduler made it
+    pub scheduled_for: Option<DateTime<Utc>>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl ContentEvent {
+    pub fn status_changed(
+        content_id: Uuid,
+        slug: &str,
+        action: WorkflowAction,
+        from_status: ContentStatus,
+        to_status: ContentStatus,
+    ) -> Self {
+        Self {
+            event_type: "content.status_changed",
+            content_id,
+            slug: slug.to_string(),
+            action,
+            from_status,
+            to_status,
+            user_id: None,
+            scheduled_for: None,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    pub fn by(mut self, user_id: Option<Uuid>) -> Self {
+        self.user_id = user_id;
+        self
+    }
+
+    pub fn scheduled_for(mut self, at: DateTime<Utc>) -> Self {
+        self.scheduled_for = Some(at);
+        self
+    }
+}
+
+/// Publish an event for other services (search indexing, cache purges,
+/// notifications). Delivery is best effort: nothing is queued for
+/// subscribers that aren't listening.
+pub async fn emit(redis: &RedisClient, event: &ContentEvent) -> anyhow::Result<()> {
+    let payload = serde_json::to_string(event)?;
+    redis::cmd("PUBLISH")
+        .arg(CONTENT_EVENTS_CHANNEL)
+        .arg(payload)
+        .query_async::<_, i64>(&mut redis.connection().clone())
+        .await?;
+    Ok(())
+}
@@ -1,20 +1,61 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Json},
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json,
+    },
 };
+use futures::stream::{self, Stream, StreamExt};
+use std::convert::Infallible;
 use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::{
-    aeo_optimizer::AEOOptimizer,
-    ai_generator::AIGenerator,
+    aeo_optimizer::{AEOOptimizer, ScoreSignals},
+    ai_generator::{AIGenerator, ArticleStream, ContentBrief, Generation},
+    auth::Actor,
     config::Config,
+    document::Document,
+    events::{self, ContentEvent},
+    entities::{self, EntityDictionary, EntityExtractor, Extraction},
+    llm::{GenerationError, StreamDelta, TokenUsage},
+    markup,
     models::*,
-    repository::{Content, ContentRepository},
-    schema_generator::SchemaGenerator,
+    prompts::{self, PromptTemplate},
+    repository::{
+        ContentRepository, GenerationJob, GenerationJobRepository,
+        KnowledgeGraphRepository, OptimizationRepository, PromptTemplateRepository,
+        RevisionRecord, RevisionRepository, ScoreRepository, StrategyRecord,
+        StrategyRepository,
+    },
+    revisions,
+    schema_extractor::{self, SchemaExtractor},
 };
 use asa_database::{PostgresPool, RedisClient};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use asa_models::aeo::optimization::OptimizationLevel;
+use asa_models::aeo::platform::{OptimizationRule, PlatformStrategy};
+use asa_models::aeo::profile::PlatformId;
+use asa_models::aeo::schema::{SchemaGenerator, SchemaType};
+use asa_models::aeo::validation::{SchemaValidator, ValidationReport};
+use asa_models::{
+    Content, ContentStatus, ContentType, UnknownWorkflowAction, UserRole,
+    WorkflowAction,
+};
+
+/// Each section is its own provider call, so the outline length bounds the cost
+const MAX_OUTLINE_SECTIONS: usize = 20;
+
+/// Entities learned from earlier content that extraction also looks for
+const GRAPH_DICTIONARY_SIZE: i64 = 500;
+
+/// Entities from the content and from the graph around it that count
+/// towards entity coverage, each
+const COVERAGE_ENTITIES: i64 = 10;
+
+/// Hops a path query follows before giving up
+const MAX_PATH_DEPTH: usize = 6;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -36,6 +77,7 @@ pub async fn health_check() -> impl IntoResponse {
 // Create content
 pub async fn create_content(
     State(state): State<AppState>,
+    actor: Actor,
     Json(payload): Json<CreateContentRequest>,
 ) -> Result<Json<ContentResponse>, AppError> {
     let repo = ContentRepository::new(state.db_pool.clone());
@@ -50,48 +92,98 @@ pub async fn create_content(
         return Err(AppError::Conflict("Slug already exists".to_string()));
     }
 
-    let content = repo
-        .create(
-            &payload.title,
-            &slug,
-            &payload.body,
-            &payload.content_type,
-            payload.author_id,
-            payload.metadata,
-        )
-        .await?;
+    let mut content = Content::new(
+        payload.title,
+        slug,
+        payload.body,
+        payload.content_type,
+        actor.user_id,
+    );
+    content.metadata = payload.metadata.unwrap_or_default();
+    measure(&mut content);
 
-    tracing::info!("Content created: {} ({})", content.title, content.id);
+    let mut content = repo.create(&content).await?;
+    content.schema = refresh_schema_markup(&state, &repo, &content).await?;
+    refresh_entities(&state, &content).await?;
+    record_revision(
+        &state,
+        &repo,
+        &mut content,
+        RevisionEvent::Created,
+        None,
+        Some(actor.user_id),
+    )
+    .await?;
 
-    Ok(Json(content_to_response(content)))
-}
+    tracing::info!("Content created: {} ({}) by {}", content.title, content.id, actor.user_id);
 
-// List content
-#[derive(Deserialize)]
-pub struct ListQuery {
-    page: Option<i64>,
-    page_size: Option<i64>,
-    status: Option<String>,
+    Ok(Json(content_to_response(content)))
 }
 
+// List content, a page at a time. Follow `next_cursor` for the next page
+// with the same filters and sort.
 pub async fn list_content(
     State(state): State<AppState>,
-    Query(query): Query<ListQuery>,
+    Query(query): Query<ContentListQuery>,
 ) -> Result<Json<ContentListResponse>, AppError> {
     let repo = ContentRepository::new(state.db_pool.clone());
 
-    let page = query.page.unwrap_or(1);
-    let page_size = query.page_size.unwrap_or(20).min(100);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let (sort, order) = query.sort_order();
+    let fields = query
+        .fields
+        .as_deref()
+        .map(|fields| {
+            fields
+                .split(',')
+                .map(str::trim)
+                .filter(|field| !field.is_empty())
+                .map(|field| {
+                    if !CONTENT_FIELDS.contains(&field) {
+                        return Err(AppError::Validation(format!(
+                            "Unknown field '{}', expected some of: {}",
+                            field,
+                            CONTENT_FIELDS.join(", ")
+                        )));
+                    }
+                    Ok(field.to_string())
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+    let after = query
+        .cursor
+        .as_deref()
+        .map(|cursor| decode_cursor(cursor, sort, order))
+        .transpose()?;
 
-    let (items, total) = repo
-        .list(page, page_size, query.status.as_deref())
-        .await?;
+    let with_body = match &fields {
+        Some(fields) => fields.iter().any(|field| field == "body"),
+        None => true,
+    };
+    let page = repo.list(&query, after, limit, with_body).await?;
+
+    let next_cursor = match page.items.last() {
+        Some(last) if page.has_more => Some(encode_cursor(last, sort, order)?),
+        _ => None,
+    };
+    let items = page
+        .items
+        .into_iter()
+        .map(|content| {
+            let mut object: serde_json::Map<String, serde_json::Value> =
+                serde_json::from_value(serde_json::to_value(content_to_response(content))?)?;
+            if let Some(fields) = &fields {
+                object.retain(|key, _| key == "id" || fields.contains(key));
+            }
+            Ok(serde_json::Value::Object(object))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
 
     Ok(Json(ContentListResponse {
-        items: items.into_iter().map(content_to_response).collect(),
-        total,
-        page,
-        page_size,
+        items,
+        total: page.total,
+        next_cursor,
     }))
 }
 
@@ -114,22 +206,41 @@ pub async fn get_content(
 pub async fn update_content(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    actor: Actor,
     Json(payload): Json<UpdateContentRequest>,
 ) -> Result<Json<ContentResponse>, AppError> {
     let repo = ContentRepository::new(state.db_pool.clone());
 
-    let content = repo
-        .update(
-            id,
-            payload.title.as_deref(),
-            payload.slug.as_deref(),
-            payload.body.as_deref(),
-            payload.content_type.as_deref(),
-            payload.metadata,
-        )
+    let mut content = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
+    authorize_change(&repo, actor, &content, "edit").await?;
+    if let Some(title) = payload.title {
+        content.title = title;
+    }
+    if let Some(slug) = payload.slug {
+        content.slug = slug;
+    }
+    if let Some(body) = payload.body {
+        content.body = body;
+    }
+    if let Some(content_type) = payload.content_type {
+        content.content_type = content_type;
+    }
+    if let Some(metadata) = payload.metadata {
+        content.metadata = metadata;
+    }
+    measure(&mut content);
+
+    let (mut content, revision) = repo
+        .update(&content, RevisionEvent::Updated, None, Some(actor.user_id))
         .await?;
+    content.schema = refresh_schema_markup(&state, &repo, &content).await?;
+    refresh_entities(&state, &content).await?;
+    score_revision(&state, &repo, &mut content, revision).await?;
 
-    tracing::info!("Content updated: {}", content.id);
+    tracing::info!("Content updated: {} by {}", content.id, actor.user_id);
 
     Ok(Json(content_to_response(content)))
 }
@@ -138,12 +249,19 @@ pub async fn update_content(
 pub async fn delete_content(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    actor: Actor,
 ) -> Result<StatusCode, AppError> {
     let repo = ContentRepository::new(state.db_pool.clone());
 
+    let content = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
+    authorize_change(&repo, actor, &content, "delete").await?;
+
     repo.delete(id).await?;
 
-    tracing::info!("Content deleted: {}", id);
+    tracing::info!("Content deleted: {} by {}", id, actor.user_id);
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -153,30 +271,211 @@ pub async fn generate_content(
     State(state): State<AppState>,
     Json(payload): Json<GenerateContentRequest>,
 ) -> Result<Json<GenerateContentResponse>, AppError> {
-    let (title, body, outline) = state
+    let profile = payload.target_platform.profile();
+    let template = prompt_template(
+        &state,
+        prompts::ARTICLE,
+        payload.prompt_template_id,
+        payload.content_type,
+        &profile.id,
+    )
+    .await?;
+    let brief = ContentBrief::from((&payload, profile));
+    let generation = state
         .ai_generator
-        .generate_content(
-            &payload.topic,
-            &payload.content_type,
-            &payload.target_platform,
-            payload.tone.as_deref(),
-            payload.length.as_deref(),
-            payload.keywords.as_deref(),
+        .generate_content(&brief, &template, payload.provider.as_deref())
+        .await?;
+
+    tracing::info!(
+        "AI content generated for topic: {} ({}/{})",
+        payload.topic,
+        generation.provenance.provider,
+        generation.provenance.model
+    );
+
+    let job_id = record_generation(&state, "article", &brief, &generation).await?;
+
+    Ok(Json(generation_response(&brief, generation, Some(job_id))))
+}
+
+// Stream generated content as server-sent events: `token` events carry
+// markdown deltas, then a `done` event carries the finished article, or an
+// `error` event if the provider fails mid-stream
+pub async fn generate_stream(
+    State(state): State<AppState>,
+    Json(payload): Json<GenerateContentRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let profile = payload.target_platform.profile();
+    let template = prompt_template(
+        &state,
+        prompts::ARTICLE,
+        payload.prompt_template_id,
+        payload.content_type,
+        &profile.id,
+    )
+    .await?;
+    let article = state
+        .ai_generator
+        .stream_content(
+            &ContentBrief::from((&payload, profile)),
+            &template,
+            payload.provider.as_deref(),
         )
         .await?;
 
-    tracing::info!("AI content generated for topic: {}", payload.topic);
+    tracing::info!(
+        "Streaming AI content for topic: {} ({}/{})",
+        payload.topic,
+        article.provenance.provider,
+        article.provenance.model
+    );
+
+    let stream = GenerationStream {
+        article,
+        body: String::new(),
+        usage: None,
+        request: payload,
+        state,
+    };
+    let events = stream::unfold(Some(stream), |stream| async move {
+        let (event, stream) = next_generation_event(stream?).await;
+        Some((Ok(event), stream))
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// A streamed generation in progress
+struct GenerationStream {
+    article: ArticleStream,
+    body: String,
+    usage: Option<TokenUsage>,
+    request: GenerateContentRequest,
+    state: AppState,
+}
 
-    Ok(Json(GenerateContentResponse {
-        title,
+/// The next event to send, and the stream if there is more to come
+async fn next_generation_event(mut stream: GenerationStream) -> (Event, Option<GenerationStream>) {
+    loop {
+        match stream.article.tokens.next().await {
+            Some(Ok(StreamDelta::Text(delta))) => {
+                // Carriage returns cannot be sent in an SSE data field
+                let delta = delta.replace('\r', "");
+                stream.body.push_str(&delta);
+                return (Event::default().event("token").data(delta), Some(stream));
+            }
+            Some(Ok(StreamDelta::Usage(usage))) => {
+                *stream.usage.get_or_insert_with(TokenUsage::default) += usage;
+            }
+            Some(Err(err)) => {
+                tracing::error!("Content stream failed: {}", err);
+                let error = serde_json::json!({ "error": err.to_string() });
+                return (Event::default().event("error").data(error.to_string()), None);
+            }
+            None => break,
+        }
+    }
+
+    let GenerationStream {
+        article,
         body,
-        outline,
+        usage,
+        request,
+        state,
+    } = stream;
+    let provenance = state
+        .ai_generator
+        .finish(article.provenance, article.started, usage);
+    let generation = Generation::from_markdown(body, provenance);
+
+    // The article has already been sent, so a failed audit record is only logged
+    let brief = ContentBrief::from((&request, request.target_platform.profile()));
+    let job_id = record_generation(&state, "stream", &brief, &generation)
+        .await
+        .map_err(|err| tracing::error!("Failed to record generation job: {}", err))
+        .ok();
+
+    let response = generation_response(&brief, generation, job_id);
+    let data = serde_json::to_string(&response).unwrap_or_default();
+    (Event::default().event("done").data(data), None)
+}
+
+/// The template for a generation: the pinned version if one is given, else
+/// the most specific active template, else the bundled default
+async fn prompt_template(
+    state: &AppState,
+    name: &str,
+    pinned: Option<Uuid>,
+    content_type: ContentType,
+    platform_id: &str,
+) -> Result<PromptTemplate, AppError> {
+    let repo = PromptTemplateRepository::new(state.db_pool.clone());
+
+    if let Some(id) = pinned {
+        let template = repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Prompt template not found".to_string()))?;
+        if template.name != name {
+            return Err(AppError::Validation(format!(
+                "Prompt template {} is a '{}' template, expected '{}'",
+                id, template.name, name
+            )));
+        }
+        return Ok(template);
+    }
+
+    match repo.resolve(name, content_type, platform_id).await? {
+        Some(template) => Ok(template),
+        None => PromptTemplate::builtin(name)
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("no bundled '{}' template", name))),
+    }
+}
+
+async fn record_generation(
+    state: &AppState,
+    kind: &str,
+    brief: &ContentBrief<'_>,
+    generation: &Generation,
+) -> anyhow::Result<Uuid> {
+    GenerationJobRepository::new(state.db_pool.clone())
+        .record(
+            kind,
+            brief.topic,
+            brief.content_type,
+            &brief.target_platform.id,
+            &generation.provenance,
+            Some(&generation.title),
+            &generation.body,
+        )
+        .await
+}
+
+fn generation_response(
+    brief: &ContentBrief<'_>,
+    generation: Generation,
+    job_id: Option<Uuid>,
+) -> GenerateContentResponse {
+    let provenance = generation.provenance;
+    GenerateContentResponse {
+        job_id,
+        title: generation.title,
+        body: generation.body,
+        outline: generation.outline,
+        mock: provenance.mock,
         metadata: serde_json::json!({
-            "topic": payload.topic,
-            "platform": payload.target_platform,
+            "topic": brief.topic,
+            "platform": brief.target_platform.id,
+            "provider": provenance.provider,
+            "model": provenance.model,
+            "prompt_template": provenance.template,
+            "prompt_tokens": provenance.usage.map(|usage| usage.prompt_tokens),
+            "completion_tokens": provenance.usage.map(|usage| usage.completion_tokens),
+            "latency_ms": provenance.latency.as_millis() as u64,
+            "estimated_cost": provenance.estimated_cost,
             "generated_at": chrono::Utc::now(),
         }),
-    }))
+    }
 }
 
 // Generate outline
@@ -184,16 +483,341 @@ pub async fn generate_outline(
     State(state): State<AppState>,
     Json(payload): Json<GenerateOutlineRequest>,
 ) -> Result<Json<Vec<String>>, AppError> {
-    let outline = state
+    let profile = payload.target_platform.profile();
+    let template = prompt_template(
+        &state,
+        prompts::OUTLINE,
+        payload.prompt_template_id,
+        payload.content_type,
+        &profile.id,
+    )
+    .await?;
+    let generation = state
         .ai_generator
         .generate_outline(
+            &ContentBrief::from((&payload, profile)),
+            &template,
+            payload.provider.as_deref(),
+        )
+        .await?;
+
+    GenerationJobRepository::new(state.db_pool.clone())
+        .record(
+            "outline",
             &payload.topic,
-            &payload.content_type,
-            &payload.target_platform,
+            payload.content_type,
+            &profile.id,
+            &generation.provenance,
+            None,
+            &generation.outline.join("\n"),
+        )
+        .await?;
+
+    Ok(Json(generation.outline))
+}
+
+// Write an article section by section from a (usually user-edited) outline,
+// then score it and build FAQ/HowTo schema from its structure
+pub async fn generate_article(
+    State(state): State<AppState>,
+    Json(payload): Json<GenerateArticleRequest>,
+) -> Result<Json<GenerateArticleResponse>, AppError> {
+    let outline: Vec<String> = payload
+        .outline
+        .iter()
+        .map(|heading| heading.trim().to_string())
+        .filter(|heading| !heading.is_empty())
+        .collect();
+    if outline.is_empty() {
+        return Err(AppError::Validation("Outline must have at least one section".to_string()));
+    }
+    if outline.len() > MAX_OUTLINE_SECTIONS {
+        return Err(AppError::Validation(format!(
+            "Outline has {} sections, at most {} are supported",
+            outline.len(),
+            MAX_OUTLINE_SECTIONS
+        )));
+    }
+
+    let profile = payload.target_platform.profile();
+    let template = prompt_template(
+        &state,
+        prompts::SECTION,
+        payload.prompt_template_id,
+        payload.content_type,
+        &profile.id,
+    )
+    .await?;
+
+    let brief = ContentBrief::from((&payload, profile));
+    let title = payload.title.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let generation = state
+        .ai_generator
+        .generate_sections(
+            &brief,
+            &template,
+            title.unwrap_or(&payload.topic),
+            &outline,
+            payload.provider.as_deref(),
+        )
+        .await?;
+
+    tracing::info!(
+        "AI article generated for topic: {} ({} sections, {}/{})",
+        payload.topic,
+        outline.len(),
+        generation.provenance.provider,
+        generation.provenance.model
+    );
+
+    let doc = Document::parse_markdown(&generation.body);
+    let score = state.aeo_optimizer.score_breakdown(&doc, profile);
+
+    // Structured data for what the article turned out to contain
+    let schema = SchemaExtractor::new(state.config.schema.clone()).structure(
+        &generation.title,
+        &schema_extractor::describe(&doc),
+        &generation.body,
+        schema_extractor::is_howto(payload.content_type, &generation.title),
+    );
+
+    let job_id = record_generation(&state, "sections", &brief, &generation).await?;
+
+    Ok(Json(GenerateArticleResponse {
+        article: generation_response(&brief, generation, Some(job_id)),
+        score,
+        schema,
+    }))
+}
+
+// Generation jobs
+#[derive(Deserialize)]
+pub struct GenerationJobQuery {
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+    limit: Option<i64>,
+}
+
+pub async fn list_generation_jobs(
+    State(state): State<AppState>,
+    Query(query): Query<GenerationJobQuery>,
+) -> Result<Json<Vec<GenerationJobResponse>>, AppError> {
+    let jobs = GenerationJobRepository::new(state.db_pool.clone())
+        .list(query.from, query.to, query.limit.unwrap_or(50).clamp(1, 500))
+        .await?;
+
+    Ok(Json(jobs.into_iter().map(job_to_response).collect()))
+}
+
+pub async fn get_generation_job(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<GenerationJobResponse>, AppError> {
+    let job = GenerationJobRepository::new(state.db_pool.clone())
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Generation job not found".to_string()))?;
+
+    Ok(Json(job_to_response(job)))
+}
+
+// Turn a generated article into draft content linked to its job
+pub async fn create_draft_from_job(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    payload: Option<Json<CreateDraftRequest>>,
+) -> Result<Json<ContentResponse>, AppError> {
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+    let job = GenerationJobRepository::new(state.db_pool.clone())
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Generation job not found".to_string()))?;
+
+    if job.kind == "outline" {
+        return Err(AppError::Validation(
+            "Outline jobs have no article to draft".to_string(),
+        ));
+    }
+    if let Some(content_id) = job.content_id {
+        return Err(AppError::Conflict(format!(
+            "Job already drafted as content {}",
+            content_id
+        )));
+    }
+
+    let repo = ContentRepository::new(state.db_pool.clone());
+    let slug = payload.slug.unwrap_or_else(|| {
+        slug::slugify(job.title.as_deref().unwrap_or(&job.topic))
+    });
+    if repo.find_by_slug(&slug).await?.is_some() {
+        return Err(AppError::Conflict("Slug already exists".to_string()));
+    }
+
+    let mut content = Content::new(
+        job.title.as_deref().unwrap_or(&job.topic),
+        slug,
+        job.output.as_str(),
+        job.content_type,
+        actor.user_id,
+    );
+    content.generation_job_id = Some(job.id);
+    measure(&mut content);
+
+    let mut content = repo.create(&content).await?;
+    content.schema = refresh_schema_markup(&state, &repo, &content).await?;
+    refresh_entities(&state, &content).await?;
+    record_revision(
+        &state,
+        &repo,
+        &mut content,
+        RevisionEvent::Created,
+        None,
+        Some(actor.user_id),
+    )
+    .await?;
+
+    tracing::info!(
+        "Content {} drafted from generation job {} by {}",
+        content.id,
+        job.id,
+        actor.user_id
+    );
+
+    Ok(Json(content_to_response(content)))
+}
+
+// Prompt templates
+#[derive(Deserialize)]
+pub struct PromptTemplateQuery {
+    name: Option<String>,
+}
+
+// Stored versions, followed by the bundled defaults
+pub async fn list_prompt_templates(
+    State(state): State<AppState>,
+    Query(query): Query<PromptTemplateQuery>,
+) -> Result<Json<Vec<PromptTemplateResponse>>, AppError> {
+    let stored = PromptTemplateRepository::new(state.db_pool.clone())
+        .list(query.name.as_deref())
+        .await?;
+    let builtin = prompts::TEMPLATE_NAMES
+        .into_iter()
+        .filter(|name| query.name.as_deref().is_none_or(|wanted| wanted == *name))
+        .filter_map(PromptTemplate::builtin);
+
+    Ok(Json(
+        stored.into_iter().chain(builtin).map(template_to_response).collect(),
+    ))
+}
+
+// Store a new version, optionally making it the active one
+pub async fn create_prompt_template(
+    State(state): State<AppState>,
+    Json(payload): Json<CreatePromptTemplateRequest>,
+) -> Result<Json<PromptTemplateResponse>, AppError> {
+    let builtin = PromptTemplate::builtin(&payload.name).ok_or_else(|| {
+        AppError::Validation(format!(
+            "Unknown template '{}', expected one of: {}",
+            payload.name,
+            prompts::TEMPLATE_NAMES.join(", ")
+        ))
+    })?;
+    PromptTemplate::validate(&payload.name, &payload.template)
+        .map_err(|err| AppError::Validation(format!("Invalid template: {}", err)))?;
+
+    let repo = PromptTemplateRepository::new(state.db_pool.clone());
+    let mut template = repo
+        .create(
+            &payload.name,
+            payload.content_type,
+            payload.platform.as_ref().map(PlatformId::as_str),
+            payload.description.as_deref(),
+            payload.system_prompt.as_deref().unwrap_or(&builtin.system_prompt),
+            &payload.template,
         )
         .await?;
 
-    Ok(Json(outline))
+    if payload.activate.unwrap_or(false) {
+        template = repo.activate(template.id.unwrap_or_default()).await?;
+    }
+
+    tracing::info!("Prompt template {} v{} created", template.name, template.version);
+
+    Ok(Json(template_to_response(template)))
+}
+
+pub async fn get_prompt_template(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PromptTemplateResponse>, AppError> {
+    let template = PromptTemplateRepository::new(state.db_pool.clone())
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Prompt template not found".to_string()))?;
+
+    Ok(Json(template_to_response(template)))
+}
+
+pub async fn activate_prompt_template(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PromptTemplateResponse>, AppError> {
+    let repo = PromptTemplateRepository::new(state.db_pool.clone());
+    if repo.find_by_id(id).await?.is_none() {
+        return Err(AppError::NotFound("Prompt template not found".to_string()));
+    }
+
+    let template = repo.activate(id).await?;
+    tracing::info!("Prompt template {} v{} activated", template.name, template.version);
+
+    Ok(Json(template_to_response(template)))
+}
+
+pub async fn deactivate_prompt_template(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PromptTemplateResponse>, AppError> {
+    let repo = PromptTemplateRepository::new(state.db_pool.clone());
+    if repo.find_by_id(id).await?.is_none() {
+        return Err(AppError::NotFound("Prompt template not found".to_string()));
+    }
+
+    let template = repo.deactivate(id).await?;
+    tracing::info!("Prompt template {} v{} deactivated", template.name, template.version);
+
+    Ok(Json(template_to_response(template)))
+}
+
+// Estimated generation spend per month and model
+#[derive(Deserialize)]
+pub struct GenerationCostQuery {
+    months: Option<i32>,
+}
+
+pub async fn get_generation_costs(
+    State(state): State<AppState>,
+    Query(query): Query<GenerationCostQuery>,
+) -> Result<Json<Vec<MonthlyCostResponse>>, AppError> {
+    let costs = GenerationJobRepository::new(state.db_pool.clone())
+        .monthly_costs(query.months.unwrap_or(12).clamp(1, 120))
+        .await?;
+
+    Ok(Json(
+        costs
+            .into_iter()
+            .map(|cost| MonthlyCostResponse {
+                month: cost.month,
+                provider: cost.provider,
+                model: cost.model,
+                jobs: cost.jobs,
+                prompt_tokens: cost.prompt_tokens,
+                completion_tokens: cost.completion_tokens,
+                estimated_cost: cost.estimated_cost,
+                unpriced_jobs: cost.unpriced_jobs,
+            })
+            .collect(),
+    ))
 }
 
 // Optimize content for AEO
@@ -209,23 +833,56 @@ pub async fn optimize_content(
         .await?
         .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
 
+    // The tenant's strategy for this platform, falling back to the global one
+    let strategies = StrategyRepository::new(state.db_pool.clone())
+        .effective(payload.tenant_id)
+        .await?;
+    let profile = payload.target_platform.profile();
+    let strategy = strategies.iter().find(|s| s.platform == profile.id);
+
     let optimization = state
         .aeo_optimizer
-        .optimize_content(&content.body, &payload.target_platform);
+        .optimize_content(&content.body, profile, strategy)
+        .map_err(anyhow::Error::from)?;
+
+    // Keep an audit trail of which deterministic rules were applied
+    let applied_rules: Vec<String> = optimization
+        .applied_rules
+        .iter()
+        .map(|rule| rule.name.clone())
+        .collect();
+    OptimizationRepository::new(state.db_pool.clone())
+        .record(
+            id,
+            &profile.id,
+            OptimizationLevel::Basic,
+            optimization.score,
+            optimization.optimized_score,
+            &applied_rules,
+            &optimization.improvements,
+        )
+        .await?;
 
     tracing::info!(
-        "Content optimized: {} for platform: {}",
+        "Content optimized: {} for platform: {} ({} rules applied)",
         id,
-        payload.target_platform
+        profile.name,
+        applied_rules.len()
     );
 
     Ok(Json(optimization))
 }
 
 // Get optimization score
+#[derive(Deserialize)]
+pub struct OptimizationScoreQuery {
+    tenant_id: Option<Uuid>,
+}
+
 pub async fn get_optimization_score(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(query): Query<OptimizationScoreQuery>,
 ) -> Result<Json<OptimizationScoreResponse>, AppError> {
     let repo = ContentRepository::new(state.db_pool.clone());
 
@@ -234,47 +891,219 @@ pub async fn get_optimization_score(
         .await?
         .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
 
-    // Calculate scores for all platforms
-    use asa_models::aeo::platform::AIPlatform;
-    let platforms = [
-        AIPlatform::ChatGPT,
-        AIPlatform::Claude,
-        AIPlatform::Perplexity,
-        AIPlatform::Gemini,
-        AIPlatform::Bing,
-    ];
+    // Scores are recorded with each revision; reading one changes nothing
+    Ok(Json(score_content(&state, &repo, &content, query.tenant_id).await?))
+}
 
-    let mut platform_scores = serde_json::Map::new();
-    let mut total_score = 0.0;
+/// Platform breakdowns, the strategy-weighted overall score and the
+/// seven-component AEO score for content as it is now
+async fn score_content(
+    state: &AppState,
+    repo: &ContentRepository,
+    content: &Content,
+    tenant_id: Option<Uuid>,
+) -> anyhow::Result<OptimizationScoreResponse> {
+    let id = content.id;
 
-    for platform in &platforms {
-        let score = state
-            .aeo_optimizer
-            .calculate_score(&content.body, platform);
-        platform_scores.insert(
-            format!("{:?}", platform).to_lowercase(),
-            serde_json::json!(score),
-        );
-        total_score += score;
+    // Calculate scores for every configured platform
+    let profiles = state.aeo_optimizer.platforms().profiles();
+
+    let doc = Document::parse(&content.body);
+    let breakdowns: Vec<ScoreBreakdown> = profiles
+        .iter()
+        .map(|profile| state.aeo_optimizer.score_breakdown(&doc, profile))
+        .collect();
+
+    let mut platform_scores = serde_json::Map::new();
+    for (profile, breakdown) in profiles.iter().zip(&breakdowns) {
+        platform_scores.insert(profile.id.clone(), serde_json::json!(breakdown.score));
     }
 
-    let overall_score = total_score / platforms.len() as f64;
+    // Strategy priorities decide how much each platform counts
+    let strategies = StrategyRepository::new(state.db_pool.clone())
+        .effective(tenant_id)
+        .await?;
+    let scores: Vec<_> = profiles
+        .iter()
+        .map(|profile| profile.id.as_str())
+        .zip(breakdowns.iter().map(|b| b.score))
+        .collect();
+    let overall_score = state.aeo_optimizer.overall_score(&scores, &strategies);
+
+    let (strengths, weaknesses) = state.aeo_optimizer.summarize(&breakdowns);
+
+    // Seven-component AEO score
+    let keywords = &content.metadata.keywords;
+    // Entities named in metadata, the ones the content is most about, and
+    // the ones the knowledge graph relates to those that it doesn't mention
+    let kg = KnowledgeGraphRepository::new(state.db_pool.clone());
+    let mut entities = content.metadata.entities.clone();
+    let salient = kg.salient_names(id, COVERAGE_ENTITIES).await?;
+    let related = kg.related_names(id, COVERAGE_ENTITIES).await?;
+    for name in salient.into_iter().chain(related) {
+        if !entities.iter().any(|entity| entity.eq_ignore_ascii_case(&name)) {
+            entities.push(name);
+        }
+    }
+    let schema_types = repo.schema_types(id).await?;
+    let aeo_score = state.aeo_optimizer.calculate_aeo_score(
+        &doc,
+        &breakdowns,
+        &ScoreSignals {
+            title: &content.title,
+            keywords,
+            entities: &entities,
+            schema_types: &schema_types,
+            updated_at: content.updated_at,
+        },
+    );
 
-    Ok(Json(OptimizationScoreResponse {
+    Ok(OptimizationScoreResponse {
         overall_score,
         platform_scores: serde_json::Value::Object(platform_scores),
-        strengths: vec![
-            "Well-structured content".to_string(),
-            "Good readability".to_string(),
-        ],
-        weaknesses: vec![
-            "Could use more examples".to_string(),
-            "Add more citations".to_string(),
-        ],
+        aeo_score,
+        breakdowns,
+        strengths,
+        weaknesses,
+    })
+}
+
+// Get AEO score history
+#[derive(Deserialize)]
+pub struct ScoreHistoryQuery {
+    limit: Option<i64>,
+}
+
+pub async fn get_score_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ScoreHistoryQuery>,
+) -> Result<Json<ScoreHistoryResponse>, AppError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+
+    let records = ScoreRepository::new(state.db_pool.clone())
+        .history(id, limit)
+        .await?;
+
+    Ok(Json(ScoreHistoryResponse {
+        content_id: id,
+        items: records
+            .into_iter()
+            .map(|record| ScoreHistoryEntry {
+                id: record.id,
+                overall: record.score.overall,
+                components: record.score.components,
+                calculated_at: record.calculated_at,
+            })
+            .collect(),
     }))
 }
 
-// Get schema.org markup
+// Platform strategies
+#[derive(Deserialize)]
+pub struct StrategyListQuery {
+    tenant_id: Option<Uuid>,
+}
+
+pub async fn list_strategies(
+    State(state): State<AppState>,
+    Query(query): Query<StrategyListQuery>,
+) -> Result<Json<Vec<StrategyResponse>>, AppError> {
+    let records = StrategyRepository::new(state.db_pool.clone())
+        .list(query.tenant_id)
+        .await?;
+
+    Ok(Json(records.into_iter().map(strategy_to_response).collect()))
+}
+
+pub async fn create_strategy(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateStrategyRequest>,
+) -> Result<Json<StrategyResponse>, AppError> {
+    let repo = StrategyRepository::new(state.db_pool.clone());
+
+    let strategy = PlatformStrategy {
+        platform: payload.platform.to_string(),
+        priority: payload.priority.unwrap_or(5),
+        active: payload.active.unwrap_or(true),
+        custom_rules: payload.custom_rules.unwrap_or_default(),
+    };
+    validate_strategy(&state.aeo_optimizer, strategy.priority, &strategy.custom_rules)?;
+
+    let record = repo
+        .create(payload.tenant_id, &strategy)
+        .await?
+        .ok_or_else(|| {
+            AppError::Conflict("Strategy already exists for this platform".to_string())
+        })?;
+
+    tracing::info!("Strategy created: {} ({})", record.strategy.platform, record.id);
+
+    Ok(Json(strategy_to_response(record)))
+}
+
+pub async fn get_strategy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<StrategyResponse>, AppError> {
+    let record = StrategyRepository::new(state.db_pool.clone())
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Strategy not found".to_string()))?;
+
+    Ok(Json(strategy_to_response(record)))
+}
+
+pub async fn update_strategy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateStrategyRequest>,
+) -> Result<Json<StrategyResponse>, AppError> {
+    let repo = StrategyRepository::new(state.db_pool.clone());
+
+    let existing = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Strategy not found".to_string()))?;
+
+    validate_strategy(
+        &state.aeo_optimizer,
+        payload.priority.unwrap_or(existing.strategy.priority),
+        payload.custom_rules.as_deref().unwrap_or_default(),
+    )?;
+
+    let record = repo
+        .update(
+            id,
+            payload.priority,
+            payload.active,
+            payload.custom_rules.as_deref(),
+        )
+        .await?;
+
+    tracing::info!("Strategy updated: {}", id);
+
+    Ok(Json(strategy_to_response(record)))
+}
+
+pub async fn delete_strategy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let repo = StrategyRepository::new(state.db_pool.clone());
+
+    repo.find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Strategy not found".to_string()))?;
+    repo.delete(id).await?;
+
+    tracing::info!("Strategy deleted: {}", id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Get schema.org markup: every applicable type in one @graph, served from
+// the schema_markup cache and generated on first request
 pub async fn get_schema_markup(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
@@ -286,44 +1115,691 @@ pub async fn get_schema_markup(
         .await?
         .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
 
-    let schema_gen = SchemaGenerator::new();
+    let mut schemas = repo.schema_markup(id).await?;
+    if schemas.is_empty() {
+        schemas = refresh_schema_markup(&state, &repo, &content).await?;
+    }
+
+    Ok(Json(SchemaGenerator::graph(&schemas).map_err(anyhow::Error::from)?))
+}
+
+// Validate schema.org markup: a JSON-LD document, or a full HTML page whose
+// JSON-LD script blocks are checked in order (paths start at `$[n]` for the
+// n-th block)
+pub async fn validate_schema(
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<ValidationReport>, AppError> {
+    if is_json_body(&headers, &body) {
+        let document: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|err| AppError::Validation(format!("Invalid JSON: {}", err)))?;
+        return Ok(Json(SchemaValidator::validate(&document)));
+    }
+
+    let blocks = markup::json_ld_blocks(&body);
+    if blocks.is_empty() {
+        return Err(AppError::Validation(
+            "No <script type=\"application/ld+json\"> blocks found in the page".to_string(),
+        ));
+    }
+
+    // Unparseable blocks are reported at their index rather than dropped
+    let mut failures = Vec::new();
+    let documents = blocks
+        .into_iter()
+        .enumerate()
+        .map(|(i, block)| {
+            block.unwrap_or_else(|err| {
+                failures.push((i, err));
+                serde_json::Value::Null
+            })
+        })
+        .collect();
+
+    let mut report = SchemaValidator::validate(&serde_json::Value::Array(documents));
+    for (i, err) in failures {
+        report.error(&format!("$[{}]", i), None, format!("Invalid JSON-LD: {}", err));
+    }
+
+    Ok(Json(report))
+}
 
-    // Generate Article schema
-    let schema = schema_gen.generate_article_schema(
-        &content.title,
-        &content.body,
-        "ASA Author", // In production, fetch actual author name
-        content.created_at,
-        content.updated_at,
+// Extract schema.org items from an HTML page (JSON-LD, microdata and RDFa),
+// or from a JSON-LD document, e.g. to audit a competitor's markup
+pub async fn parse_schema(
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<ParsedMarkup>, AppError> {
+    if is_json_body(&headers, &body) {
+        let document: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|err| AppError::Validation(format!("Invalid JSON: {}", err)))?;
+        return Ok(Json(ParsedMarkup {
+            items: markup::items(MarkupSource::JsonLd, &document),
+            errors: vec![],
+        }));
+    }
+
+    Ok(Json(markup::parse_page(&body)))
+}
+
+/// JSON when the content type says so, or when there is none and the body
+/// looks like a JSON document
+fn is_json_body(headers: &HeaderMap, body: &str) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map_or_else(
+            || body.trim_start().starts_with(['{', '[']),
+            |content_type| content_type.contains("json"),
+        )
+}
+
+/// Regenerate the cached schema.org markup after content is saved
+async fn refresh_schema_markup(
+    state: &AppState,
+    repo: &ContentRepository,
+    content: &Content,
+) -> anyhow::Result<Vec<SchemaType>> {
+    let author = repo.author_name(content.author_id).await?;
+    let schemas = SchemaExtractor::new(state.config.schema.clone()).for_content(
+        content,
+        author.as_deref().unwrap_or(&state.config.schema.publisher_name),
     );
+    repo.replace_schema_markup(content.id, &schemas).await?;
+    Ok(schemas)
+}
 
-    Ok(Json(schema))
+/// Re-extract a content item's entities into the knowledge graph after it is
+/// saved. Only the dictionary and patterns run here; the model is opt-in.
+async fn refresh_entities(state: &AppState, content: &Content) -> anyhow::Result<()> {
+    let kg = KnowledgeGraphRepository::new(state.db_pool.clone());
+    let (_, extraction) = extract_entities(state, &kg, content).await?;
+    kg.replace_content_entities(content.id, &extraction).await
 }
 
-// Publish content
-pub async fn publish_content(
+/// Snapshot content as a new revision by `created_by`, with its AEO score at
+/// that point, which also becomes `content`'s current score
+async fn record_revision(
+    state: &AppState,
+    repo: &ContentRepository,
+    content: &mut Content,
+    event: RevisionEvent,
+    restored_from: Option<i32>,
+    created_by: Option<Uuid>,
+) -> anyhow::Result<i32> {
+    let revision = RevisionRepository::new(state.db_pool.clone())
+        .record(content, event, restored_from, created_by)
+        .await?;
+    score_revision(state, repo, content, revision).await?;
+    Ok(revision)
+}
+
+/// Score content as it is now, which becomes its current score and the
+/// score of `revision`
+async fn score_revision(
+    state: &AppState,
+    repo: &ContentRepository,
+    content: &mut Content,
+    revision: i32,
+) -> anyhow::Result<()> {
+    let score = score_content(state, repo, content, None).await?;
+    let score = ScoreRepository::new(state.db_pool.clone())
+        .record(content.id, &score.aeo_score)
+        .await?;
+    content.aeo_score = Some(score.score);
+    RevisionRepository::new(state.db_pool.clone())
+        .attach_score(content.id, revision, score.id)
+        .await
+}
+
+/// Dictionary and pattern extraction, with entities already in the graph
+/// added to the built-in dictionary
+async fn extract_entities(
+    state: &AppState,
+    kg: &KnowledgeGraphRepository,
+    content: &Content,
+) -> anyhow::Result<(Document, Extraction)> {
+    let dictionary = EntityDictionary::builtin(state.aeo_optimizer.platforms())
+        .extend(kg.dictionary(GRAPH_DICTIONARY_SIZE).await?);
+    let doc = Document::parse(&content.body);
+    let extraction = EntityExtractor::new(&dictionary).extract(&content.title, &doc);
+    Ok((doc, extraction))
+}
+
+// Knowledge graph
+// Re-run entity extraction for a content item; `llm=true` also asks a
+// language model for entities and typed relationships
+pub async fn extract_content_entities(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(query): Query<ExtractEntitiesQuery>,
+) -> Result<Json<ContentEntitiesResponse>, AppError> {
+    let content = ContentRepository::new(state.db_pool.clone())
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
+
+    let kg = KnowledgeGraphRepository::new(state.db_pool.clone());
+    let (doc, mut extraction) = extract_entities(&state, &kg, &content).await?;
+
+    if query.llm.unwrap_or(false) {
+        let providers = state.ai_generator.providers();
+        let provider = providers.select_default(query.provider.as_deref())?;
+        let usage = entities::extract_with_llm(
+            providers,
+            provider.as_ref(),
+            &content.title,
+            &doc,
+            &mut extraction,
+        )
+        .await?;
+        if let Some(usage) = usage {
+            tracing::info!(
+                "Entity extraction for {} used {} tokens from {}",
+                id,
+                usage.prompt_tokens + usage.completion_tokens,
+                provider.name()
+            );
+        }
+    }
+
+    kg.replace_content_entities(id, &extraction).await?;
+
+    Ok(Json(ContentEntitiesResponse {
+        content_id: id,
+        entities: kg.content_entities(id).await?,
+    }))
+}
+
+// Entities a content item mentions, most salient first
+pub async fn get_content_entities(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ContentEntitiesResponse>, AppError> {
+    if ContentRepository::new(state.db_pool.clone())
+        .find_by_id(id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::NotFound("Content not found".to_string()));
+    }
+
+    let entities = KnowledgeGraphRepository::new(state.db_pool.clone())
+        .content_entities(id)
+        .await?;
+
+    Ok(Json(ContentEntitiesResponse {
+        content_id: id,
+        entities,
+    }))
+}
+
+// Search entities by name and type
+pub async fn list_entities(
+    State(state): State<AppState>,
+    Query(query): Query<EntitySearchQuery>,
+) -> Result<Json<Vec<EntityResponse>>, AppError> {
+    let entities = KnowledgeGraphRepository::new(state.db_pool.clone())
+        .search(
+            query.q.as_deref().filter(|q| !q.trim().is_empty()),
+            query.entity_type.as_deref(),
+            query.limit.unwrap_or(20).clamp(1, 100),
+        )
+        .await?;
+
+    Ok(Json(entities))
+}
+
+// Entities related to one, strongest relationship first
+pub async fn get_entity_neighbors(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<EntityNeighborsQuery>,
+) -> Result<Json<EntityNeighborsResponse>, AppError> {
+    let kg = KnowledgeGraphRepository::new(state.db_pool.clone());
+
+    let entity = kg
+        .find(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Entity not found".to_string()))?;
+    let neighbors = kg
+        .neighbors(
+            id,
+            query.relationship_type.as_deref(),
+            query.limit.unwrap_or(20).clamp(1, 100),
+        )
+        .await?;
+
+    Ok(Json(EntityNeighborsResponse { entity, neighbors }))
+}
+
+// Shortest chain of relationships between two entities
+pub async fn get_entity_path(
+    State(state): State<AppState>,
+    Query(query): Query<EntityPathQuery>,
+) -> Result<Json<EntityPathResponse>, AppError> {
+    let kg = KnowledgeGraphRepository::new(state.db_pool.clone());
+
+    for id in [query.from, query.to] {
+        if kg.find(id).await?.is_none() {
+            return Err(AppError::NotFound(format!("Entity {} not found", id)));
+        }
+    }
+
+    let max_depth = query.max_depth.unwrap_or(MAX_PATH_DEPTH).clamp(1, MAX_PATH_DEPTH);
+    let steps = kg
+        .shortest_path(query.from, query.to, max_depth)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "No path within {} hops between the entities",
+                max_depth
+            ))
+        })?;
+
+    Ok(Json(EntityPathResponse {
+        hops: steps.len().saturating_sub(1),
+        steps,
+    }))
+}
+
+// Revisions
+#[derive(Deserialize)]
+pub struct RevisionListQuery {
+    limit: Option<i64>,
+}
+
+// Saved states of a content item, newest first
+pub async fn list_revisions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<RevisionListQuery>,
+) -> Result<Json<RevisionListResponse>, AppError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+
+    ContentRepository::new(state.db_pool.clone())
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
+    let records = RevisionRepository::new(state.db_pool.clone())
+        .list(id, limit)
+        .await?;
+
+    Ok(Json(RevisionListResponse {
+        content_id: id,
+        items: records
+            .into_iter()
+            .map(|record| RevisionSummary {
+                revision: record.revision,
+                event: record.event,
+                title: record.title,
+                status: record.status,
+                word_count: record.body.split_whitespace().count(),
+                aeo_score: record.aeo_score.map(|score| score.overall),
+                restored_from: record.restored_from,
+                created_by: record.created_by,
+                created_at: record.created_at,
+            })
+            .collect(),
+    }))
+}
+
+pub async fn get_revision(
+    State(state): State<AppState>,
+    Path((id, revision)): Path<(Uuid, i32)>,
+) -> Result<Json<RevisionResponse>, AppError> {
+    let record = find_revision(&RevisionRepository::new(state.db_pool.clone()), id, revision).await?;
+
+    Ok(Json(RevisionResponse {
+        content_id: record.content_id,
+        revision: record.revision,
+        event: record.event,
+        title: record.title,
+        slug: record.slug,
+        body: record.body,
+        content_type: record.content_type,
+        status: record.status,
+        metadata: record.metadata,
+        aeo_score: record.aeo_score,
+        restored_from: record.restored_from,
+        created_by: record.created_by,
+        created_at: record.created_at,
+    }))
+}
+
+// Compare two revisions: changed fields, the body word by word and
+// metadata value by value
+pub async fn diff_revisions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<RevisionDiffQuery>,
+) -> Result<Json<RevisionDiffResponse>, AppError> {
+    let repo = RevisionRepository::new(state.db_pool.clone());
+
+    let from = find_revision(&repo, id, query.from).await?;
+    let to = match query.to {
+        Some(revision) => find_revision(&repo, id, revision).await?,
+        None => repo
+            .latest(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?,
+    };
+
+    Ok(Json(revisions::diff(&from, &to)))
+}
+
+// Restore title, slug, body, content type and metadata from a revision. The
+// restore is itself a new revision, so it can be undone the same way.
+// Authors may restore their own unpublished content; replacing what is live
+// takes the same editor or admin role as publishing it.
+pub async fn restore_revision(
+    State(state): State<AppState>,
+    Path((id, revision)): Path<(Uuid, i32)>,
+    actor: Actor,
+) -> Result<Json<ContentResponse>, AppError> {
+    let repo = ContentRepository::new(state.db_pool.clone());
+    let current = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
+    authorize_change(&repo, actor, &current, "restore").await?;
+
+    let record = find_revision(&RevisionRepository::new(state.db_pool.clone()), id, revision).await?;
+
+    if let Some(other) = repo.find_by_slug(&record.slug).await? {
+        if other.id != id {
+            return Err(AppError::Conflict(format!(
+                "Slug '{}' is now used by other content",
+                record.slug
+            )));
+        }
+    }
+
+    // Revisions keep what was stored at the time, which may predate the
+    // current content types and metadata fields
+    let unrestorable =
+        |err: String| AppError::Conflict(format!("Revision {} can't be restored: {}", revision, err));
+    let mut content = current;
+    content.title = record.title;
+    content.slug = record.slug;
+    content.body = record.body;
+    content.content_type = record.content_type;
+    content.metadata = record
+        .metadata
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|err| unrestorable(err.to_string()))?
+        .unwrap_or_default();
+    measure(&mut content);
+
+    let (mut content, restored) = repo
+        .update(&content, RevisionEvent::Restored, Some(revision), Some(actor.user_id))
+        .await?;
+    content.schema = refresh_schema_markup(&state, &repo, &content).await?;
+    refresh_entities(&state, &content).await?;
+    score_revision(&state, &repo, &mut content, restored).await?;
+
+    tracing::info!("Content {} restored to revision {} by {}", id, revision, actor.user_id);
+
+    Ok(Json(content_to_response(content)))
+}
+
+async fn find_revision(
+    repo: &RevisionRepository,
+    id: Uuid,
+    revision: i32,
+) -> Result<RevisionRecord, AppError> {
+    repo.find(id, revision)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Revision {} not found", revision)))
+}
+
+// Editorial workflow
+// Take a workflow action (submit, approve, request_changes, publish,
+// unpublish, archive, restore or comment) as the authenticated user
+pub async fn apply_workflow_action(
+    State(state): State<AppState>,
+    Path((id, action)): Path<(Uuid, String)>,
+    actor: Actor,
+    payload: Option<Json<WorkflowActionRequest>>,
 ) -> Result<Json<ContentResponse>, AppError> {
+    let action: WorkflowAction = action
+        .parse()
+        .map_err(|err: UnknownWorkflowAction| AppError::Validation(err.to_string()))?;
+
+    Ok(Json(content_to_response(
+        transition_content(&state, id, action, actor, payload).await?,
+    )))
+}
+
+// Workflow transitions and review comments, newest first
+pub async fn get_workflow_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WorkflowHistoryResponse>, AppError> {
     let repo = ContentRepository::new(state.db_pool.clone());
 
-    let content = repo.publish(id).await?;
+    let content = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
 
-    tracing::info!("Content published: {}", content.id);
+    Ok(Json(WorkflowHistoryResponse {
+        content_id: id,
+        status: content.status,
+        items: repo.workflow_history(id).await?,
+    }))
+}
 
+// Publish content, skipping review (editors and admins)
+pub async fn publish_content(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    payload: Option<Json<WorkflowActionRequest>>,
+) -> Result<Json<ContentResponse>, AppError> {
+    let content = transition_content(&state, id, WorkflowAction::Publish, actor, payload).await?;
     Ok(Json(content_to_response(content)))
 }
 
-// Unpublish content
+// Unpublish content back to draft (editors and admins)
 pub async fn unpublish_content(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    actor: Actor,
+    payload: Option<Json<WorkflowActionRequest>>,
+) -> Result<Json<ContentResponse>, AppError> {
+    let content = transition_content(&state, id, WorkflowAction::Unpublish, actor, payload).await?;
+    Ok(Json(content_to_response(content)))
+}
+
+/// Check that the user's role allows the action and the content's status
+/// admits it, then apply and audit it
+async fn transition_content(
+    state: &AppState,
+    id: Uuid,
+    action: WorkflowAction,
+    actor: Actor,
+    payload: Option<Json<WorkflowActionRequest>>,
+) -> Result<Content, AppError> {
+    let repo = ContentRepository::new(state.db_pool.clone());
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+
+    let content = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
+    let role = acting_role(&repo, actor.user_id).await?;
+
+    if !action.permits(role, content.author_id == actor.user_id) {
+        return Err(AppError::Forbidden(format!(
+            "A user with role '{}' can't {} this content",
+            role.id(),
+            action.id()
+        )));
+    }
+
+    let from = content.status;
+    let to = action.next_status(from).ok_or_else(|| {
+        AppError::Conflict(format!("Can't {} content that is {}", action.id(), from.id()))
+    })?;
+
+    let comment = payload
+        .comment
+        .as_deref()
+        .map(str::trim)
+        .filter(|comment| !comment.is_empty());
+    if action.requires_comment() && comment.is_none() {
+        return Err(AppError::Validation(format!("A comment is required to {}", action.id())));
+    }
+
+    let mut content = repo
+        .transition(id, action, from, to, Some(actor.user_id), comment)
+        .await?
+        .ok_or_else(|| {
+            AppError::Conflict("Content status changed meanwhile, reload and try again".to_string())
+        })?;
+
+    if to != from {
+        let event = ContentEvent::status_changed(id, &content.slug, action, from, to)
+            .by(Some(actor.user_id));
+        after_transition(state, &repo, &mut content, event).await?;
+    }
+
+    tracing::info!(
+        "Content {} {}: {} -> {} by {}",
+        id,
+        action.id(),
+        from.id(),
+        to.id(),
+        actor.user_id
+    );
+
+    Ok(content)
+}
+
+/// Revisions and events for a status change: going live or coming down
+/// is a revision, other steps are only in the workflow audit
+pub(crate) async fn after_transition(
+    state: &AppState,
+    repo: &ContentRepository,
+    content: &mut Content,
+    event: ContentEvent,
+) -> anyhow::Result<()> {
+    if event.to_status == ContentStatus::Published {
+        record_revision(state, repo, content, RevisionEvent::Published, None, event.user_id)
+            .await?;
+    } else if event.from_status == ContentStatus::Published {
+        record_revision(state, repo, content, RevisionEvent::Unpublished, None, event.user_id)
+            .await?;
+    }
+
+    // Subscribers missing an event is no reason to fail the transition
+    if let Err(err) = events::emit(&state.redis_client, &event).await {
+        tracing::warn!("Failed to emit content event for {}: {:?}", content.id, err);
+    }
+    Ok(())
+}
+
+/// Changing content outside the workflow (editing, restoring or deleting it):
+/// content that is live or in review needs a user who may publish, anything
+/// else a user who may submit it
+async fn authorize_change(
+    repo: &ContentRepository,
+    actor: Actor,
+    content: &Content,
+    verb: &str,
+) -> Result<(), AppError> {
+    let role = acting_role(repo, actor.user_id).await?;
+    let own_content = content.author_id == actor.user_id;
+    let permitted = match content.status {
+        ContentStatus::Published | ContentStatus::Review => {
+            WorkflowAction::Publish.permits(role, own_content)
+        }
+        ContentStatus::Draft | ContentStatus::Archived => {
+            WorkflowAction::Submit.permits(role, own_content)
+        }
+    };
+    if !permitted {
+        return Err(AppError::Forbidden(format!(
+            "A user with role '{}' can't {} this {} content",
+            role.id(),
+            verb,
+            content.status.id()
+        )));
+    }
+    Ok(())
+}
+
+/// The workflow role of the user a request acts for
+async fn acting_role(repo: &ContentRepository, user_id: Uuid) -> Result<UserRole, AppError> {
+    let role = repo
+        .user_role(user_id)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized(format!("User {} not found", user_id)))?;
+    // Roles this service doesn't know get no permissions
+    Ok(role.parse().unwrap_or(UserRole::Viewer))
+}
+
+// Schedule publishing and unpublishing (editors and admins). Both times are
+// replaced; leave one out to clear it.
+pub async fn schedule_content(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Json(payload): Json<ScheduleContentRequest>,
 ) -> Result<Json<ContentResponse>, AppError> {
     let repo = ContentRepository::new(state.db_pool.clone());
 
-    let content = repo.unpublish(id).await?;
+    let content = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Content not found".to_string()))?;
+    let role = acting_role(&repo, actor.user_id).await?;
+    if !WorkflowAction::Publish.permits(role, content.author_id == actor.user_id) {
+        return Err(AppError::Forbidden(format!(
+            "A user with role '{}' can't schedule this content",
+            role.id()
+        )));
+    }
 
-    tracing::info!("Content unpublished: {}", content.id);
+    let now = chrono::Utc::now();
+    let status = content.status;
+    if let Some(publish_at) = payload.publish_at {
+        if publish_at <= now {
+            return Err(AppError::Validation("publish_at must be in the future".to_string()));
+        }
+        if WorkflowAction::Publish.next_status(status).is_none() {
+            return Err(AppError::Conflict(format!(
+                "Can't schedule publishing content that is {}",
+                status.id()
+            )));
+        }
+    }
+    if let Some(unpublish_at) = payload.unpublish_at {
+        if unpublish_at <= payload.publish_at.unwrap_or(now) {
+            return Err(AppError::Validation(
+                "unpublish_at must be in the future and after publish_at".to_string(),
+            ));
+        }
+        if status != ContentStatus::Published && payload.publish_at.is_none() {
+            return Err(AppError::Conflict(
+                "Only published content, or content scheduled to be, can be unpublished"
+                    .to_string(),
+            ));
+        }
+    }
+
+    let content = repo
+        .schedule(id, payload.publish_at, payload.unpublish_at, actor.user_id)
+        .await?;
+
+    tracing::info!(
+        "Content {} scheduled: publish {:?}, unpublish {:?}",
+        id,
+        content.scheduled_publish_at,
+        content.scheduled_unpublish_at
+    );
 
     Ok(Json(content_to_response(content)))
 }
@@ -335,24 +1811,169 @@ fn content_to_response(content: Content) -> ContentResponse {
         title: content.title,
         slug: content.slug,
         body: content.body,
+        excerpt: content.excerpt,
         content_type: content.content_type,
         status: content.status,
         author_id: content.author_id,
+        schema: content.schema,
+        aeo_score: content.aeo_score,
         metadata: content.metadata,
         created_at: content.created_at,
         updated_at: content.updated_at,
         published_at: content.published_at,
+        generation_job_id: content.generation_job_id,
+        scheduled_publish_at: content.scheduled_publish_at,
+        scheduled_unpublish_at: content.scheduled_unpublish_at,
+    }
+}
+
+fn template_to_response(template: PromptTemplate) -> PromptTemplateResponse {
+    PromptTemplateResponse {
+        id: template.id,
+        name: template.name,
+        content_type: template.content_type,
+        platform: template.platform,
+        version: template.version,
+        description: template.description,
+        system_prompt: template.system_prompt,
+        template: template.template,
+        active: template.active,
+        created_at: template.created_at,
+    }
+}
+
+fn job_to_response(job: GenerationJob) -> GenerationJobResponse {
+    let provenance = job.provenance;
+    GenerationJobResponse {
+        id: job.id,
+        kind: job.kind,
+        topic: job.topic,
+        content_type: job.content_type,
+        platform: job.platform,
+        provider: provenance.provider,
+        model: provenance.model,
+        mock: provenance.mock,
+        system_prompt: provenance.system_prompt,
+        prompt: provenance.prompt,
+        prompt_template: provenance.template,
+        prompt_tokens: provenance.usage.map(|usage| usage.prompt_tokens),
+        completion_tokens: provenance.usage.map(|usage| usage.completion_tokens),
+        latency_ms: provenance.latency.as_millis() as u64,
+        estimated_cost: provenance.estimated_cost,
+        title: job.title,
+        output: job.output,
+        content_id: job.content_id,
+        created_at: job.created_at,
+    }
+}
+
+fn strategy_to_response(record: StrategyRecord) -> StrategyResponse {
+    StrategyResponse {
+        id: record.id,
+        tenant_id: record.tenant_id,
+        platform: record.strategy.platform,
+        priority: record.strategy.priority,
+        active: record.strategy.active,
+        custom_rules: record.strategy.custom_rules,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }
+}
+
+/// Reject strategies whose priority is out of range or whose rules don't compile
+fn validate_strategy(
+    optimizer: &AEOOptimizer,
+    priority: u8,
+    rules: &[OptimizationRule],
+) -> Result<(), AppError> {
+    if !(1..=10).contains(&priority) {
+        return Err(AppError::Validation(
+            "Priority must be between 1 and 10".to_string(),
+        ));
+    }
+
+    for (i, rule) in rules.iter().enumerate() {
+        if rule.name.trim().is_empty() {
+            return Err(AppError::Validation(format!("Rule {} has no name", i)));
+        }
+        if rules[..i].iter().any(|other| other.name == rule.name) {
+            return Err(AppError::Validation(format!(
+                "Duplicate rule name '{}'",
+                rule.name
+            )));
+        }
+        optimizer
+            .validate_rule(rule)
+            .map_err(|err| AppError::Validation(err.to_string()))?;
     }
+
+    Ok(())
+}
+
+/// Word count, reading time and excerpt follow the body. The excerpt is
+/// the same opening text schema markup uses as a description.
+fn measure(content: &mut Content) {
+    let doc = Document::parse(&content.body);
+    content.metadata.set_word_count(doc.word_count());
+    content.excerpt = Some(schema_extractor::describe(&doc)).filter(|text| !text.is_empty());
+}
+
+/// Opaque cursor for the page after `last`
+fn encode_cursor(content: &Content, sort: ContentSort, order: SortOrder) -> anyhow::Result<String> {
+    let value = match sort {
+        ContentSort::CreatedAt => serde_json::to_value(content.created_at)?,
+        ContentSort::UpdatedAt => serde_json::to_value(content.updated_at)?,
+        ContentSort::PublishedAt => serde_json::to_value(content.published_at)?,
+        ContentSort::Title => serde_json::to_value(&content.title)?,
+        ContentSort::AeoScore => {
+            serde_json::to_value(content.aeo_score.as_ref().map(|score| score.overall))?
+        }
+    };
+    let cursor = ContentCursor {
+        sort,
+        order,
+        value,
+        id: content.id,
+    };
+    Ok(URL_SAFE_NO_PAD.encode(serde_json::to_vec(&cursor)?))
+}
+
+/// The sort key and id a cursor points after, if it was made for this sort
+fn decode_cursor(
+    cursor: &str,
+    sort: ContentSort,
+    order: SortOrder,
+) -> Result<(SortValue, Uuid), AppError> {
+    let invalid = || AppError::Validation("Invalid cursor".to_string());
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let cursor: ContentCursor = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+    if cursor.sort != sort || cursor.order != order {
+        return Err(AppError::Validation(
+            "Cursor is for a different sort or order".to_string(),
+        ));
+    }
+
+    Ok((cursor.sort_value().map_err(|_| invalid())?, cursor.id))
 }
 
 // Error handling
 #[derive(Debug)]
 pub enum AppError {
     NotFound(String),
+    Unauthorized(String),
+    Forbidden(String),
     Conflict(String),
+    Validation(String),
+    Generation(GenerationError),
     Internal(anyhow::Error),
 }
 
+impl From<GenerationError> for AppError {
+    fn from(err: GenerationError) -> Self {
+        AppError::Generation(err)
+    }
+}
+
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
         AppError::Internal(err)
@@ -369,7 +1990,35 @@ impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
         let (status, message) = match self {
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
             AppError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            AppError::Validation(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg),
+            AppError::Generation(err) => {
+                let status = match &err {
+                    GenerationError::UnknownProvider { .. }
+                    | GenerationError::ContentFiltered { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+                    GenerationError::NotConfigured(_) => StatusCode::SERVICE_UNAVAILABLE,
+                    GenerationError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+                    GenerationError::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+                    GenerationError::Template { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+                    GenerationError::Auth { .. }
+                    | GenerationError::MalformedOutput { .. }
+                    | GenerationError::Upstream { .. }
+                    | GenerationError::Unreachable { .. } => StatusCode::BAD_GATEWAY,
+                };
+                tracing::warn!("Generation failed: {}", err);
+
+                if let Some(retry_after) = err.retry_after() {
+                    return (
+                        status,
+                        [(header::RETRY_AFTER, retry_after.as_secs().max(1).to_string())],
+                        Json(serde_json::json!({ "error": err.to_string() })),
+                    )
+                        .into_response();
+                }
+                (status, err.to_string())
+            }
             AppError::Internal(err) => {
                 tracing::error!("Internal error: {:?}", err);
                 (
@@ -0,0 +1,1104 @@
+use asa_models::aeo::profile::PlatformRegistry;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use reqwest::{header::RETRY_AFTER, Response, StatusCode};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::future::{ready, Future};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::LlmConfig;
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com/v1";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Longest backoff between retries, whatever the provider asks for
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Provider names `LlmProviders` can register
+const KNOWN_PROVIDERS: [&str; 4] = ["openai", "anthropic", "openai_compatible", "fixture"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum GenerationError {
+    #[error("provider '{name}' is not configured, expected one of: {available}")]
+    UnknownProvider { name: String, available: String },
+    #[error("provider '{0}' has no credentials or base URL configured")]
+    NotConfigured(String),
+    #[error("{provider} rejected our credentials")]
+    Auth { provider: String },
+    #[error("{provider} rate limit exceeded")]
+    RateLimited {
+        provider: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("{provider} refused the request: {reason}")]
+    ContentFiltered { provider: String, reason: String },
+    #[error("{provider} returned malformed output: {reason}")]
+    MalformedOutput { provider: String, reason: String },
+    #[error("{provider} timed out")]
+    Timeout { provider: String },
+    #[error("{provider} returned {status}: {message}")]
+    Upstream {
+        provider: String,
+        status: u16,
+        message: String,
+    },
+    #[error("prompt template '{name}' v{version} failed to render: {reason}")]
+    Template {
+        name: String,
+        version: i32,
+        reason: String,
+    },
+    #[error("{provider} could not be reached: {source}")]
+    Unreachable {
+        provider: String,
+        #[source]
+        source: reqwest::Error,
+    },
+}
+
+impl GenerationError {
+    /// Worth retrying: the same request may succeed later
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } | Self::Timeout { .. } | Self::Unreachable { .. } => true,
+            // 529 is Anthropic's "overloaded"
+            Self::Upstream { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    fn malformed(provider: &str, reason: impl Into<String>) -> Self {
+        Self::MalformedOutput {
+            provider: provider.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    fn transport(provider: &str, source: reqwest::Error) -> Self {
+        if source.is_timeout() {
+            Self::Timeout {
+                provider: provider.to_string(),
+            }
+        } else {
+            Self::Unreachable {
+                provider: provider.to_string(),
+                source,
+            }
+        }
+    }
+
+    /// Classify a non-2xx response
+    async fn from_response(provider: &str, response: Response) -> Self {
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let message = response.text().await.unwrap_or_default();
+        let provider = provider.to_string();
+
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Auth { provider },
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited {
+                provider,
+                retry_after,
+            },
+            StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => Self::Timeout { provider },
+            _ if message.contains("content_filter") || message.contains("content_policy") => {
+                Self::ContentFiltered {
+                    provider,
+                    reason: message,
+                }
+            }
+            _ => Self::Upstream {
+                provider,
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
+}
+
+/// How often and how long to retry transient failures
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Exponential backoff, or the provider's Retry-After when it sends one
+    fn delay(&self, attempt: u32, err: &GenerationError) -> Duration {
+        err.retry_after()
+            .unwrap_or_else(|| self.base_delay.saturating_mul(2u32.saturating_pow(attempt)))
+            .min(MAX_BACKOFF)
+    }
+}
+
+/// A single prompt sent to a model
+#[derive(Debug, Clone)]
+pub struct CompletionRequest {
+    pub system: Option<String>,
+    pub prompt: String,
+    /// Ask for a JSON object where the backend supports it
+    pub json: bool,
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+impl CompletionRequest {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            system: None,
+            prompt: prompt.into(),
+            json: false,
+            temperature: 0.7,
+            max_tokens: 4096,
+        }
+    }
+
+    pub fn system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    pub fn json(mut self) -> Self {
+        self.json = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+    }
+}
+
+/// USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+pub struct ModelPrice {
+    pub prompt: f64,
+    pub completion: f64,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.prompt_tokens as f64 * self.prompt + usage.completion_tokens as f64 * self.completion)
+            / 1_000_000.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub text: String,
+    pub usage: Option<TokenUsage>,
+}
+
+/// One piece of a streamed completion
+#[derive(Debug, Clone)]
+pub enum StreamDelta {
+    Text(String),
+    /// Token counts, possibly partial; add them up
+    Usage(TokenUsage),
+}
+
+/// Deltas of a completion, in order
+pub type TokenStream = BoxStream<'static, Result<StreamDelta, GenerationError>>;
+
+/// A text-generation backend
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Name used to select the provider in config and requests
+    fn name(&self) -> &str;
+
+    fn model(&self) -> &str;
+
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion, GenerationError>;
+
+    /// Stream the completion as it is generated. Backends without streaming
+    /// support yield the whole completion as a single delta.
+    async fn stream(&self, request: &CompletionRequest) -> Result<TokenStream, GenerationError> {
+        let completion = self.complete(request).await?;
+        let deltas = std::iter::once(StreamDelta::Text(completion.text))
+            .chain(completion.usage.map(StreamDelta::Usage))
+            .map(Ok);
+        Ok(stream::iter(deltas.collect::<Vec<_>>()).boxed())
+    }
+
+    /// Canned output rather than a real model
+    fn is_mock(&self) -> bool {
+        false
+    }
+}
+
+/// OpenAI chat completions, or any server exposing the same API
+/// (Ollama, vLLM, llama.cpp) when built with `compatible`
+pub struct OpenAiProvider {
+    name: String,
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    /// Whether the server understands `response_format: json_object`
+    json_mode: bool,
+}
+
+impl OpenAiProvider {
+    pub fn new(client: reqwest::Client, api_key: String, model: String) -> Self {
+        Self {
+            name: "openai".to_string(),
+            client,
+            base_url: OPENAI_BASE_URL.to_string(),
+            api_key: Some(api_key),
+            model,
+            json_mode: true,
+        }
+    }
+
+    pub fn compatible(
+        client: reqwest::Client,
+        base_url: String,
+        api_key: Option<String>,
+        model: String,
+    ) -> Self {
+        Self {
+            name: "openai_compatible".to_string(),
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+            json_mode: false,
+        }
+    }
+
+    /// Send requests somewhere other than api.openai.com, e.g. a proxy
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    async fn send(&self, request: &CompletionRequest, stream: bool) -> Result<Response, GenerationError> {
+        let mut messages = Vec::new();
+        if let Some(system) = &request.system {
+            messages.push(json!({ "role": "system", "content": system }));
+        }
+        messages.push(json!({ "role": "user", "content": request.prompt }));
+
+        let mut body = json!({
+            "model": self.model,
+            "messages": messages,
+            "temperature": request.temperature,
+            "max_tokens": request.max_tokens,
+            "stream": stream,
+        });
+        if stream {
+            body["stream_options"] = json!({ "include_usage": true });
+        }
+        if request.json && self.json_mode {
+            body["response_format"] = json!({ "type": "json_object" });
+        }
+
+        let mut http = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&body);
+        if let Some(api_key) = &self.api_key {
+            http = http.bearer_auth(api_key);
+        }
+
+        let response = http
+            .send()
+            .await
+            .map_err(|err| GenerationError::transport(&self.name, err))?;
+        if !response.status().is_success() {
+            return Err(GenerationError::from_response(&self.name, response).await);
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion, GenerationError> {
+        let response = self.send(request, false).await?;
+
+        let data: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|err| GenerationError::malformed(&self.name, err.to_string()))?;
+        if data["choices"][0]["finish_reason"] == "content_filter" {
+            return Err(GenerationError::ContentFiltered {
+                provider: self.name.clone(),
+                reason: "finish_reason content_filter".to_string(),
+            });
+        }
+        let text = data["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| GenerationError::malformed(&self.name, "no message content"))?;
+
+        Ok(Completion {
+            text: text.to_string(),
+            usage: data.get("usage").map(|usage| TokenUsage {
+                prompt_tokens: usage["prompt_tokens"].as_u64().unwrap_or(0) as u32,
+                completion_tokens: usage["completion_tokens"].as_u64().unwrap_or(0) as u32,
+            }),
+        })
+    }
+
+    async fn stream(&self, request: &CompletionRequest) -> Result<TokenStream, GenerationError> {
+        let response = self.send(request, true).await?;
+        let name = self.name.clone();
+
+        Ok(sse_data(&self.name, response)
+            .try_take_while(|data| ready(Ok(data != "[DONE]")))
+            .try_filter_map(move |data| ready(openai_delta(&name, &data)))
+            .boxed())
+    }
+}
+
+/// Anthropic messages API
+pub struct AnthropicProvider {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(client: reqwest::Client, api_key: String, model: String) -> Self {
+        Self {
+            client,
+            base_url: ANTHROPIC_BASE_URL.to_string(),
+            api_key,
+            model,
+        }
+    }
+
+    /// Send requests somewhere other than api.anthropic.com, e.g. a proxy
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    async fn send(&self, request: &CompletionRequest, stream: bool) -> Result<Response, GenerationError> {
+        let mut body = json!({
+            "model": self.model,
+            "max_tokens": request.max_tokens,
+            "temperature": request.temperature,
+            "messages": [{ "role": "user", "content": request.prompt }],
+            "stream": stream,
+        });
+        if let Some(system) = &request.system {
+            body["system"] = json!(system);
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|err| GenerationError::transport("anthropic", err))?;
+        if !response.status().is_success() {
+            return Err(GenerationError::from_response("anthropic", response).await);
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion, GenerationError> {
+        let response = self.send(request, false).await?;
+
+        let data: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|err| GenerationError::malformed(self.name(), err.to_string()))?;
+        if data["stop_reason"] == "refusal" {
+            return Err(GenerationError::ContentFiltered {
+                provider: self.name().to_string(),
+                reason: "stop_reason refusal".to_string(),
+            });
+        }
+        let text = data["content"][0]["text"]
+            .as_str()
+            .ok_or_else(|| GenerationError::malformed(self.name(), "no text content"))?;
+
+        Ok(Completion {
+            text: text.to_string(),
+            usage: data.get("usage").map(|usage| TokenUsage {
+                prompt_tokens: usage["input_tokens"].as_u64().unwrap_or(0) as u32,
+                completion_tokens: usage["output_tokens"].as_u64().unwrap_or(0) as u32,
+            }),
+        })
+    }
+
+    async fn stream(&self, request: &CompletionRequest) -> Result<TokenStream, GenerationError> {
+        let response = self.send(request, true).await?;
+
+        Ok(sse_data(self.name(), response)
+            .try_filter_map(|data| ready(anthropic_delta(&data)))
+            .boxed())
+    }
+}
+
+/// Deterministic canned responses for tests and offline runs. Returns the
+/// same article for every prompt: the JSON object when JSON is requested,
+/// its markdown body otherwise.
+pub struct FixtureProvider {
+    response: String,
+}
+
+impl FixtureProvider {
+    pub fn new() -> Self {
+        Self {
+            response: fixture_article().to_string(),
+        }
+    }
+
+    fn text(&self, request: &CompletionRequest) -> String {
+        if request.json {
+            return self.response.clone();
+        }
+        serde_json::from_str::<serde_json::Value>(&self.response)
+            .ok()
+            .and_then(|article| article["body"].as_str().map(String::from))
+            .unwrap_or_else(|| self.response.clone())
+    }
+}
+
+impl Default for FixtureProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for FixtureProvider {
+    fn name(&self) -> &str {
+        "fixture"
+    }
+
+    fn model(&self) -> &str {
+        "fixture"
+    }
+
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion, GenerationError> {
+        let text = self.text(request);
+        Ok(Completion {
+            usage: Some(TokenUsage {
+                prompt_tokens: request.prompt.split_whitespace().count() as u32,
+                completion_tokens: text.split_whitespace().count() as u32,
+            }),
+            text,
+        })
+    }
+
+    /// One delta per word, like a model would send them
+    async fn stream(&self, request: &CompletionRequest) -> Result<TokenStream, GenerationError> {
+        let completion = self.complete(request).await?;
+        let deltas: Vec<_> = completion
+            .text
+            .split_inclusive(char::is_whitespace)
+            .map(|delta| StreamDelta::Text(delta.to_string()))
+            .chain(completion.usage.map(StreamDelta::Usage))
+            .map(Ok)
+            .collect();
+        Ok(stream::iter(deltas).boxed())
+    }
+
+    fn is_mock(&self) -> bool {
+        true
+    }
+}
+
+/// Configured providers and the rules for picking one
+#[derive(Clone)]
+pub struct LlmProviders {
+    providers: HashMap<String, Arc<dyn LlmProvider>>,
+    default: String,
+    by_platform: HashMap<String, String>, // by platform id
+    pricing: HashMap<String, ModelPrice>,
+    retry: RetryPolicy,
+}
+
+impl LlmProviders {
+    /// Register every provider with credentials or a base URL; the fixture
+    /// provider is always available but only used when selected. Platform
+    /// overrides may name any platform or alias in `platforms`.
+    pub fn from_config(
+        config: &LlmConfig,
+        platforms: &PlatformRegistry,
+        openai_api_key: &str,
+        anthropic_api_key: &str,
+    ) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()?;
+        let mut providers: HashMap<String, Arc<dyn LlmProvider>> = HashMap::new();
+
+        let mut register = |provider: Arc<dyn LlmProvider>| {
+            providers.insert(provider.name().to_string(), provider);
+        };
+
+        register(Arc::new(FixtureProvider::new()));
+        if !openai_api_key.is_empty() {
+            let provider = OpenAiProvider::new(
+                client.clone(),
+                openai_api_key.to_string(),
+                config.openai_model.clone(),
+            );
+            register(Arc::new(match &config.openai_base_url {
+                Some(base_url) => provider.base_url(base_url),
+                None => provider,
+            }));
+        }
+        if !anthropic_api_key.is_empty() {
+            let provider = AnthropicProvider::new(
+                client.clone(),
+                anthropic_api_key.to_string(),
+                config.anthropic_model.clone(),
+            );
+            register(Arc::new(match &config.anthropic_base_url {
+                Some(base_url) => provider.base_url(base_url),
+                None => provider,
+            }));
+        }
+        if let Some(base_url) = &config.compatible_base_url {
+            register(Arc::new(OpenAiProvider::compatible(
+                client,
+                base_url.clone(),
+                config.compatible_api_key.clone(),
+                config.compatible_model.clone(),
+            )));
+        }
+
+        let by_platform = config
+            .platform_providers
+            .iter()
+            .map(|(platform, provider)| {
+                let profile = platforms.get(platform).ok_or_else(|| {
+                    anyhow::anyhow!("unknown platform '{}' in provider overrides", platform)
+                })?;
+                Ok((profile.id.clone(), provider.clone()))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            providers,
+            default: config.default_provider.clone(),
+            by_platform,
+            pricing: config.pricing.clone(),
+            retry: RetryPolicy {
+                max_retries: config.max_retries,
+                base_delay: Duration::from_millis(500),
+            },
+        })
+    }
+
+    /// Pick a provider: the one named in the request, else the platform's,
+    /// else the default
+    pub fn select(
+        &self,
+        requested: Option<&str>,
+        platform_id: &str,
+    ) -> Result<Arc<dyn LlmProvider>, GenerationError> {
+        let name = requested
+            .or_else(|| self.by_platform.get(platform_id).map(String::as_str))
+            .unwrap_or(&self.default);
+        self.by_name(name)
+    }
+
+    /// The requested provider, or the default for work that isn't written
+    /// for a particular platform
+    pub fn select_default(
+        &self,
+        requested: Option<&str>,
+    ) -> Result<Arc<dyn LlmProvider>, GenerationError> {
+        self.by_name(requested.unwrap_or(&self.default))
+    }
+
+    fn by_name(&self, name: &str) -> Result<Arc<dyn LlmProvider>, GenerationError> {
+        if let Some(provider) = self.providers.get(name) {
+            return Ok(provider.clone());
+        }
+
+        // Known backends that are missing credentials are a deployment problem,
+        // anything else is a bad request
+        if KNOWN_PROVIDERS.contains(&name) {
+            Err(GenerationError::NotConfigured(name.to_string()))
+        } else {
+            Err(GenerationError::UnknownProvider {
+                name: name.to_string(),
+                available: self.names().join(", "),
+            })
+        }
+    }
+
+    /// Run a completion, retrying transient failures with backoff
+    pub async fn complete(
+        &self,
+        provider: &dyn LlmProvider,
+        request: &CompletionRequest,
+    ) -> Result<Completion, GenerationError> {
+        self.with_retries(provider.name(), || provider.complete(request))
+            .await
+    }
+
+    /// Open a completion stream. Only establishing the stream is retried:
+    /// deltas already sent to the caller cannot be taken back.
+    pub async fn stream(
+        &self,
+        provider: &dyn LlmProvider,
+        request: &CompletionRequest,
+    ) -> Result<TokenStream, GenerationError> {
+        self.with_retries(provider.name(), || provider.stream(request))
+            .await
+    }
+
+    async fn with_retries<T, F, Fut>(&self, provider: &str, mut call: F) -> Result<T, GenerationError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, GenerationError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match call().await {
+                Err(err) if err.is_transient() && attempt < self.retry.max_retries => {
+                    let delay = self.retry.delay(attempt, &err);
+                    tracing::warn!(
+                        "{} attempt {} failed, retrying in {:?}: {}",
+                        provider,
+                        attempt + 1,
+                        delay,
+                        err
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Estimated USD cost of a completion, when the model has a known price
+    pub fn estimate_cost(&self, model: &str, usage: &TokenUsage) -> Option<f64> {
+        self.pricing.get(model).map(|price| price.cost(usage))
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.providers.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+/// The `data:` payloads of a server-sent event response
+fn sse_data(provider: &str, response: Response) -> BoxStream<'static, Result<String, GenerationError>> {
+    let provider = provider.to_string();
+    let state = (response.bytes_stream().boxed(), Vec::new(), VecDeque::new());
+
+    stream::try_unfold(state, move |(mut bytes, mut buffer, mut pending)| {
+        let provider = provider.clone();
+        async move {
+            loop {
+                if let Some(data) = pending.pop_front() {
+                    return Ok(Some((data, (bytes, buffer, pending))));
+                }
+                let Some(chunk) = bytes.next().await else {
+                    return Ok(None);
+                };
+                buffer.extend_from_slice(&chunk.map_err(|err| GenerationError::transport(&provider, err))?);
+
+                // Only split on complete lines so multi-byte characters stay intact
+                while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=end).collect();
+                    let line = String::from_utf8_lossy(&line);
+                    if let Some(data) = line.trim_end().strip_prefix("data:") {
+                        pending.push_back(data.trim_start().to_string());
+                    }
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Text or usage of one OpenAI `chat.completion.chunk`
+fn openai_delta(provider: &str, data: &str) -> Result<Option<StreamDelta>, GenerationError> {
+    let chunk: serde_json::Value =
+        serde_json::from_str(data).map_err(|err| GenerationError::malformed(provider, err.to_string()))?;
+    if chunk["choices"][0]["finish_reason"] == "content_filter" {
+        return Err(GenerationError::ContentFiltered {
+            provider: provider.to_string(),
+            reason: "finish_reason content_filter".to_string(),
+        });
+    }
+    if let Some(usage) = chunk.get("usage").filter(|usage| usage.is_object()) {
+        return Ok(Some(StreamDelta::Usage(TokenUsage {
+            prompt_tokens: usage["prompt_tokens"].as_u64().unwrap_or(0) as u32,
+            completion_tokens: usage["completion_tokens"].as_u64().unwrap_or(0) as u32,
+        })));
+    }
+    Ok(chunk["choices"][0]["delta"]["content"]
+        .as_str()
+        .filter(|text| !text.is_empty())
+        .map(|text| StreamDelta::Text(text.to_string())))
+}
+
+/// Text or usage of one Anthropic stream event; other events are skipped
+fn anthropic_delta(data: &str) -> Result<Option<StreamDelta>, GenerationError> {
+    let event: serde_json::Value =
+        serde_json::from_str(data).map_err(|err| GenerationError::malformed("anthropic", err.to_string()))?;
+    let tokens = |value: &serde_json::Value| value.as_u64().unwrap_or(0) as u32;
+
+    match event["type"].as_str() {
+        Some("content_block_delta") => Ok(event["delta"]["text"]
+            .as_str()
+            .filter(|text| !text.is_empty())
+            .map(|text| StreamDelta::Text(text.to_string()))),
+        Some("message_delta") if event["delta"]["stop_reason"] == "refusal" => {
+            Err(GenerationError::ContentFiltered {
+                provider: "anthropic".to_string(),
+                reason: "stop_reason refusal".to_string(),
+            })
+        }
+        // Prompt tokens arrive with the first event, completion tokens with the last
+        Some("message_start") => Ok(Some(StreamDelta::Usage(TokenUsage {
+            prompt_tokens: tokens(&event["message"]["usage"]["input_tokens"]),
+            completion_tokens: 0,
+        }))),
+        Some("message_delta") => Ok(Some(StreamDelta::Usage(TokenUsage {
+            prompt_tokens: 0,
+            completion_tokens: tokens(&event["usage"]["output_tokens"]),
+        }))),
+        Some("error") => Err(GenerationError::Upstream {
+            provider: "anthropic".to_string(),
+            // Anthropic reports mid-stream overload as an event, not a status
+            status: if event["error"]["type"] == "overloaded_error" { 529 } else { 500 },
+            message: event["error"]["message"].as_str().unwrap_or_default().to_string(),
+        }),
+        _ => Ok(None),
+    }
+}
+
+fn fixture_article() -> serde_json::Value {
+    json!({
+        "title": "AI-Generated Content: Understanding AEO",
+        "body": r#"# Understanding Answer Engine Optimization (AEO)
+
+## What is AEO?
+
+Answer Engine Optimization (AEO) is the practice of optimizing content to appear in AI-powered answer engines like ChatGPT, Claude, Perplexity, Google Gemini, and Bing Chat.
+
+## Key Principles
+
+### 1. Structured Content
+Content should be well-organized with clear headings and subheadings that guide both AI and human readers.
+
+### 2. Direct Answers
+Provide concise, direct answers to common questions at the beginning of sections.
+
+### 3. Citations and Sources
+Include authoritative sources and citations to build credibility with AI platforms.
+
+## Implementation Strategies
+
+1. **Use Schema.org markup** for structured data
+2. **Create FAQ sections** for common queries
+3. **Optimize for featured snippets** with concise answers
+4. **Build topical authority** through comprehensive coverage
+
+## Platform-Specific Optimization
+
+Different AI platforms have different preferences:
+- ChatGPT prefers clear structure and examples
+- Claude values depth and nuance
+- Perplexity prioritizes citations
+- Gemini favors multimedia integration
+
+## Conclusion
+
+AEO is becoming essential for content visibility in the age of AI. By following these principles, you can ensure your content is discovered and cited by AI platforms.
+"#,
+        "outline": [
+            "What is AEO?",
+            "Key Principles",
+            "Implementation Strategies",
+            "Platform-Specific Optimization",
+            "Conclusion",
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{llm_config, StubResponse, StubServer};
+    use std::time::Instant;
+
+    fn providers(stub: &StubServer, max_retries: u32) -> LlmProviders {
+        let mut config = llm_config("openai", Some(stub));
+        config.max_retries = max_retries;
+        LlmProviders::from_config(&config, PlatformRegistry::builtin(), "sk-test", "sk-ant-test")
+            .unwrap()
+    }
+
+    async fn complete(providers: &LlmProviders, name: &str) -> Result<Completion, GenerationError> {
+        let provider = providers.select_default(Some(name)).unwrap();
+        let request = CompletionRequest::new("Write about AEO")
+            .system("Be brief")
+            .json();
+        providers.complete(provider.as_ref(), &request).await
+    }
+
+    fn openai_reply(text: &str) -> StubResponse {
+        StubResponse::json(
+            200,
+            json!({
+                "choices": [{ "message": { "content": text }, "finish_reason": "stop" }],
+                "usage": { "prompt_tokens": 12, "completion_tokens": 34 },
+            }),
+        )
+    }
+
+    fn anthropic_reply(text: &str) -> StubResponse {
+        StubResponse::json(
+            200,
+            json!({
+                "content": [{ "type": "text", "text": text }],
+                "stop_reason": "end_turn",
+                "usage": { "input_tokens": 5, "output_tokens": 7 },
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_openai_completion() {
+        let stub = StubServer::start([openai_reply("Hello")]).await;
+
+        let completion = complete(&providers(&stub, 0), "openai").await.unwrap();
+        assert_eq!(completion.text, "Hello");
+        let usage = completion.usage.unwrap();
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens), (12, 34));
+
+        let [request] = &stub.requests()[..] else {
+            panic!("expected one request");
+        };
+        assert_eq!(request.path, "/chat/completions");
+        assert_eq!(request.headers["authorization"], "Bearer sk-test");
+        assert_eq!(request.body["model"], "gpt-test");
+        assert_eq!(request.body["messages"][0]["content"], "Be brief");
+        assert_eq!(request.body["messages"][1]["content"], "Write about AEO");
+        assert_eq!(request.body["response_format"]["type"], "json_object");
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_completion() {
+        let stub = StubServer::start([anthropic_reply("Hi")]).await;
+
+        let completion = complete(&providers(&stub, 0), "anthropic").await.unwrap();
+        assert_eq!(completion.text, "Hi");
+        let usage = completion.usage.unwrap();
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens), (5, 7));
+
+        let [request] = &stub.requests()[..] else {
+            panic!("expected one request");
+        };
+        assert_eq!(request.path, "/messages");
+        assert_eq!(request.headers["x-api-key"], "sk-ant-test");
+        assert_eq!(request.headers["anthropic-version"], ANTHROPIC_VERSION);
+        assert_eq!(request.body["system"], "Be brief");
+        assert_eq!(request.body["model"], "claude-test");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_waits_for_retry_after() {
+        let stub = StubServer::start([
+            StubResponse::text(429, "slow down").header("retry-after", "1"),
+            openai_reply("Done"),
+        ])
+        .await;
+
+        let started = Instant::now();
+        let completion = complete(&providers(&stub, 1), "openai").await.unwrap();
+        assert_eq!(completion.text, "Done");
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(stub.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_reports_retry_after_when_out_of_retries() {
+        for name in ["openai", "anthropic"] {
+            let stub =
+                StubServer::start([StubResponse::text(429, "").header("retry-after", "7")]).await;
+
+            let err = complete(&providers(&stub, 0), name).await.unwrap_err();
+            assert!(
+                matches!(err, GenerationError::RateLimited { .. }),
+                "{name}: {err:?}"
+            );
+            assert_eq!(err.retry_after(), Some(Duration::from_secs(7)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_is_not_retried() {
+        for name in ["openai", "anthropic"] {
+            let stub = StubServer::start([
+                StubResponse::json(401, json!({ "error": "bad key" })),
+                openai_reply("unreachable"),
+            ])
+            .await;
+
+            let err = complete(&providers(&stub, 2), name).await.unwrap_err();
+            assert!(
+                matches!(err, GenerationError::Auth { .. }),
+                "{name}: {err:?}"
+            );
+            assert_eq!(stub.requests().len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_bodies() {
+        let cases = [
+            ("openai", StubResponse::text(200, "not json")),
+            ("openai", StubResponse::json(200, json!({ "choices": [] }))),
+            ("anthropic", StubResponse::text(200, "<html>")),
+            (
+                "anthropic",
+                StubResponse::json(200, json!({ "content": [] })),
+            ),
+        ];
+        for (name, response) in cases {
+            let stub = StubServer::start([response]).await;
+
+            let err = complete(&providers(&stub, 2), name).await.unwrap_err();
+            assert!(
+                matches!(err, GenerationError::MalformedOutput { .. }),
+                "{name}: {err:?}"
+            );
+            assert_eq!(stub.requests().len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_retried() {
+        let stub =
+            StubServer::start([StubResponse::text(503, "busy"), anthropic_reply("Back")]).await;
+
+        let completion = complete(&providers(&stub, 1), "anthropic").await.unwrap();
+        assert_eq!(completion.text, "Back");
+        assert_eq!(stub.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_streams() {
+        async fn collect(providers: &LlmProviders, name: &str) -> (String, TokenUsage) {
+            let provider = providers.select_default(Some(name)).unwrap();
+            let request = CompletionRequest::new("Stream it");
+            let mut tokens = providers.stream(provider.as_ref(), &request).await.unwrap();
+            let (mut text, mut usage) = (String::new(), TokenUsage::default());
+            while let Some(delta) = tokens.next().await {
+                match delta.unwrap() {
+                    StreamDelta::Text(delta) => text.push_str(&delta),
+                    StreamDelta::Usage(delta) => usage += delta,
+                }
+            }
+            (text, usage)
+        }
+
+        let stub = StubServer::start([StubResponse::events([
+            r#"{"choices":[{"delta":{"content":"Hel"}}]}"#,
+            r#"{"choices":[{"delta":{"content":"lo"}}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":3,"completion_tokens":2}}"#,
+            "[DONE]",
+        ])])
+        .await;
+        let (text, usage) = collect(&providers(&stub, 0), "openai").await;
+        assert_eq!(text, "Hello");
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens), (3, 2));
+        assert_eq!(stub.requests()[0].body["stream"], true);
+
+        let stub = StubServer::start([StubResponse::events([
+            r#"{"type":"message_start","message":{"usage":{"input_tokens":4}}}"#,
+            r#"{"type":"content_block_delta","delta":{"text":"Hi "}}"#,
+            r#"{"type":"content_block_delta","delta":{"text":"there"}}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":2}}"#,
+            r#"{"type":"message_stop"}"#,
+        ])])
+        .await;
+        let (text, usage) = collect(&providers(&stub, 0), "anthropic").await;
+        assert_eq!(text, "Hi there");
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens), (4, 2));
+    }
+
+    #[tokio::test]
+    async fn test_provider_selection() {
+        let mut config = llm_config("anthropic", None);
+        config
+            .platform_providers
+            .insert("Copilot".to_string(), "fixture".to_string());
+        let providers =
+            LlmProviders::from_config(&config, PlatformRegistry::builtin(), "", "").unwrap();
+
+        assert_eq!(providers.names(), vec!["fixture"]);
+        assert_eq!(providers.select(None, "bing").unwrap().name(), "fixture");
+        assert!(matches!(
+            providers.select(None, "chatgpt"),
+            Err(GenerationError::NotConfigured(_))
+        ));
+        assert!(matches!(
+            providers.select_default(Some("anthropic")),
+            Err(GenerationError::NotConfigured(_))
+        ));
+        assert!(matches!(
+            providers.select_default(Some("nope")),
+            Err(GenerationError::UnknownProvider { .. })
+        ));
+
+        config
+            .platform_providers
+            .insert("chatgtp".to_string(), "fixture".to_string());
+        assert!(LlmProviders::from_config(&config, PlatformRegistry::builtin(), "", "").is_err());
+    }
+}
@@ -2,16 +2,31 @@ use axum::{
     routing::{delete, get, post, put},
     Router,
 };
+use asa_models::aeo::profile::PlatformRegistry;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 
 mod aeo_optimizer;
 mod ai_generator;
+mod article;
+mod auth;
 mod config;
+mod document;
+mod entities;
+mod events;
 mod handlers;
+mod llm;
+mod markup;
 mod models;
+mod prompts;
 mod repository;
-mod schema_generator;
+mod revisions;
+mod rule_engine;
+mod scheduler;
+mod schema_extractor;
+#[cfg(test)]
+mod testing;
 
 use config::Config;
 
@@ -31,14 +46,27 @@ async fn main() -> anyhow::Result<()> {
     let db_pool = asa_database::PostgresPool::new(&config.database.url).await?;
     let redis_client = asa_database::RedisClient::new(&config.redis.url).await?;
 
+    // Platform profiles shared by generation and optimization, and the ones
+    // request bodies' platform ids are checked against
+    let platforms = match &config.platform_profiles {
+        Some(path) => PlatformRegistry::builtin_with(path)?,
+        None => PlatformRegistry::builtin().clone(),
+    }
+    .install();
+    tracing::info!("Loaded {} platform profiles", platforms.profiles().len());
+
     // Create AI generator
-    let ai_generator = ai_generator::AIGenerator::new(
-        config.openai_api_key.clone(),
-        config.anthropic_api_key.clone(),
-    );
+    let providers = llm::LlmProviders::from_config(
+        &config.llm,
+        platforms,
+        &config.openai_api_key,
+        &config.anthropic_api_key,
+    )?;
+    tracing::info!("LLM providers: {}", providers.names().join(", "));
+    let ai_generator = ai_generator::AIGenerator::new(providers);
 
     // Create AEO optimizer
-    let aeo_optimizer = aeo_optimizer::AEOOptimizer::new();
+    let aeo_optimizer = aeo_optimizer::AEOOptimizer::new(Arc::new(platforms.clone()));
 
     // Create shared state
     let state = handlers::AppState {
@@ -49,6 +77,9 @@ async fn main() -> anyhow::Result<()> {
         config: config.clone(),
     };
 
+    // Scheduled publishing runs alongside the API on every replica
+    tokio::spawn(scheduler::run(state.clone()));
+
     // Build router
     let app = Router::new()
         .route("/health", get(handlers::health_check))
@@ -58,14 +89,49 @@ async fn main() -> anyhow::Result<()> {
         .route("/content/:id", get(handlers::get_content))
         .route("/content/:id", put(handlers::update_content))
         .route("/content/:id", delete(handlers::delete_content))
+        .route("/content/:id/revisions", get(handlers::list_revisions))
+        .route("/content/:id/revisions/diff", get(handlers::diff_revisions))
+        .route("/content/:id/revisions/:revision", get(handlers::get_revision))
+        .route("/content/:id/revisions/:revision/restore", post(handlers::restore_revision))
         // AI Generation
         .route("/generate", post(handlers::generate_content))
+        .route("/generate/stream", post(handlers::generate_stream))
         .route("/generate/outline", post(handlers::generate_outline))
+        .route("/generate/article", post(handlers::generate_article))
+        .route("/generate/jobs", get(handlers::list_generation_jobs))
+        .route("/generate/jobs/:id", get(handlers::get_generation_job))
+        .route("/generate/jobs/:id/draft", post(handlers::create_draft_from_job))
+        .route("/generate/costs", get(handlers::get_generation_costs))
+        // Prompt templates
+        .route("/prompts", get(handlers::list_prompt_templates))
+        .route("/prompts", post(handlers::create_prompt_template))
+        .route("/prompts/:id", get(handlers::get_prompt_template))
+        .route("/prompts/:id/activate", post(handlers::activate_prompt_template))
+        .route("/prompts/:id/deactivate", post(handlers::deactivate_prompt_template))
         // AEO Optimization
         .route("/optimize/:id", post(handlers::optimize_content))
         .route("/optimize/score/:id", get(handlers::get_optimization_score))
+        .route("/optimize/score/:id/history", get(handlers::get_score_history))
+        // Platform strategies
+        .route("/strategies", get(handlers::list_strategies))
+        .route("/strategies", post(handlers::create_strategy))
+        .route("/strategies/:id", get(handlers::get_strategy))
+        .route("/strategies/:id", put(handlers::update_strategy))
+        .route("/strategies/:id", delete(handlers::delete_strategy))
         // Schema.org
+        .route("/schema/validate", post(handlers::validate_schema))
+        .route("/schema/parse", post(handlers::parse_schema))
         .route("/schema/:id", get(handlers::get_schema_markup))
+        // Knowledge graph
+        .route("/content/:id/entities", get(handlers::get_content_entities))
+        .route("/content/:id/entities/extract", post(handlers::extract_content_entities))
+        .route("/entities", get(handlers::list_entities))
+        .route("/entities/path", get(handlers::get_entity_path))
+        .route("/entities/:id/neighbors", get(handlers::get_entity_neighbors))
+        // Editorial workflow
+        .route("/content/:id/workflow", get(handlers::get_workflow_history))
+        .route("/content/:id/workflow/:action", post(handlers::apply_workflow_action))
+        .route("/content/:id/schedule", put(handlers::schedule_content))
         // Publishing
         .route("/publish/:id", post(handlers::publish_content))
         .route("/unpublish/:id", post(handlers::unpublish_content))
@@ -0,0 +1,373 @@
+use asa_models::aeo::parser::SchemaParser;
+use asa_models::aeo::schema::SchemaType;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value};
+
+use crate::models::{MarkupSource, ParsedMarkup, ParsedSchema};
+
+/// Every schema.org item on an HTML page: JSON-LD blocks, then microdata,
+/// then RDFa. Malformed JSON-LD blocks are reported rather than dropped.
+pub fn parse_page(html: &str) -> ParsedMarkup {
+    let mut markup = ParsedMarkup::default();
+
+    let page = Html::parse_document(html);
+    let mut blocks = Vec::new();
+    for (i, block) in scripts(&page).into_iter().enumerate() {
+        match block {
+            Ok(block) => blocks.push(block),
+            Err(err) => markup
+                .errors
+                .push(format!("JSON-LD block {}: invalid JSON: {}", i, err)),
+        }
+    }
+
+    for (source, nodes) in [
+        (MarkupSource::JsonLd, blocks),
+        (MarkupSource::Microdata, microdata(&page)),
+        (MarkupSource::Rdfa, rdfa(&page)),
+    ] {
+        markup.items.extend(items(source, &Value::Array(nodes)));
+    }
+
+    markup
+}
+
+/// The items of a JSON-LD document, tagged with where they were found
+pub fn items(source: MarkupSource, document: &Value) -> Vec<ParsedSchema> {
+    SchemaParser::parse(document)
+        .into_iter()
+        .map(|schema| ParsedSchema {
+            source,
+            schema_type: schema.type_name().to_string(),
+            typed: !matches!(schema, SchemaType::Unknown(_)),
+            schema,
+        })
+        .collect()
+}
+
+/// Every `<script type="application/ld+json">` block in an HTML page, parsed
+pub fn json_ld_blocks(html: &str) -> Vec<Result<Value, serde_json::Error>> {
+    scripts(&Html::parse_document(html))
+}
+
+fn scripts(page: &Html) -> Vec<Result<Value, serde_json::Error>> {
+    let selector =
+        Selector::parse(r#"script[type="application/ld+json" i]"#).expect("valid selector");
+
+    page.select(&selector)
+        .map(|script| serde_json::from_str(&script.text().collect::<String>()))
+        .collect()
+}
+
+/// Top-level microdata items (`itemscope` elements that aren't a property of
+/// another item) as JSON-LD nodes
+fn microdata(page: &Html) -> Vec<Value> {
+    let selector = Selector::parse("[itemscope]:not([itemprop])").expect("valid selector");
+    page.select(&selector).map(microdata_item).collect()
+}
+
+fn microdata_item(element: ElementRef) -> Value {
+    let mut item = Map::new();
+    if let Some(types) = element.value().attr("itemtype") {
+        item.insert("@type".to_string(), type_value(types));
+    }
+    if let Some(id) = element.value().attr("itemid") {
+        item.insert("@id".to_string(), Value::String(id.to_string()));
+    }
+    microdata_properties(element, &mut item);
+    Value::Object(item)
+}
+
+/// Properties of an item are found in its descendants, stopping at nested
+/// items, which own theirs
+fn microdata_properties(element: ElementRef, item: &mut Map<String, Value>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        let scope = child.value().attr("itemscope").is_some();
+        if let Some(names) = child.value().attr("itemprop") {
+            let value = if scope {
+                microdata_item(child)
+            } else {
+                Value::String(microdata_value(child))
+            };
+            for name in names.split_whitespace() {
+                add_property(item, name, value.clone());
+            }
+        }
+        if !scope {
+            microdata_properties(child, item);
+        }
+    }
+}
+
+/// A property's value per the HTML microdata rules: an attribute for media,
+/// links and machine-readable elements, the text otherwise
+fn microdata_value(element: ElementRef) -> String {
+    let attr = |name| element.value().attr(name).map(str::to_string);
+    let value = match element.value().name() {
+        "meta" => attr("content"),
+        "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => attr("src"),
+        "a" | "area" | "link" => attr("href"),
+        "object" => attr("data"),
+        "data" | "meter" => attr("value"),
+        "time" => attr("datetime"),
+        _ => None,
+    };
+    value.unwrap_or_else(|| text(element))
+}
+
+/// Top-level RDFa Lite items (`typeof` elements that aren't a property of
+/// another item) as JSON-LD nodes
+fn rdfa(page: &Html) -> Vec<Value> {
+    let selector = Selector::parse("[typeof]:not([property])").expect("valid selector");
+    page.select(&selector).map(rdfa_item).collect()
+}
+
+fn rdfa_item(element: ElementRef) -> Value {
+    let mut item = Map::new();
+    if let Some(types) = element.value().attr("typeof") {
+        item.insert("@type".to_string(), type_value(types));
+    }
+    if let Some(id) = element.value().attr("resource").or(element.value().attr("about")) {
+        item.insert("@id".to_string(), Value::String(id.to_string()));
+    }
+    rdfa_properties(element, &mut item);
+    Value::Object(item)
+}
+
+fn rdfa_properties(element: ElementRef, item: &mut Map<String, Value>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        let scope = child.value().attr("typeof").is_some();
+        if let Some(names) = child.value().attr("property") {
+            let value = if scope {
+                rdfa_item(child)
+            } else {
+                Value::String(rdfa_value(child))
+            };
+            for name in names.split_whitespace() {
+                add_property(item, &vocabulary_term(name), value.clone());
+            }
+        }
+        if !scope {
+            rdfa_properties(child, item);
+        }
+    }
+}
+
+/// `content` wins in RDFa, then the element's link, then its text
+fn rdfa_value(element: ElementRef) -> String {
+    let node = element.value();
+    node.attr("content")
+        .or_else(|| node.attr("datetime"))
+        .or_else(|| node.attr("href"))
+        .or_else(|| node.attr("src"))
+        .or_else(|| node.attr("resource"))
+        .map(str::to_string)
+        .unwrap_or_else(|| text(element))
+}
+
+/// A repeated property becomes an array of its values
+fn add_property(item: &mut Map<String, Value>, name: &str, value: Value) {
+    match item.get_mut(name) {
+        None => {
+            item.insert(name.to_string(), value);
+        }
+        Some(Value::Array(values)) => values.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+    }
+}
+
+/// One type or an array of them, schema.org terms without their prefix
+fn type_value(types: &str) -> Value {
+    let mut types: Vec<Value> = types
+        .split_whitespace()
+        .map(|name| Value::String(vocabulary_term(name)))
+        .collect();
+    if types.len() == 1 {
+        types.remove(0)
+    } else {
+        Value::Array(types)
+    }
+}
+
+fn vocabulary_term(name: &str) -> String {
+    ["https://schema.org/", "http://schema.org/", "schema:"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name)
+        .to_string()
+}
+
+/// Text content with whitespace collapsed
+fn text(element: ElementRef) -> String {
+    element
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SchemaConfig;
+    use crate::schema_extractor::SchemaExtractor;
+    use asa_models::aeo::schema::SchemaGenerator;
+    use asa_models::content::{Content, ContentType};
+    use serde_json::json;
+
+    const JSON_LD: &str = r##"<html><head>
+<script type="application/LD+JSON">
+{"@context": "https://schema.org", "@graph": [
+  {"@type": "Article", "headline": "Beans", "description": "Light roast", "author": {"@type": "Person", "name": "Jane Smith"}, "datePublished": "2024-01-02T00:00:00Z", "image": [], "publisher": {"@type": "Organization", "name": "Acme", "logo": {"@type": "ImageObject", "url": "https://example.com/logo.png"}}},
+  {"@type": "FAQPage", "mainEntity": [{"@type": "Question", "name": "Why?", "acceptedAnswer": {"@type": "Answer", "text": "Because."}}]}
+]}
+</script>
+<script type="application/ld+json">{"@type": "Product", "name": </script>
+<script type="application/ld+json">{"@type": "Brewery", "name": "Acme Brewing"}</script>
+</head></html>"##;
+
+    const MICRODATA: &str = r##"<div itemscope itemtype="https://schema.org/Product">
+  <h1 itemprop="name">Beans</h1>
+  <p itemprop="description">Light
+     roast</p>
+  <img itemprop="image" src="https://example.com/beans.jpg">
+  <img itemprop="image" src="https://example.com/bag.jpg">
+  <div itemprop="offers" itemscope itemtype="https://schema.org/Offer">
+    <meta itemprop="price" content="12.50">
+    <meta itemprop="priceCurrency" content="EUR">
+    <link itemprop="availability" href="https://schema.org/InStock">
+  </div>
+</div>"##;
+
+    const RDFA: &str = r##"<div vocab="https://schema.org/" typeof="Person" resource="#jane">
+  <span property="name">Jane Smith</span>
+  <span property="jobTitle">Editor</span>
+  <a property="url" href="https://example.com/jane">site</a>
+  <a property="sameAs" href="https://x.com/jane">x</a>
+  <a property="schema:sameAs" href="https://github.com/jane">gh</a>
+</div>"##;
+
+    fn value(item: &ParsedSchema) -> Value {
+        SchemaGenerator::to_value(&item.schema).unwrap()
+    }
+
+    #[test]
+    fn test_json_ld_graph() {
+        let markup = parse_page(JSON_LD);
+        let types: Vec<(&str, bool)> = markup
+            .items
+            .iter()
+            .map(|item| (item.schema_type.as_str(), item.typed))
+            .collect();
+        assert_eq!(
+            types,
+            vec![("Article", true), ("FAQPage", true), ("Brewery", false)]
+        );
+        assert!(markup
+            .items
+            .iter()
+            .all(|item| item.source == MarkupSource::JsonLd));
+        assert_eq!(value(&markup.items[1])["mainEntity"][0]["name"], "Why?");
+    }
+
+    #[test]
+    fn test_malformed_json_ld_is_skipped() {
+        let markup = parse_page(JSON_LD);
+        // The broken block is reported and the blocks around it still parse
+        assert_eq!(markup.errors.len(), 1);
+        assert!(markup.errors[0].starts_with("JSON-LD block 1: invalid JSON"));
+        assert_eq!(markup.items.len(), 3);
+
+        let markup = parse_page("<script type=\"application/ld+json\">not json</script>");
+        assert!(markup.items.is_empty());
+        assert_eq!(markup.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_microdata() {
+        let markup = parse_page(MICRODATA);
+        assert_eq!(markup.items.len(), 1);
+        let item = &markup.items[0];
+        assert_eq!(item.source, MarkupSource::Microdata);
+        assert_eq!((item.schema_type.as_str(), item.typed), ("Product", true));
+
+        let product = value(item);
+        // Text is collapsed, media and links give their URLs and repeated
+        // properties become arrays
+        assert_eq!(product["description"], "Light roast");
+        assert_eq!(
+            product["image"],
+            json!([
+                "https://example.com/beans.jpg",
+                "https://example.com/bag.jpg"
+            ])
+        );
+        assert_eq!(product["offers"]["price"], "12.50");
+        assert_eq!(
+            product["offers"]["availability"],
+            "https://schema.org/InStock"
+        );
+    }
+
+    #[test]
+    fn test_rdfa() {
+        let markup = parse_page(RDFA);
+        assert_eq!(markup.items.len(), 1);
+        let item = &markup.items[0];
+        assert_eq!(item.source, MarkupSource::Rdfa);
+        assert_eq!((item.schema_type.as_str(), item.typed), ("Person", true));
+
+        let person = value(item);
+        assert_eq!(person["name"], "Jane Smith");
+        assert_eq!(person["jobTitle"], "Editor");
+        assert_eq!(person["url"], "https://example.com/jane");
+        // Prefixed terms are the same property
+        assert_eq!(
+            person["sameAs"],
+            json!(["https://x.com/jane", "https://github.com/jane"])
+        );
+    }
+
+    #[test]
+    fn test_generated_markup_round_trips() {
+        let extractor = SchemaExtractor::new(SchemaConfig {
+            site_url: "https://example.com".to_string(),
+            publisher_name: "Example".to_string(),
+            publisher_logo: "https://example.com/logo.png".to_string(),
+        });
+        let mut content = Content::new(
+            "How to brew coffee",
+            "how-to-brew-coffee",
+            "Brew a better cup.\n\n1. Grind the beans.\n2. Pour the water.\n\nHow long does it take?\nFour minutes.\n",
+            ContentType::Guide,
+            uuid::Uuid::new_v4(),
+        );
+        content.metadata.extra.insert(
+            "product".to_string(),
+            json!({"price": "12.50", "currency": "EUR"}),
+        );
+        let schemas = extractor.for_content(&content, "Jane Smith");
+        let expected: Vec<Value> = schemas
+            .iter()
+            .map(|schema| SchemaGenerator::to_value(schema).unwrap())
+            .collect();
+        assert_eq!(expected.len(), 4);
+
+        // One script per schema, and all of them in one @graph
+        let scripts = SchemaGenerator::generate_multiple(schemas.clone()).unwrap();
+        let graph = format!(
+            "<script type=\"application/ld+json\">{}</script>",
+            SchemaGenerator::graph(&schemas).unwrap()
+        );
+        for page in [scripts, graph] {
+            let markup = parse_page(&page);
+            assert!(markup.errors.is_empty());
+            assert!(markup.items.iter().all(|item| item.typed));
+            let found: Vec<Value> = markup.items.iter().map(value).collect();
+            assert_eq!(found, expected);
+        }
+    }
+}
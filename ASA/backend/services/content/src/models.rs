@@ -1,23 +1,30 @@
+use asa_models::aeo::optimization::{AEOScore, ScoreComponents};
+use asa_models::aeo::platform::OptimizationRule;
+use asa_models::aeo::profile::PlatformId;
+use asa_models::aeo::schema::SchemaType;
+use asa_models::{ContentMetadata, ContentStatus, ContentType};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::prompts::TemplateRef;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateContentRequest {
     pub title: String,
     pub slug: Option<String>,
     pub body: String,
-    pub content_type: String,
-    pub author_id: Uuid,
-    pub metadata: Option<serde_json::Value>,
+    pub content_type: ContentType,
+    pub metadata: Option<ContentMetadata>,
 }
 
+/// Word count, reading time and excerpt follow the body and can't be set
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateContentRequest {
     pub title: Option<String>,
     pub slug: Option<String>,
     pub body: Option<String>,
-    pub content_type: Option<String>,
-    pub metadata: Option<serde_json::Value>,
+    pub content_type: Option<ContentType>,
+    pub metadata: Option<ContentMetadata>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,63 +33,316 @@ pub struct ContentResponse {
     pub title: String,
     pub slug: String,
     pub body: String,
-    pub content_type: String,
-    pub status: String,
+    pub excerpt: Option<String>,
+    pub content_type: ContentType,
+    pub status: ContentStatus,
     pub author_id: Uuid,
-    pub metadata: Option<serde_json::Value>,
+    pub schema: Vec<SchemaType>, // cached schema.org markup, Article first
+    pub aeo_score: Option<AEOScore>, // latest score, None until first scored
+    pub metadata: ContentMetadata,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub published_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub generation_job_id: Option<Uuid>, // set when drafted from a generation job
+    pub scheduled_publish_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub scheduled_unpublish_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Fields of `ContentResponse` a listing can be narrowed to with `fields`
+pub const CONTENT_FIELDS: [&str; 17] = [
+    "id",
+    "title",
+    "slug",
+    "body",
+    "excerpt",
+    "content_type",
+    "status",
+    "author_id",
+    "schema",
+    "aeo_score",
+    "metadata",
+    "created_at",
+    "updated_at",
+    "published_at",
+    "generation_job_id",
+    "scheduled_publish_at",
+    "scheduled_unpublish_at",
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ContentListResponse {
-    pub items: Vec<ContentResponse>,
+    /// `ContentResponse`s, with only the requested fields
+    pub items: Vec<serde_json::Value>,
+    /// Content matching the filters, across all pages
     pub total: i64,
-    pub page: i64,
-    pub page_size: i64,
+    /// Pass as `cursor` for the next page; None on the last one
+    pub next_cursor: Option<String>,
 }
 
-// AI Generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentSort {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    PublishedAt,
+    Title,
+    AeoScore,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Content listing filters, sort and page. Date and score ranges are
+/// inclusive.
+#[derive(Debug, Default, Deserialize)]
+pub struct ContentListQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    pub sort: Option<ContentSort>,
+    pub order: Option<SortOrder>, // A to Z for titles, newest or highest first otherwise
+    pub status: Option<ContentStatus>,
+    pub content_type: Option<ContentType>,
+    pub author_id: Option<Uuid>,
+    /// Exact, case-sensitive match on one of `metadata.tags`
+    pub tag: Option<String>,
+    /// Exact, case-sensitive match on one of `metadata.keywords`, not a
+    /// search of the text
+    pub keyword: Option<String>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub published_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub published_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub min_score: Option<f64>,
+    pub max_score: Option<f64>,
+    /// Comma-separated `CONTENT_FIELDS`; everything when omitted
+    pub fields: Option<String>,
+}
+
+impl ContentListQuery {
+    pub fn sort_order(&self) -> (ContentSort, SortOrder) {
+        let sort = self.sort.unwrap_or_default();
+        let order = self.order.unwrap_or(match sort {
+            ContentSort::Title => SortOrder::Asc,
+            _ => SortOrder::Desc,
+        });
+        (sort, order)
+    }
+}
+
+/// Where a listing page ended: the sort key and id of its last item, for
+/// the same sort and order only. Content without a sort key (unpublished,
+/// unscored) comes last either way.
 #[derive(Debug, Serialize, Deserialize)]
+pub struct ContentCursor {
+    pub sort: ContentSort,
+    pub order: SortOrder,
+    pub value: serde_json::Value,
+    pub id: Uuid,
+}
+
+/// A cursor's sort key, typed by its sort
+#[derive(Debug, Clone)]
+pub enum SortValue {
+    Time(Option<chrono::DateTime<chrono::Utc>>),
+    Text(String),
+    Score(Option<f64>),
+}
+
+impl ContentCursor {
+    pub fn sort_value(&self) -> Result<SortValue, serde_json::Error> {
+        let value = self.value.clone();
+        Ok(match self.sort {
+            ContentSort::CreatedAt | ContentSort::UpdatedAt | ContentSort::PublishedAt => {
+                SortValue::Time(serde_json::from_value(value)?)
+            }
+            ContentSort::Title => SortValue::Text(serde_json::from_value(value)?),
+            ContentSort::AeoScore => SortValue::Score(serde_json::from_value(value)?),
+        })
+    }
+}
+
+// AI Generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerateContentRequest {
     pub topic: String,
-    pub content_type: String,
-    pub target_platform: String, // ChatGPT, Claude, Perplexity, Gemini, Bing
+    pub content_type: ContentType,
+    pub target_platform: PlatformId, // platform id, name or alias
     pub tone: Option<String>,
     pub length: Option<String>,
     pub keywords: Option<Vec<String>>,
+    pub provider: Option<String>, // overrides the platform's configured LLM provider
+    pub prompt_template_id: Option<Uuid>, // pins a template version instead of the active one
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GenerateOutlineRequest {
     pub topic: String,
-    pub content_type: String,
-    pub target_platform: String,
+    pub content_type: ContentType,
+    pub target_platform: PlatformId, // platform id, name or alias
+    pub provider: Option<String>,
+    pub prompt_template_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GenerateContentResponse {
+    pub job_id: Option<Uuid>,
     pub title: String,
     pub body: String,
     pub outline: Vec<String>,
     pub metadata: serde_json::Value,
+    /// Only present when the fixture provider wrote the content
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mock: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerateArticleRequest {
+    pub topic: String,
+    pub content_type: ContentType,
+    pub target_platform: PlatformId, // platform id, name or alias
+    pub title: Option<String>, // defaults to the topic
+    pub outline: Vec<String>, // section headings, usually an edited /generate/outline result
+    pub tone: Option<String>,
+    pub length: Option<String>,
+    pub keywords: Option<Vec<String>>,
+    pub provider: Option<String>,
+    pub prompt_template_id: Option<Uuid>, // pins a 'section' template version
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerateArticleResponse {
+    #[serde(flatten)]
+    pub article: GenerateContentResponse,
+    pub score: ScoreBreakdown,
+    /// FAQPage and HowTo markup for structure found in the article
+    pub schema: Vec<SchemaType>,
+}
+
+// Markup parsed from external pages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarkupSource {
+    JsonLd,
+    Microdata,
+    Rdfa,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParsedSchema {
+    pub source: MarkupSource,
+    pub schema_type: String,
+    /// False when the item didn't fit a typed schema and `schema` is the
+    /// markup as found
+    pub typed: bool,
+    pub schema: SchemaType,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ParsedMarkup {
+    pub items: Vec<ParsedSchema>,
+    /// Blocks that could not be read, e.g. malformed JSON-LD
+    pub errors: Vec<String>,
+}
+
+// Generation jobs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerationJobResponse {
+    pub id: Uuid,
+    pub kind: String,
+    pub topic: String,
+    pub content_type: ContentType,
+    pub platform: String,
+    pub provider: String,
+    pub model: String,
+    pub mock: bool,
+    pub system_prompt: String,
+    pub prompt: String,
+    pub prompt_template: Option<TemplateRef>,
+    pub prompt_tokens: Option<u32>,
+    pub completion_tokens: Option<u32>,
+    pub latency_ms: u64,
+    pub estimated_cost: Option<f64>, // USD
+    pub title: Option<String>,
+    pub output: String,
+    pub content_id: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CreateDraftRequest {
+    pub slug: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MonthlyCostResponse {
+    pub month: chrono::DateTime<chrono::Utc>,
+    pub provider: String,
+    pub model: String,
+    pub jobs: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub estimated_cost: f64, // USD
+    pub unpriced_jobs: i64,
+}
+
+// Prompt templates
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePromptTemplateRequest {
+    pub name: String,
+    pub content_type: Option<ContentType>, // None applies to every content type
+    pub platform: Option<PlatformId>, // None applies to every platform
+    pub description: Option<String>,
+    pub system_prompt: Option<String>, // defaults to the bundled system prompt
+    pub template: String,
+    pub activate: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PromptTemplateResponse {
+    pub id: Option<Uuid>, // None for bundled defaults
+    pub name: String,
+    pub content_type: Option<String>,
+    pub platform: Option<String>,
+    pub version: i32,
+    pub description: Option<String>,
+    pub system_prompt: String,
+    pub template: String,
+    pub active: bool,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 // AEO Optimization
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OptimizeContentRequest {
-    pub target_platform: String,
+    pub target_platform: PlatformId, // platform id, name or alias
+    pub tenant_id: Option<Uuid>, // selects the tenant's platform strategy
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OptimizationResponse {
     pub score: f64,
+    pub optimized_score: f64,
+    pub breakdown: ScoreBreakdown,
     pub improvements: Vec<Improvement>,
     pub optimized_content: String,
+    pub applied_rules: Vec<AppliedRule>,
+    pub diff: String, // unified diff from the stored body to optimized_content
     pub platform_specific_tips: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedRule {
+    pub name: String,
+    pub changes: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Improvement {
     pub category: String,
@@ -94,6 +354,297 @@ pub struct Improvement {
 pub struct OptimizationScoreResponse {
     pub overall_score: f64,
     pub platform_scores: serde_json::Value,
+    pub aeo_score: AEOScore,
+    pub breakdowns: Vec<ScoreBreakdown>,
     pub strengths: Vec<String>,
     pub weaknesses: Vec<String>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub platform: String,
+    pub score: f64,
+    pub rules: Vec<RuleResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScoreHistoryResponse {
+    pub content_id: Uuid,
+    pub items: Vec<ScoreHistoryEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScoreHistoryEntry {
+    pub id: Uuid,
+    pub overall: f64,
+    pub components: ScoreComponents,
+    pub calculated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Outcome of a single scoring rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleResult {
+    pub id: String,
+    pub category: String, // structure, quality, platform, readability
+    pub description: String,
+    pub suggestion: String,
+    pub weight: f64, // share of the category, 0-1
+    pub points_awarded: f64,
+    pub points_possible: f64,
+    pub evidence: Vec<Evidence>,
+}
+
+/// Text that triggered a rule, with its byte range in the body when it is a quote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Evidence {
+    pub text: String,
+    pub span: Option<std::ops::Range<usize>>,
+}
+
+// Platform strategies
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateStrategyRequest {
+    pub platform: PlatformId, // platform id, name or alias
+    pub tenant_id: Option<Uuid>, // None for the global default
+    pub priority: Option<u8>,
+    pub active: Option<bool>,
+    pub custom_rules: Option<Vec<OptimizationRule>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateStrategyRequest {
+    pub priority: Option<u8>,
+    pub active: Option<bool>,
+    pub custom_rules: Option<Vec<OptimizationRule>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StrategyResponse {
+    pub id: Uuid,
+    pub tenant_id: Option<Uuid>,
+    pub platform: String,
+    pub priority: u8,
+    pub active: bool,
+    pub custom_rules: Vec<OptimizationRule>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+// Knowledge graph
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtractEntitiesQuery {
+    pub llm: Option<bool>,
+    pub provider: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntitySearchQuery {
+    pub q: Option<String>,
+    #[serde(rename = "type")]
+    pub entity_type: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntityNeighborsQuery {
+    pub relationship_type: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntityPathQuery {
+    pub from: Uuid,
+    pub to: Uuid,
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub entity_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContentEntityResponse {
+    #[serde(flatten)]
+    pub entity: EntityResponse,
+    pub mentions: i32,
+    pub salience: f64,
+    pub source: String, // dictionary, pattern, llm
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContentEntitiesResponse {
+    pub content_id: Uuid,
+    pub entities: Vec<ContentEntityResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntityNeighborResponse {
+    pub entity: EntityResponse,
+    pub relationship_type: String,
+    pub weight: f64,
+    pub contents: i64, // content items the relationship was found in
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntityNeighborsResponse {
+    pub entity: EntityResponse,
+    pub neighbors: Vec<EntityNeighborResponse>,
+}
+
+/// An entity on a path, with the relationship that led to it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntityPathStep {
+    pub entity: EntityResponse,
+    pub relationship_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntityPathResponse {
+    pub hops: usize,
+    pub steps: Vec<EntityPathStep>,
+}
+
+// Revisions
+/// What wrote a content revision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevisionEvent {
+    Created,
+    Updated,
+    Published,
+    Unpublished,
+    Restored,
+}
+
+impl RevisionEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Published => "published",
+            Self::Unpublished => "unpublished",
+            Self::Restored => "restored",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionSummary {
+    pub revision: i32,
+    pub event: String,
+    pub title: String,
+    pub status: String,
+    pub word_count: usize,
+    pub aeo_score: Option<f64>,
+    pub restored_from: Option<i32>,
+    pub created_by: Option<Uuid>, // None for system changes and older revisions
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionListResponse {
+    pub content_id: Uuid,
+    pub items: Vec<RevisionSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionResponse {
+    pub content_id: Uuid,
+    pub revision: i32,
+    pub event: String,
+    pub title: String,
+    pub slug: String,
+    pub body: String,
+    pub content_type: ContentType,
+    pub status: String,
+    pub metadata: Option<serde_json::Value>,
+    pub aeo_score: Option<AEOScore>,
+    pub restored_from: Option<i32>,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionDiffQuery {
+    pub from: i32,
+    pub to: Option<i32>, // latest when omitted
+}
+
+/// A changed top-level field
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// A run of body text that was kept, added or removed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordChange {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// A metadata value that was added, removed or changed, by JSON pointer
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MetadataChange {
+    pub path: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionDiffResponse {
+    pub content_id: Uuid,
+    pub from: i32,
+    pub to: i32,
+    pub fields: Vec<FieldChange>,
+    pub body: Vec<WordChange>,
+    pub words_added: usize,
+    pub words_removed: usize,
+    pub metadata: Vec<MetadataChange>,
+    pub aeo_score_before: Option<f64>,
+    pub aeo_score_after: Option<f64>,
+}
+
+// Editorial workflow
+/// Optional body of workflow requests; a missing body means no comment
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WorkflowActionRequest {
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowEventResponse {
+    pub action: String,
+    pub from_status: String,
+    pub to_status: String,
+    pub user_id: Option<Uuid>,
+    pub username: Option<String>,
+    pub comment: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowHistoryResponse {
+    pub content_id: Uuid,
+    pub status: ContentStatus,
+    pub items: Vec<WorkflowEventResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScheduleContentRequest {
+    pub publish_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub unpublish_at: Option<chrono::DateTime<chrono::Utc>>,
+}
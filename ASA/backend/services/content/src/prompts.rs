@@ -0,0 +1,276 @@
+use chrono::{DateTime, Utc};
+use minijinja::{Environment, UndefinedBehavior};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const ARTICLE: &str = "article";
+pub const OUTLINE: &str = "outline";
+pub const SECTION: &str = "section";
+
+/// Template names the generator renders
+pub const TEMPLATE_NAMES: [&str; 3] = [ARTICLE, OUTLINE, SECTION];
+
+const DEFAULT_SYSTEM_PROMPT: &str = "You are an expert content creator specializing in AEO (Answer Engine Optimization). Create content that is optimized for AI platforms while remaining valuable for human readers.";
+
+/// A named, versioned prompt. Stored templates apply to a content type
+/// and/or platform, or to everything when those are unset; the bundled
+/// defaults are version 0 and have no id.
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub content_type: Option<String>,
+    pub platform: Option<String>, // platform id
+    pub version: i32,
+    pub description: Option<String>,
+    pub system_prompt: String,
+    /// Jinja template for the user prompt
+    pub template: String,
+    pub active: bool,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Values available to templates
+#[derive(Debug, Serialize)]
+pub struct PromptContext<'a> {
+    pub topic: &'a str,
+    pub content_type: &'a str,
+    /// Display name, e.g. "Perplexity"
+    pub platform: &'a str,
+    /// Stable id, e.g. "perplexity"
+    pub platform_id: &'a str,
+    pub tone: &'a str,
+    pub length: &'a str,
+    pub keywords: &'a [String],
+    /// Guidance from the platform profile
+    pub guidance: &'a str,
+}
+
+/// Values available to section templates: the brief plus where the section
+/// sits in the article and what has been written so far
+#[derive(Debug, Serialize)]
+pub struct SectionContext<'a> {
+    #[serde(flatten)]
+    pub brief: PromptContext<'a>,
+    pub title: &'a str,
+    pub outline: &'a [String],
+    /// Heading of the section to write
+    pub section: &'a str,
+    /// 1-based position of the section in the outline
+    pub position: usize,
+    pub previous: Vec<WrittenSection<'a>>,
+}
+
+/// A section already written. The body is left out for all but the most
+/// recent sections to keep prompts bounded on long articles.
+#[derive(Debug, Serialize)]
+pub struct WrittenSection<'a> {
+    pub heading: &'a str,
+    pub body: Option<&'a str>,
+}
+
+/// The template and version a prompt was rendered from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateRef {
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub version: i32,
+}
+
+impl PromptTemplate {
+    /// The bundled default for a template name
+    pub fn builtin(name: &str) -> Option<Self> {
+        let template = match name {
+            ARTICLE => include_str!("prompts/article.j2"),
+            OUTLINE => include_str!("prompts/outline.j2"),
+            SECTION => include_str!("prompts/section.j2"),
+            _ => return None,
+        };
+
+        Some(Self {
+            id: None,
+            name: name.to_string(),
+            content_type: None,
+            platform: None,
+            version: 0,
+            description: Some("Bundled default".to_string()),
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+            template: template.to_string(),
+            active: true,
+            created_at: None,
+        })
+    }
+
+    pub fn reference(&self) -> TemplateRef {
+        TemplateRef {
+            id: self.id,
+            name: self.name.clone(),
+            version: self.version,
+        }
+    }
+
+    pub fn render(&self, context: &impl Serialize) -> Result<String, minijinja::Error> {
+        environment().render_str(&self.template, context)
+    }
+
+    /// Check a template compiles and renders against sample values for its
+    /// name, so a typo is rejected on save rather than at generation time
+    pub fn validate(name: &str, template: &str) -> Result<(), String> {
+        let brief = PromptContext {
+            topic: "Answer engine optimization",
+            content_type: "article",
+            platform: "ChatGPT",
+            platform_id: "chatgpt",
+            tone: "professional",
+            length: "medium",
+            keywords: &["aeo".to_string()],
+            guidance: "Clear structure",
+        };
+        let rendered = if name == SECTION {
+            let outline = ["What is AEO?".to_string(), "Getting started".to_string()];
+            let sample = SectionContext {
+                brief,
+                title: "A guide to answer engine optimization",
+                outline: &outline,
+                section: &outline[1],
+                position: 2,
+                previous: vec![WrittenSection {
+                    heading: &outline[0],
+                    body: Some("AEO structures content so answer engines can quote it."),
+                }],
+            };
+            environment().render_str(template, &sample)
+        } else {
+            environment().render_str(template, &brief)
+        };
+        rendered.map(|_| ()).map_err(|err| err.to_string())
+    }
+}
+
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    // A misspelled variable should fail loudly, not render as an empty string
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.set_keep_trailing_newline(true);
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brief<'a>(keywords: &'a [String]) -> PromptContext<'a> {
+        PromptContext {
+            topic: "Cold brew at home",
+            content_type: "guide",
+            platform: "Perplexity",
+            platform_id: "perplexity",
+            tone: "friendly",
+            length: "short",
+            keywords,
+            guidance: "Cite your sources",
+        }
+    }
+
+    #[test]
+    fn test_builtin_templates() {
+        for name in TEMPLATE_NAMES {
+            let template = PromptTemplate::builtin(name).unwrap();
+            assert_eq!(template.name, name);
+            assert_eq!((template.id, template.version), (None, 0));
+            assert!(template.active);
+            assert_eq!(template.system_prompt, DEFAULT_SYSTEM_PROMPT);
+            assert!(PromptTemplate::validate(name, &template.template).is_ok());
+        }
+
+        let reference = PromptTemplate::builtin(OUTLINE).unwrap().reference();
+        assert_eq!((reference.name.as_str(), reference.version), (OUTLINE, 0));
+    }
+
+    #[test]
+    fn test_unknown_template_name() {
+        assert!(PromptTemplate::builtin("summary").is_none());
+        assert!(PromptTemplate::builtin("Article").is_none());
+    }
+
+    #[test]
+    fn test_render_article() {
+        let keywords = ["cold brew".to_string(), "ratio".to_string()];
+        let rendered = PromptTemplate::builtin(ARTICLE)
+            .unwrap()
+            .render(&brief(&keywords))
+            .unwrap();
+
+        assert!(rendered.starts_with("Generate comprehensive content optimized for Perplexity"));
+        for line in [
+            "Topic: Cold brew at home",
+            "Content Type: guide",
+            "Tone: friendly",
+            "Length: short",
+            "Keywords to include: cold brew, ratio",
+            "Cite your sources",
+        ] {
+            assert!(rendered.contains(line), "missing {:?}", line);
+        }
+
+        // The keyword line is left out when there are none
+        let rendered = PromptTemplate::builtin(ARTICLE)
+            .unwrap()
+            .render(&brief(&[]))
+            .unwrap();
+        assert!(!rendered.contains("Keywords"));
+    }
+
+    #[test]
+    fn test_render_section() {
+        let keywords = ["cold brew".to_string()];
+        let outline = [
+            "What is cold brew?".to_string(),
+            "Choosing beans".to_string(),
+            "Steeping".to_string(),
+        ];
+        let context = SectionContext {
+            brief: brief(&keywords),
+            title: "Cold brew, start to finish",
+            outline: &outline,
+            section: &outline[2],
+            position: 3,
+            previous: vec![
+                WrittenSection {
+                    heading: &outline[0],
+                    body: None,
+                },
+                WrittenSection {
+                    heading: &outline[1],
+                    body: Some("Use a medium roast."),
+                },
+            ],
+        };
+        let rendered = PromptTemplate::builtin(SECTION)
+            .unwrap()
+            .render(&context)
+            .unwrap();
+
+        assert!(rendered
+            .contains("a guide titled \"Cold brew, start to finish\", optimized for Perplexity"));
+        assert!(rendered.contains("Keywords to include where they fit: cold brew"));
+        assert!(rendered.contains("2. Choosing beans\n"));
+        assert!(rendered.contains("3. Steeping  <- this section\n"));
+        assert!(rendered.contains("## What is cold brew?\n(written, see outline)"));
+        assert!(rendered.contains("## Choosing beans\nUse a medium roast."));
+        assert!(rendered.contains("Now write section 3 of 3: \"Steeping\"."));
+    }
+
+    #[test]
+    fn test_undefined_variables_fail() {
+        let template = PromptTemplate {
+            template: "Write about {{ topik }}".to_string(),
+            ..PromptTemplate::builtin(ARTICLE).unwrap()
+        };
+        assert!(template.render(&brief(&[])).is_err());
+        assert!(PromptTemplate::validate(ARTICLE, &template.template).is_err());
+        // Section variables only exist for section templates
+        assert!(PromptTemplate::validate(OUTLINE, "{{ section }}").is_err());
+        assert!(PromptTemplate::validate(SECTION, "{{ section }}").is_ok());
+    }
+}
@@ -1,66 +1,1398 @@
 use asa_database::PostgresPool;
+use asa_models::aeo::optimization::{AEOScore, OptimizationLevel, ScoreComponents};
+use asa_models::aeo::platform::{OptimizationRule, PlatformStrategy};
+use asa_models::aeo::schema::{SchemaGenerator, SchemaType};
+use asa_models::{Content, ContentStatus, ContentType, WorkflowAction};
 use chrono::{DateTime, Utc};
-use sqlx::Row;
+use sqlx::types::Json;
+use sqlx::{Postgres, QueryBuilder, Row};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 use uuid::Uuid;
 
-pub struct Content {
+use crate::ai_generator::Provenance;
+use crate::entities::{DictionaryEntry, Extraction};
+use crate::llm::TokenUsage;
+use crate::models::{
+    ContentEntityResponse, ContentListQuery, ContentSort, SortOrder, SortValue, EntityNeighborResponse, EntityPathStep, EntityResponse, Improvement,
+    RevisionEvent, WorkflowEventResponse,
+};
+use crate::prompts::{PromptTemplate, TemplateRef};
+
+/// Columns read by `ContentRepository::row_to_content`: the content row with
+/// `$body` as its body, its cached schema.org markup (Article first) and its
+/// latest AEO score. Also valid in `RETURNING` on `content`.
+macro_rules! content_columns {
+    ($body:literal) => {
+        concat!(
+            "id, title, slug, ",
+            $body,
+            r#", excerpt, content_type, status, author_id, metadata, created_at,
+            updated_at, published_at, generation_job_id, scheduled_publish_at,
+            scheduled_unpublish_at,
+            (
+                SELECT COALESCE(
+                    jsonb_agg(m.json_ld ORDER BY m.schema_type <> 'Article', m.schema_type),
+                    '[]'
+                )
+                FROM schema_markup m
+                WHERE m.content_id = content.id
+            ) AS schema_json,
+            (
+                SELECT jsonb_build_object(
+                    'overall', s.overall_score::FLOAT8,
+                    'components', jsonb_build_object(
+                        'schema_markup', s.schema_markup::FLOAT8,
+                        'content_quality', s.content_quality::FLOAT8,
+                        'keyword_optimization', s.keyword_optimization::FLOAT8,
+                        'entity_coverage', s.entity_coverage::FLOAT8,
+                        'citation_potential', s.citation_potential::FLOAT8,
+                        'freshness', s.freshness::FLOAT8,
+                        'engagement', s.engagement::FLOAT8
+                    )
+                )
+                FROM aeo_scores s
+                WHERE s.content_id = content.id
+                ORDER BY s.calculated_at DESC
+                LIMIT 1
+            ) AS latest_score"#
+        )
+    };
+}
+
+const CONTENT_COLUMNS: &str = content_columns!("body");
+
+/// `CONTENT_COLUMNS` for listings that leave the body out
+const LISTING_COLUMNS: &str = content_columns!("''::TEXT AS body");
+
+/// A scheduled publish or unpublish taken up by the scheduler
+pub struct ScheduledTransition {
+    pub content_id: Uuid,
+    pub action: WorkflowAction,
+    pub from: ContentStatus,
+    pub scheduled_for: DateTime<Utc>,
+    pub scheduled_by: Option<Uuid>,
+    /// The content after the transition; None when its status no longer
+    /// allowed it and the schedule was dropped
+    pub content: Option<Content>,
+}
+
+pub struct ContentRepository {
+    db: PostgresPool,
+}
+
+impl ContentRepository {
+    pub fn new(db: PostgresPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, content: &Content) -> anyhow::Result<Content> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO content (id, title, slug, body, excerpt, content_type, status, author_id,
+                                 metadata, generation_job_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING {}
+            "#,
+            CONTENT_COLUMNS
+        ))
+        .bind(content.id)
+        .bind(&content.title)
+        .bind(&content.slug)
+        .bind(&content.body)
+        .bind(&content.excerpt)
+        .bind(content.content_type)
+        .bind(content.status)
+        .bind(content.author_id)
+        .bind(Json(&content.metadata))
+        .bind(content.generation_job_id)
+        .fetch_one(self.db.pool())
+        .await?;
+
+        self.row_to_content(row)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> anyhow::Result<Option<Content>> {
+        let result = sqlx::query(&format!(
+            "SELECT {} FROM content WHERE id = $1",
+            CONTENT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        result.map(|row| self.row_to_content(row)).transpose()
+    }
+
+    pub async fn find_by_slug(&self, slug: &str) -> anyhow::Result<Option<Content>> {
+        let result = sqlx::query(&format!(
+            "SELECT {} FROM content WHERE slug = $1",
+            CONTENT_COLUMNS
+        ))
+        .bind(slug)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        result.map(|row| self.row_to_content(row)).transpose()
+    }
+
+    /// A page of content matching the filters, after `after` (the sort key
+    /// and id the previous page ended on). `body` is left empty unless
+    /// `with_body`.
+    pub async fn list(
+        &self,
+        query: &ContentListQuery,
+        after: Option<(SortValue, Uuid)>,
+        limit: i64,
+        with_body: bool,
+    ) -> anyhow::Result<ContentPage> {
+        let (sort, order) = query.sort_order();
+        let column = match sort {
+            ContentSort::CreatedAt => "created_at",
+            ContentSort::UpdatedAt => "updated_at",
+            ContentSort::PublishedAt => "published_at",
+            ContentSort::Title => "title",
+            ContentSort::AeoScore => "aeo_score",
+        };
+        let (direction, cmp) = match order {
+            SortOrder::Asc => ("ASC", ">"),
+            SortOrder::Desc => ("DESC", "<"),
+        };
+
+        let columns = if with_body {
+            CONTENT_COLUMNS
+        } else {
+            LISTING_COLUMNS
+        };
+        let mut select = QueryBuilder::new(format!("SELECT {} FROM content", columns));
+        push_content_filters(&mut select, query);
+
+        // Rows after the cursor in (sort key, id) order, with rows that have
+        // no sort key after all the rest
+        match after {
+            Some((SortValue::Time(value), id)) => push_keyset(&mut select, column, cmp, value, id),
+            Some((SortValue::Text(value), id)) => {
+                push_keyset(&mut select, column, cmp, Some(value), id)
+            }
+            Some((SortValue::Score(value), id)) => push_keyset(&mut select, column, cmp, value, id),
+            None => {}
+        }
+        select
+            .push(format!(
+                " ORDER BY {} {} NULLS LAST, id {} LIMIT ",
+                column, direction, direction
+            ))
+            .push_bind(limit + 1);
+
+        let rows = select.build().fetch_all(self.db.pool()).await?;
+        let has_more = rows.len() as i64 > limit;
+        let items = rows
+            .into_iter()
+            .take(limit as usize)
+            .map(|row| self.row_to_content(row))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM content");
+        push_content_filters(&mut count, query);
+        let total: i64 = count.build_query_scalar().fetch_one(self.db.pool()).await?;
+
+        Ok(ContentPage {
+            items,
+            total,
+            has_more,
+        })
+    }
+
+    /// Save edits to the title, slug, body, content type, excerpt and
+    /// metadata. Status and schedules change through the workflow.
+    /// Save an edit and snapshot it as the content's next revision in one
+    /// transaction, so history can't miss a saved change. Returns the saved
+    /// content and its revision number; the revision's score is attached
+    /// once it has been calculated.
+    pub async fn update(
+        &self,
+        content: &Content,
+        event: RevisionEvent,
+        restored_from: Option<i32>,
+        created_by: Option<Uuid>,
+    ) -> anyhow::Result<(Content, i32)> {
+        let mut tx = self.db.pool().begin().await?;
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE content
+            SET title = $2, slug = $3, body = $4, excerpt = $5, content_type = $6,
+                metadata = $7, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            CONTENT_COLUMNS
+        ))
+        .bind(content.id)
+        .bind(&content.title)
+        .bind(&content.slug)
+        .bind(&content.body)
+        .bind(&content.excerpt)
+        .bind(content.content_type)
+        .bind(Json(&content.metadata))
+        .fetch_one(&mut *tx)
+        .await?;
+        let content = self.row_to_content(row)?;
+
+        let revision = insert_revision(&mut tx, &content, event, restored_from, created_by).await?;
+        tx.commit().await?;
+
+        Ok((content, revision))
+    }
+
+    pub async fn delete(&self, id: Uuid) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM content WHERE id = $1")
+            .bind(id)
+            .execute(self.db.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Move content between workflow statuses and audit it, as long as it
+    /// is still in `from`. None when it isn't, e.g. after a concurrent
+    /// transition. A comment leaves the status as it is.
+    pub async fn transition(
+        &self,
+        id: Uuid,
+        action: WorkflowAction,
+        from: ContentStatus,
+        to: ContentStatus,
+        user_id: Option<Uuid>,
+        comment: Option<&str>,
+    ) -> anyhow::Result<Option<Content>> {
+        let mut tx = self.db.pool().begin().await?;
+        let content = self
+            .apply_transition(&mut tx, id, action, from, to, user_id, comment)
+            .await?;
+        tx.commit().await?;
+        Ok(content)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn apply_transition(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+        action: WorkflowAction,
+        from: ContentStatus,
+        to: ContentStatus,
+        user_id: Option<Uuid>,
+        comment: Option<&str>,
+    ) -> anyhow::Result<Option<Content>> {
+        // Publishing stamps published_at, leaving published clears it. A
+        // schedule the transition makes moot is dropped, so it can't fire
+        // later on content that has moved on.
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE content
+            SET status = $2,
+                published_at = CASE WHEN $2 = status THEN published_at
+                                    WHEN $2 = 'published' THEN NOW()
+                                    WHEN status = 'published' THEN NULL
+                                    ELSE published_at END,
+                scheduled_publish_at = CASE WHEN $2 IN ('published', 'archived') THEN NULL
+                                            ELSE scheduled_publish_at END,
+                scheduled_unpublish_at = CASE WHEN $2 = status THEN scheduled_unpublish_at
+                                              WHEN status = 'published' THEN NULL
+                                              ELSE scheduled_unpublish_at END
+            WHERE id = $1 AND status = $3
+            RETURNING {}
+            "#,
+            CONTENT_COLUMNS
+        ))
+        .bind(id)
+        .bind(to.id())
+        .bind(from.id())
+        .fetch_optional(&mut **tx)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO content_workflow_events (content_id, action, from_status, to_status,
+                                                 user_id, comment)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(id)
+        .bind(action.id())
+        .bind(from.id())
+        .bind(to.id())
+        .bind(user_id)
+        .bind(comment)
+        .execute(&mut **tx)
+        .await?;
+
+        self.row_to_content(row).map(Some)
+    }
+
+    /// Set or clear when content is published and unpublished, and by whom
+    pub async fn schedule(
+        &self,
+        id: Uuid,
+        publish_at: Option<DateTime<Utc>>,
+        unpublish_at: Option<DateTime<Utc>>,
+        user_id: Uuid,
+    ) -> anyhow::Result<Content> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE content
+            SET scheduled_publish_at = $2, scheduled_unpublish_at = $3, scheduled_by = $4
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            CONTENT_COLUMNS
+        ))
+        .bind(id)
+        .bind(publish_at)
+        .bind(unpublish_at)
+        .bind(user_id)
+        .fetch_one(self.db.pool())
+        .await?;
+
+        self.row_to_content(row)
+    }
+
+    /// Carry out publishes and unpublishes that are due, at most `limit`.
+    ///
+    /// Due rows are locked with `FOR UPDATE SKIP LOCKED` and their schedule
+    /// is cleared in the same transaction as the status change, so with
+    /// several replicas polling each one fires exactly once. A schedule the
+    /// status no longer allows (e.g. publishing archived content) is
+    /// cleared without a transition.
+    pub async fn run_due_schedules(&self, limit: i64) -> anyhow::Result<Vec<ScheduledTransition>> {
+        let mut tx = self.db.pool().begin().await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, status, scheduled_by,
+                   CASE WHEN scheduled_publish_at <= NOW() THEN scheduled_publish_at END
+                       AS publish_due,
+                   CASE WHEN scheduled_unpublish_at <= NOW() THEN scheduled_unpublish_at END
+                       AS unpublish_due
+            FROM content
+            WHERE scheduled_publish_at <= NOW() OR scheduled_unpublish_at <= NOW()
+            ORDER BY LEAST(scheduled_publish_at, scheduled_unpublish_at)
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut fired = Vec::new();
+        for row in rows {
+            let id: Uuid = row.get("id");
+            let from: ContentStatus = row.try_get("status")?;
+            // Due by the database clock, the same one the rows were picked by
+            let publish_due: Option<DateTime<Utc>> = row.get("publish_due");
+            let unpublish_due: Option<DateTime<Utc>> = row.get("unpublish_due");
+            let scheduled_by: Option<Uuid> = row.get("scheduled_by");
+
+            // The earlier of the two when both are due; the other fires on
+            // the next poll
+            let (action, scheduled_for, column) = match (publish_due, unpublish_due) {
+                (Some(publish), Some(unpublish)) if unpublish < publish => {
+                    (WorkflowAction::Unpublish, unpublish, "scheduled_unpublish_at")
+                }
+                (Some(publish), _) => (WorkflowAction::Publish, publish, "scheduled_publish_at"),
+                (None, Some(unpublish)) => {
+                    (WorkflowAction::Unpublish, unpublish, "scheduled_unpublish_at")
+                }
+                (None, None) => continue,
+            };
+
+            sqlx::query(&format!("UPDATE content SET {} = NULL WHERE id = $1", column))
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+
+            let content = match action.next_status(from) {
+                Some(to) => {
+                    let comment = format!("Scheduled for {}", scheduled_for.to_rfc3339());
+                    self.apply_transition(&mut tx, id, action, from, to, scheduled_by, Some(&comment))
+                        .await?
+                }
+                None => None,
+            };
+
+            fired.push(ScheduledTransition {
+                content_id: id,
+                action,
+                from,
+                scheduled_for,
+                scheduled_by,
+                content,
+            });
+        }
+
+        tx.commit().await?;
+        Ok(fired)
+    }
+
+    /// Workflow transitions and comments for a content item, newest first
+    pub async fn workflow_history(&self, id: Uuid) -> anyhow::Result<Vec<WorkflowEventResponse>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.action, e.from_status, e.to_status, e.user_id, u.username, e.comment,
+                   e.created_at
+            FROM content_workflow_events e
+            LEFT JOIN users u ON u.id = e.user_id
+            WHERE e.content_id = $1
+            ORDER BY e.created_at DESC
+            "#,
+        )
+        .bind(id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| WorkflowEventResponse {
+                action: row.get("action"),
+                from_status: row.get("from_status"),
+                to_status: row.get("to_status"),
+                user_id: row.get("user_id"),
+                username: row.get("username"),
+                comment: row.get("comment"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    /// Schema.org types cached for a content item
+    pub async fn schema_types(&self, content_id: Uuid) -> anyhow::Result<Vec<String>> {
+        let types = sqlx::query_scalar(
+            "SELECT DISTINCT schema_type FROM schema_markup WHERE content_id = $1",
+        )
+        .bind(content_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(types)
+    }
+
+    /// Display name of a content author
+    pub async fn author_name(&self, author_id: Uuid) -> anyhow::Result<Option<String>> {
+        let name = sqlx::query_scalar("SELECT username FROM users WHERE id = $1")
+            .bind(author_id)
+            .fetch_optional(self.db.pool())
+            .await?;
+
+        Ok(name)
+    }
+
+    /// Role of a user, as stored in `users.role`
+    pub async fn user_role(&self, user_id: Uuid) -> anyhow::Result<Option<String>> {
+        let role = sqlx::query_scalar("SELECT role FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(self.db.pool())
+            .await?;
+
+        Ok(role)
+    }
+
+    /// Cached schema.org markup for a content item, Article first
+    pub async fn schema_markup(&self, content_id: Uuid) -> anyhow::Result<Vec<SchemaType>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT json_ld FROM schema_markup
+            WHERE content_id = $1
+            ORDER BY schema_type <> 'Article', schema_type
+            "#,
+        )
+        .bind(content_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.get("json_ld"))?))
+            .collect()
+    }
+
+    /// Replace the cached schema.org markup for a content item
+    pub async fn replace_schema_markup(
+        &self,
+        content_id: Uuid,
+        schemas: &[SchemaType],
+    ) -> anyhow::Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query("DELETE FROM schema_markup WHERE content_id = $1")
+            .bind(content_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for schema in schemas {
+            sqlx::query(
+                "INSERT INTO schema_markup (content_id, schema_type, json_ld) VALUES ($1, $2, $3)",
+            )
+            .bind(content_id)
+            .bind(schema.type_name())
+            .bind(SchemaGenerator::to_value(schema)?)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    fn row_to_content(&self, row: sqlx::postgres::PgRow) -> anyhow::Result<Content> {
+        let Json(metadata) = row.try_get("metadata")?;
+        let Json(schema) = row.try_get("schema_json")?;
+        let aeo_score: Option<Json<AEOScore>> = row.try_get("latest_score")?;
+        Ok(Content {
+            id: row.get("id"),
+            title: row.get("title"),
+            slug: row.get("slug"),
+            body: row.get("body"),
+            excerpt: row.get("excerpt"),
+            content_type: row.try_get("content_type")?,
+            status: row.try_get("status")?,
+            author_id: row.get("author_id"),
+            schema,
+            aeo_score: aeo_score.map(|Json(score)| score),
+            metadata,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            published_at: row.get("published_at"),
+            generation_job_id: row.get("generation_job_id"),
+            scheduled_publish_at: row.get("scheduled_publish_at"),
+            scheduled_unpublish_at: row.get("scheduled_unpublish_at"),
+        })
+    }
+}
+
+/// `WHERE` clause for content listings
+fn push_content_filters<'a>(builder: &mut QueryBuilder<'a, Postgres>, query: &'a ContentListQuery) {
+    builder.push(" WHERE TRUE");
+    if let Some(status) = query.status {
+        builder.push(" AND status = ").push_bind(status);
+    }
+    if let Some(content_type) = query.content_type {
+        builder.push(" AND content_type = ").push_bind(content_type);
+    }
+    if let Some(author_id) = query.author_id {
+        builder.push(" AND author_id = ").push_bind(author_id);
+    }
+    // Containment, so the metadata GIN index applies; it matches whole
+    // tags and keywords only
+    for (key, value) in [("tags", &query.tag), ("keywords", &query.keyword)] {
+        if let Some(value) = value {
+            builder
+                .push(format!(
+                    " AND metadata @> jsonb_build_object('{}', jsonb_build_array(",
+                    key
+                ))
+                .push_bind(value)
+                .push("::TEXT))");
+        }
+    }
+    for (column, from, to) in [
+        ("created_at", query.created_after, query.created_before),
+        ("updated_at", query.updated_after, query.updated_before),
+        ("published_at", query.published_after, query.published_before),
+    ] {
+        if let Some(from) = from {
+            builder.push(format!(" AND {} >= ", column)).push_bind(from);
+        }
+        if let Some(to) = to {
+            builder.push(format!(" AND {} <= ", column)).push_bind(to);
+        }
+    }
+    if let Some(min) = query.min_score {
+        builder.push(" AND aeo_score >= ").push_bind(min);
+    }
+    if let Some(max) = query.max_score {
+        builder.push(" AND aeo_score <= ").push_bind(max);
+    }
+}
+
+/// Rows after (`value`, `id`) in `column`, `id` order, `cmp` being `>` for
+/// ascending and `<` for descending. NULL keys sort last.
+fn push_keyset<'a, T>(
+    builder: &mut QueryBuilder<'a, Postgres>,
+    column: &str,
+    cmp: &str,
+    value: Option<T>,
+    id: Uuid,
+) where
+    T: 'a + Clone + Send + sqlx::Encode<'a, Postgres> + sqlx::Type<Postgres>,
+{
+    match value {
+        Some(value) => {
+            builder
+                .push(format!(" AND ({} {} ", column, cmp))
+                .push_bind(value.clone())
+                .push(format!(" OR ({} = ", column))
+                .push_bind(value)
+                .push(format!(" AND id {} ", cmp))
+                .push_bind(id)
+                .push(format!(") OR {} IS NULL)", column));
+        }
+        None => {
+            builder
+                .push(format!(" AND {} IS NULL AND id {} ", column, cmp))
+                .push_bind(id);
+        }
+    }
+}
+
+pub struct ContentPage {
+    pub items: Vec<Content>,
+    pub total: i64,
+    /// Whether there are items after these
+    pub has_more: bool,
+}
+
+pub struct RevisionRecord {
+    pub content_id: Uuid,
+    pub revision: i32,
+    pub event: String,
+    pub title: String,
+    pub slug: String,
+    pub body: String,
+    pub content_type: ContentType,
+    pub status: String,
+    pub metadata: Option<serde_json::Value>,
+    pub aeo_score: Option<AEOScore>,
+    pub restored_from: Option<i32>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct RevisionRepository {
+    db: PostgresPool,
+}
+
+/// Snapshot content as its next revision. The content row is locked first,
+/// so concurrent writers number their revisions one after the other instead
+/// of both taking the same MAX + 1.
+async fn insert_revision(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    content: &Content,
+    event: RevisionEvent,
+    restored_from: Option<i32>,
+    created_by: Option<Uuid>,
+) -> anyhow::Result<i32> {
+    sqlx::query("SELECT id FROM content WHERE id = $1 FOR UPDATE")
+        .bind(content.id)
+        .execute(&mut **tx)
+        .await?;
+
+    let revision = sqlx::query_scalar(
+        r#"
+        INSERT INTO content_revisions (content_id, revision, event, title, slug, body,
+                                       content_type, status, metadata, restored_from, created_by)
+        SELECT $1, COALESCE(MAX(revision), 0) + 1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+        FROM content_revisions
+        WHERE content_id = $1
+        RETURNING revision
+        "#,
+    )
+    .bind(content.id)
+    .bind(event.as_str())
+    .bind(&content.title)
+    .bind(&content.slug)
+    .bind(&content.body)
+    .bind(content.content_type)
+    .bind(content.status)
+    .bind(Json(&content.metadata))
+    .bind(restored_from)
+    .bind(created_by)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(revision)
+}
+
+const REVISION_COLUMNS: &str = r#"
+    r.content_id, r.revision, r.event, r.title, r.slug, r.body, r.content_type,
+    r.status, r.metadata, r.restored_from, r.created_by, r.created_at, s.overall_score::FLOAT8,
+    s.schema_markup::FLOAT8, s.content_quality::FLOAT8, s.keyword_optimization::FLOAT8,
+    s.entity_coverage::FLOAT8, s.citation_potential::FLOAT8, s.freshness::FLOAT8,
+    s.engagement::FLOAT8
+"#;
+
+impl RevisionRepository {
+    pub fn new(db: PostgresPool) -> Self {
+        Self { db }
+    }
+
+    /// Snapshot a content item as the next revision, written by `created_by`
+    pub async fn record(
+        &self,
+        content: &Content,
+        event: RevisionEvent,
+        restored_from: Option<i32>,
+        created_by: Option<Uuid>,
+    ) -> anyhow::Result<i32> {
+        let mut tx = self.db.pool().begin().await?;
+        let revision = insert_revision(&mut tx, content, event, restored_from, created_by).await?;
+        tx.commit().await?;
+        Ok(revision)
+    }
+
+    /// Record the AEO score calculated for a revision
+    pub async fn attach_score(
+        &self,
+        content_id: Uuid,
+        revision: i32,
+        aeo_score_id: Uuid,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "UPDATE content_revisions SET aeo_score_id = $3 WHERE content_id = $1 AND revision = $2",
+        )
+        .bind(content_id)
+        .bind(revision)
+        .bind(aeo_score_id)
+        .execute(self.db.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Revisions of a content item, newest first
+    pub async fn list(&self, content_id: Uuid, limit: i64) -> anyhow::Result<Vec<RevisionRecord>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM content_revisions r
+            LEFT JOIN aeo_scores s ON s.id = r.aeo_score_id
+            WHERE r.content_id = $1
+            ORDER BY r.revision DESC
+            LIMIT $2
+            "#,
+            REVISION_COLUMNS
+        ))
+        .bind(content_id)
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_record(row)).collect())
+    }
+
+    pub async fn find(&self, content_id: Uuid, revision: i32) -> anyhow::Result<Option<RevisionRecord>> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM content_revisions r
+            LEFT JOIN aeo_scores s ON s.id = r.aeo_score_id
+            WHERE r.content_id = $1 AND r.revision = $2
+            "#,
+            REVISION_COLUMNS
+        ))
+        .bind(content_id)
+        .bind(revision)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        Ok(row.map(|row| self.row_to_record(row)))
+    }
+
+    pub async fn latest(&self, content_id: Uuid) -> anyhow::Result<Option<RevisionRecord>> {
+        Ok(self.list(content_id, 1).await?.pop())
+    }
+
+    fn row_to_record(&self, row: sqlx::postgres::PgRow) -> RevisionRecord {
+        // Revisions from before scoring was recorded have no score
+        let overall: Option<f64> = row.get("overall_score");
+        RevisionRecord {
+            content_id: row.get("content_id"),
+            revision: row.get("revision"),
+            event: row.get("event"),
+            title: row.get("title"),
+            slug: row.get("slug"),
+            body: row.get("body"),
+            content_type: row.get("content_type"),
+            status: row.get("status"),
+            metadata: row.get("metadata"),
+            aeo_score: overall.map(|overall| AEOScore {
+                overall,
+                components: ScoreComponents {
+                    schema_markup: row.get("schema_markup"),
+                    content_quality: row.get("content_quality"),
+                    keyword_optimization: row.get("keyword_optimization"),
+                    entity_coverage: row.get("entity_coverage"),
+                    citation_potential: row.get("citation_potential"),
+                    freshness: row.get("freshness"),
+                    engagement: row.get("engagement"),
+                },
+            }),
+            restored_from: row.get("restored_from"),
+            created_by: row.get("created_by"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+pub struct ScoreRecord {
+    pub id: Uuid,
+    pub score: AEOScore,
+    pub calculated_at: DateTime<Utc>,
+}
+
+pub struct ScoreRepository {
+    db: PostgresPool,
+}
+
+impl ScoreRepository {
+    pub fn new(db: PostgresPool) -> Self {
+        Self { db }
+    }
+
+    /// Store a score calculation, which becomes the content's current score
+    pub async fn record(&self, content_id: Uuid, score: &AEOScore) -> anyhow::Result<ScoreRecord> {
+        let c = &score.components;
+        let mut tx = self.db.pool().begin().await?;
+        let row = sqlx::query(
+            r#"
+            INSERT INTO aeo_scores (content_id, overall_score, schema_markup, content_quality,
+                                    keyword_optimization, entity_coverage, citation_potential,
+                                    freshness, engagement)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, overall_score::FLOAT8, schema_markup::FLOAT8,
+                      content_quality::FLOAT8, keyword_optimization::FLOAT8,
+                      entity_coverage::FLOAT8, citation_potential::FLOAT8,
+                      freshness::FLOAT8, engagement::FLOAT8, calculated_at
+            "#,
+        )
+        .bind(content_id)
+        .bind(score.overall)
+        .bind(c.schema_markup)
+        .bind(c.content_quality)
+        .bind(c.keyword_optimization)
+        .bind(c.entity_coverage)
+        .bind(c.citation_potential)
+        .bind(c.freshness)
+        .bind(c.engagement)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE content SET aeo_score = $2 WHERE id = $1")
+            .bind(content_id)
+            .bind(score.overall)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(self.row_to_record(row))
+    }
+
+    /// Score calculations for a content item, newest first
+    pub async fn history(&self, content_id: Uuid, limit: i64) -> anyhow::Result<Vec<ScoreRecord>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, overall_score::FLOAT8, schema_markup::FLOAT8,
+                   content_quality::FLOAT8, keyword_optimization::FLOAT8,
+                   entity_coverage::FLOAT8, citation_potential::FLOAT8,
+                   freshness::FLOAT8, engagement::FLOAT8, calculated_at
+            FROM aeo_scores
+            WHERE content_id = $1
+            ORDER BY calculated_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(content_id)
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows.into_iter().map(|row| self.row_to_record(row)).collect())
+    }
+
+    fn row_to_record(&self, row: sqlx::postgres::PgRow) -> ScoreRecord {
+        ScoreRecord {
+            id: row.get("id"),
+            score: AEOScore {
+                overall: row.get("overall_score"),
+                components: ScoreComponents {
+                    schema_markup: row.get("schema_markup"),
+                    content_quality: row.get("content_quality"),
+                    keyword_optimization: row.get("keyword_optimization"),
+                    entity_coverage: row.get("entity_coverage"),
+                    citation_potential: row.get("citation_potential"),
+                    freshness: row.get("freshness"),
+                    engagement: row.get("engagement"),
+                },
+            },
+            calculated_at: row.get("calculated_at"),
+        }
+    }
+}
+
+pub struct OptimizationRepository {
+    db: PostgresPool,
+}
+
+impl OptimizationRepository {
+    pub fn new(db: PostgresPool) -> Self {
+        Self { db }
+    }
+
+    /// Record a ContentOptimization row
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record(
+        &self,
+        content_id: Uuid,
+        platform: &str,
+        level: OptimizationLevel,
+        before_score: f64,
+        after_score: f64,
+        applied_rules: &[String],
+        improvements: &[Improvement],
+    ) -> anyhow::Result<Uuid> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO content_optimizations
+                (content_id, platform, optimization_level, before_score, after_score,
+                 applied_rules, improvements)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id
+            "#,
+        )
+        .bind(content_id)
+        .bind(platform.to_lowercase())
+        .bind(format!("{:?}", level).to_lowercase())
+        .bind(before_score)
+        .bind(after_score)
+        .bind(serde_json::to_value(applied_rules)?)
+        .bind(serde_json::to_value(improvements)?)
+        .fetch_one(self.db.pool())
+        .await?;
+
+        Ok(row.get("id"))
+    }
+}
+
+pub struct StrategyRecord {
+    pub id: Uuid,
+    pub tenant_id: Option<Uuid>,
+    pub strategy: PlatformStrategy,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct StrategyRepository {
+    db: PostgresPool,
+}
+
+impl StrategyRepository {
+    pub fn new(db: PostgresPool) -> Self {
+        Self { db }
+    }
+
+    /// Insert a strategy, or `None` if the owner already has one for the
+    /// platform (including one created concurrently)
+    pub async fn create(
+        &self,
+        tenant_id: Option<Uuid>,
+        strategy: &PlatformStrategy,
+    ) -> anyhow::Result<Option<StrategyRecord>> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO platform_strategies (tenant_id, platform, priority, active, custom_rules)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT DO NOTHING
+            RETURNING id, tenant_id, platform, priority, active, custom_rules,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(&strategy.platform)
+        .bind(strategy.priority as i16)
+        .bind(strategy.active)
+        .bind(serde_json::to_value(&strategy.custom_rules)?)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.map(Self::row_to_record).transpose()
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> anyhow::Result<Option<StrategyRecord>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, tenant_id, platform, priority, active, custom_rules,
+                   created_at, updated_at
+            FROM platform_strategies
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.map(Self::row_to_record).transpose()
+    }
+
+    /// Strategies owned by a tenant, or the global defaults when `None`
+    pub async fn list(&self, tenant_id: Option<Uuid>) -> anyhow::Result<Vec<StrategyRecord>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, tenant_id, platform, priority, active, custom_rules,
+                   created_at, updated_at
+            FROM platform_strategies
+            WHERE tenant_id IS NOT DISTINCT FROM $1
+            ORDER BY priority DESC, platform
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.into_iter().map(Self::row_to_record).collect()
+    }
+
+    /// One strategy per platform: the tenant's own where it has one, otherwise
+    /// the global default. Inactive strategies are included so that a tenant
+    /// can switch a platform off.
+    pub async fn effective(&self, tenant_id: Option<Uuid>) -> anyhow::Result<Vec<PlatformStrategy>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT ON (platform)
+                   id, tenant_id, platform, priority, active, custom_rules,
+                   created_at, updated_at
+            FROM platform_strategies
+            WHERE tenant_id IS NULL OR tenant_id = $1
+            ORDER BY platform, tenant_id NULLS LAST
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Self::row_to_record(row).map(|record| record.strategy))
+            .collect()
+    }
+
+    pub async fn update(
+        &self,
+        id: Uuid,
+        priority: Option<u8>,
+        active: Option<bool>,
+        custom_rules: Option<&[OptimizationRule]>,
+    ) -> anyhow::Result<StrategyRecord> {
+        let custom_rules = custom_rules.map(serde_json::to_value).transpose()?;
+
+        let row = sqlx::query(
+            r#"
+            UPDATE platform_strategies
+            SET priority = COALESCE($2, priority),
+                active = COALESCE($3, active),
+                custom_rules = COALESCE($4, custom_rules),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, tenant_id, platform, priority, active, custom_rules,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(priority.map(|p| p as i16))
+        .bind(active)
+        .bind(custom_rules)
+        .fetch_one(self.db.pool())
+        .await?;
+
+        Self::row_to_record(row)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM platform_strategies WHERE id = $1")
+            .bind(id)
+            .execute(self.db.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    fn row_to_record(row: sqlx::postgres::PgRow) -> anyhow::Result<StrategyRecord> {
+        let custom_rules: Option<serde_json::Value> = row.get("custom_rules");
+        let priority: i16 = row.get("priority");
+
+        Ok(StrategyRecord {
+            id: row.get("id"),
+            tenant_id: row.get("tenant_id"),
+            strategy: PlatformStrategy {
+                platform: row.get("platform"),
+                priority: priority.clamp(0, u8::MAX as i16) as u8,
+                active: row.get("active"),
+                custom_rules: custom_rules
+                    .map(serde_json::from_value)
+                    .transpose()?
+                    .unwrap_or_default(),
+            },
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+}
+
+pub struct GenerationJob {
     pub id: Uuid,
-    pub title: String,
-    pub slug: String,
-    pub body: String,
-    pub content_type: String,
-    pub status: String,
-    pub author_id: Uuid,
-    pub metadata: Option<serde_json::Value>,
+    pub kind: String, // article, outline, stream
+    pub topic: String,
+    pub content_type: ContentType,
+    pub platform: String, // platform id
+    pub provenance: Provenance,
+    pub title: Option<String>,
+    pub output: String,
+    /// Content drafted from this job
+    pub content_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
-    pub published_at: Option<DateTime<Utc>>,
 }
 
-pub struct ContentRepository {
+/// Generation spend for one model in one month
+pub struct MonthlyCost {
+    pub month: DateTime<Utc>,
+    pub provider: String,
+    pub model: String,
+    pub jobs: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub estimated_cost: f64,
+    /// Jobs without a price, not included in `estimated_cost`
+    pub unpriced_jobs: i64,
+}
+
+pub struct GenerationJobRepository {
     db: PostgresPool,
 }
 
-impl ContentRepository {
+impl GenerationJobRepository {
     pub fn new(db: PostgresPool) -> Self {
         Self { db }
     }
 
-    pub async fn create(
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record(
         &self,
-        title: &str,
-        slug: &str,
-        body: &str,
-        content_type: &str,
-        author_id: Uuid,
-        metadata: Option<serde_json::Value>,
-    ) -> anyhow::Result<Content> {
+        kind: &str,
+        topic: &str,
+        content_type: ContentType,
+        platform: &str,
+        provenance: &Provenance,
+        title: Option<&str>,
+        output: &str,
+    ) -> anyhow::Result<Uuid> {
+        let template = provenance.template.as_ref();
         let row = sqlx::query(
             r#"
-            INSERT INTO content (title, slug, body, content_type, status, author_id, metadata)
-            VALUES ($1, $2, $3, $4, 'draft', $5, $6)
-            RETURNING id, title, slug, body, content_type, status, author_id, metadata,
-                      created_at, updated_at, published_at
+            INSERT INTO generation_jobs
+                (kind, topic, content_type, platform, provider, model, mock, system_prompt,
+                 prompt, prompt_tokens, completion_tokens, latency_ms, estimated_cost, title, output,
+                 prompt_template_id, prompt_template, prompt_template_version)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+            RETURNING id
             "#,
         )
+        .bind(kind)
+        .bind(topic)
+        .bind(content_type)
+        .bind(platform)
+        .bind(&provenance.provider)
+        .bind(&provenance.model)
+        .bind(provenance.mock)
+        .bind(&provenance.system_prompt)
+        .bind(&provenance.prompt)
+        .bind(provenance.usage.map(|usage| usage.prompt_tokens as i32))
+        .bind(provenance.usage.map(|usage| usage.completion_tokens as i32))
+        .bind(provenance.latency.as_millis().min(i32::MAX as u128) as i32)
+        .bind(provenance.estimated_cost)
         .bind(title)
-        .bind(slug)
-        .bind(body)
+        .bind(output)
+        .bind(template.and_then(|template| template.id))
+        .bind(template.map(|template| &template.name))
+        .bind(template.map(|template| template.version))
+        .fetch_one(self.db.pool())
+        .await?;
+
+        Ok(row.get("id"))
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> anyhow::Result<Option<GenerationJob>> {
+        let row = sqlx::query(
+            r#"
+            SELECT j.id, j.kind, j.topic, j.content_type, j.platform, j.provider, j.model,
+                   j.mock, j.system_prompt, j.prompt, j.prompt_tokens, j.completion_tokens,
+                   j.latency_ms, j.estimated_cost::FLOAT8, j.title, j.output, j.created_at,
+                   j.prompt_template_id, j.prompt_template, j.prompt_template_version,
+                   c.id AS content_id
+            FROM generation_jobs j
+            LEFT JOIN content c ON c.generation_job_id = j.id
+            WHERE j.id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        row.map(|row| self.row_to_job(row)).transpose()
+    }
+
+    /// Jobs in a time range, newest first
+    pub async fn list(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<GenerationJob>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT j.id, j.kind, j.topic, j.content_type, j.platform, j.provider, j.model,
+                   j.mock, j.system_prompt, j.prompt, j.prompt_tokens, j.completion_tokens,
+                   j.latency_ms, j.estimated_cost::FLOAT8, j.title, j.output, j.created_at,
+                   j.prompt_template_id, j.prompt_template, j.prompt_template_version,
+                   c.id AS content_id
+            FROM generation_jobs j
+            LEFT JOIN content c ON c.generation_job_id = j.id
+            WHERE ($1::TIMESTAMP IS NULL OR j.created_at >= $1)
+              AND ($2::TIMESTAMP IS NULL OR j.created_at < $2)
+            ORDER BY j.created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(from.map(|from| from.naive_utc()))
+        .bind(to.map(|to| to.naive_utc()))
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.into_iter().map(|row| self.row_to_job(row)).collect()
+    }
+
+    /// Spend per month and model, newest month first
+    pub async fn monthly_costs(&self, months: i32) -> anyhow::Result<Vec<MonthlyCost>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT date_trunc('month', created_at) AS month, provider, model,
+                   COUNT(*) AS jobs,
+                   COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+                   COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens,
+                   COALESCE(SUM(estimated_cost), 0)::FLOAT8 AS estimated_cost,
+                   COUNT(*) FILTER (WHERE estimated_cost IS NULL) AS unpriced_jobs
+            FROM generation_jobs
+            WHERE created_at >= date_trunc('month', NOW()) - make_interval(months => $1 - 1)
+            GROUP BY 1, 2, 3
+            ORDER BY 1 DESC, estimated_cost DESC
+            "#,
+        )
+        .bind(months)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| MonthlyCost {
+                month: row.get("month"),
+                provider: row.get("provider"),
+                model: row.get("model"),
+                jobs: row.get("jobs"),
+                prompt_tokens: row.get("prompt_tokens"),
+                completion_tokens: row.get("completion_tokens"),
+                estimated_cost: row.get("estimated_cost"),
+                unpriced_jobs: row.get("unpriced_jobs"),
+            })
+            .collect())
+    }
+
+    fn row_to_job(&self, row: sqlx::postgres::PgRow) -> anyhow::Result<GenerationJob> {
+        let prompt_tokens: Option<i32> = row.get("prompt_tokens");
+        let completion_tokens: Option<i32> = row.get("completion_tokens");
+        let latency_ms: i32 = row.get("latency_ms");
+        let template_name: Option<String> = row.get("prompt_template");
+        let template_version: Option<i32> = row.get("prompt_template_version");
+
+        Ok(GenerationJob {
+            id: row.get("id"),
+            kind: row.get("kind"),
+            topic: row.get("topic"),
+            content_type: row.get("content_type"),
+            platform: row.get("platform"),
+            provenance: Provenance {
+                provider: row.get("provider"),
+                model: row.get("model"),
+                mock: row.get("mock"),
+                system_prompt: row.get::<Option<String>, _>("system_prompt").unwrap_or_default(),
+                prompt: row.get("prompt"),
+                template: template_name.zip(template_version).map(|(name, version)| TemplateRef {
+                    id: row.get("prompt_template_id"),
+                    name,
+                    version,
+                }),
+                usage: prompt_tokens.zip(completion_tokens).map(|(prompt, completion)| TokenUsage {
+                    prompt_tokens: prompt as u32,
+                    completion_tokens: completion as u32,
+                }),
+                latency: Duration::from_millis(latency_ms as u64),
+                estimated_cost: row.get("estimated_cost"),
+            },
+            title: row.get("title"),
+            output: row.get("output"),
+            content_id: row.get("content_id"),
+            created_at: row.get("created_at"),
+        })
+    }
+}
+
+pub struct PromptTemplateRepository {
+    db: PostgresPool,
+}
+
+impl PromptTemplateRepository {
+    pub fn new(db: PostgresPool) -> Self {
+        Self { db }
+    }
+
+    /// Store the next version for the template's name, content type and platform
+    pub async fn create(
+        &self,
+        name: &str,
+        content_type: Option<ContentType>,
+        platform: Option<&str>,
+        description: Option<&str>,
+        system_prompt: &str,
+        template: &str,
+    ) -> anyhow::Result<PromptTemplate> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO prompt_templates
+                (name, content_type, platform, version, description, system_prompt, template)
+            SELECT $1, $2, $3, COALESCE(MAX(version), 0) + 1, $4, $5, $6
+            FROM prompt_templates
+            WHERE name = $1
+              AND content_type IS NOT DISTINCT FROM $2
+              AND platform IS NOT DISTINCT FROM $3
+            RETURNING id, name, content_type, platform, version, description, system_prompt,
+                      template, active, created_at
+            "#,
+        )
+        .bind(name)
         .bind(content_type)
-        .bind(author_id)
-        .bind(metadata)
+        .bind(platform)
+        .bind(description)
+        .bind(system_prompt)
+        .bind(template)
         .fetch_one(self.db.pool())
         .await?;
 
-        Ok(self.row_to_content(row))
+        self.row_to_template(row)
     }
 
-    pub async fn find_by_id(&self, id: Uuid) -> anyhow::Result<Option<Content>> {
-        let result = sqlx::query(
+    pub async fn find_by_id(&self, id: Uuid) -> anyhow::Result<Option<PromptTemplate>> {
+        let row = sqlx::query(
             r#"
-            SELECT id, title, slug, body, content_type, status, author_id, metadata,
-                   created_at, updated_at, published_at
-            FROM content
+            SELECT id, name, content_type, platform, version, description, system_prompt,
+                   template, active, created_at
+            FROM prompt_templates
             WHERE id = $1
             "#,
         )
@@ -68,193 +1400,511 @@ impl ContentRepository {
         .fetch_optional(self.db.pool())
         .await?;
 
-        Ok(result.map(|row| self.row_to_content(row)))
+        row.map(|row| self.row_to_template(row)).transpose()
     }
 
-    pub async fn find_by_slug(&self, slug: &str) -> anyhow::Result<Option<Content>> {
-        let result = sqlx::query(
+    pub async fn list(&self, name: Option<&str>) -> anyhow::Result<Vec<PromptTemplate>> {
+        let rows = sqlx::query(
             r#"
-            SELECT id, title, slug, body, content_type, status, author_id, metadata,
-                   created_at, updated_at, published_at
-            FROM content
-            WHERE slug = $1
+            SELECT id, name, content_type, platform, version, description, system_prompt,
+                   template, active, created_at
+            FROM prompt_templates
+            WHERE $1::TEXT IS NULL OR name = $1
+            ORDER BY name, content_type NULLS FIRST, platform NULLS FIRST, version DESC
             "#,
         )
-        .bind(slug)
+        .bind(name)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.into_iter().map(|row| self.row_to_template(row)).collect()
+    }
+
+    /// The active template for a generation: one scoped to both the content
+    /// type and platform wins over one scoped to the platform, then the
+    /// content type, then an unscoped one
+    pub async fn resolve(
+        &self,
+        name: &str,
+        content_type: ContentType,
+        platform: &str,
+    ) -> anyhow::Result<Option<PromptTemplate>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, content_type, platform, version, description, system_prompt,
+                   template, active, created_at
+            FROM prompt_templates
+            WHERE name = $1 AND active
+              AND (content_type IS NULL OR content_type = $2)
+              AND (platform IS NULL OR platform = $3)
+            ORDER BY platform IS NOT NULL DESC, content_type IS NOT NULL DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(name)
+        .bind(content_type)
+        .bind(platform)
         .fetch_optional(self.db.pool())
         .await?;
 
-        Ok(result.map(|row| self.row_to_content(row)))
+        row.map(|row| self.row_to_template(row)).transpose()
     }
 
-    pub async fn list(
+    /// Make a version the active one for its name, content type and platform
+    pub async fn activate(&self, id: Uuid) -> anyhow::Result<PromptTemplate> {
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE prompt_templates t
+            SET active = FALSE
+            FROM prompt_templates target
+            WHERE target.id = $1
+              AND t.active
+              AND t.name = target.name
+              AND t.content_type IS NOT DISTINCT FROM target.content_type
+              AND t.platform IS NOT DISTINCT FROM target.platform
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        let row = sqlx::query(
+            r#"
+            UPDATE prompt_templates
+            SET active = TRUE
+            WHERE id = $1
+            RETURNING id, name, content_type, platform, version, description, system_prompt,
+                      template, active, created_at
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        self.row_to_template(row)
+    }
+
+    /// Stop using a version; generation falls back to a less specific
+    /// template or the bundled default
+    pub async fn deactivate(&self, id: Uuid) -> anyhow::Result<PromptTemplate> {
+        let row = sqlx::query(
+            r#"
+            UPDATE prompt_templates
+            SET active = FALSE
+            WHERE id = $1
+            RETURNING id, name, content_type, platform, version, description, system_prompt,
+                      template, active, created_at
+            "#,
+        )
+        .bind(id)
+        .fetch_one(self.db.pool())
+        .await?;
+
+        self.row_to_template(row)
+    }
+
+    fn row_to_template(&self, row: sqlx::postgres::PgRow) -> anyhow::Result<PromptTemplate> {
+        Ok(PromptTemplate {
+            id: Some(row.get("id")),
+            name: row.get("name"),
+            content_type: row.get("content_type"),
+            platform: row.get("platform"),
+            version: row.get("version"),
+            description: row.get("description"),
+            system_prompt: row.get("system_prompt"),
+            template: row.get("template"),
+            active: row.get("active"),
+            created_at: row.get("created_at"),
+        })
+    }
+}
+
+pub struct KnowledgeGraphRepository {
+    db: PostgresPool,
+}
+
+impl KnowledgeGraphRepository {
+    pub fn new(db: PostgresPool) -> Self {
+        Self { db }
+    }
+
+    /// Entities worth recognizing in new content: ones found by the
+    /// dictionary or a model, or found in more than one content item
+    pub async fn dictionary(&self, limit: i64) -> anyhow::Result<Vec<DictionaryEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.name, e.entity_type
+            FROM kg_entities e
+            JOIN kg_content_entities ce ON ce.entity_id = e.id
+            GROUP BY e.id, e.name, e.entity_type
+            HAVING COUNT(*) >= 2 OR BOOL_OR(ce.source <> 'pattern')
+            ORDER BY COUNT(*) DESC, e.name
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DictionaryEntry {
+                name: row.get("name"),
+                entity_type: row.get("entity_type"),
+                aliases: vec![],
+            })
+            .collect())
+    }
+
+    /// Replace a content item's entity links and the relationships
+    /// extracted from it. Entities are shared, so they are only added.
+    pub async fn replace_content_entities(
         &self,
-        page: i64,
-        page_size: i64,
-        status_filter: Option<&str>,
-    ) -> anyhow::Result<(Vec<Content>, i64)> {
-        let offset = (page - 1) * page_size;
+        content_id: Uuid,
+        extraction: &Extraction,
+    ) -> anyhow::Result<()> {
+        let mut tx = self.db.pool().begin().await?;
+
+        sqlx::query("DELETE FROM kg_content_entities WHERE content_id = $1")
+            .bind(content_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM kg_relationships WHERE content_id = $1")
+            .bind(content_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut ids = HashMap::new();
+        for entity in &extraction.entities {
+            let id: Uuid = sqlx::query_scalar(
+                r#"
+                INSERT INTO kg_entities (name, entity_type)
+                VALUES ($1, $2)
+                ON CONFLICT (LOWER(name), entity_type) DO UPDATE SET updated_at = NOW()
+                RETURNING id
+                "#,
+            )
+            .bind(&entity.name)
+            .bind(&entity.entity_type)
+            .fetch_one(&mut *tx)
+            .await?;
 
-        let query = if let Some(status) = status_filter {
             sqlx::query(
                 r#"
-                SELECT id, title, slug, body, content_type, status, author_id, metadata,
-                       created_at, updated_at, published_at
-                FROM content
-                WHERE status = $1
-                ORDER BY created_at DESC
-                LIMIT $2 OFFSET $3
+                INSERT INTO kg_content_entities (content_id, entity_id, mentions, salience, source)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (content_id, entity_id) DO NOTHING
                 "#,
             )
-            .bind(status)
-            .bind(page_size)
-            .bind(offset)
-        } else {
+            .bind(content_id)
+            .bind(id)
+            .bind(entity.mentions as i32)
+            .bind(entity.salience)
+            .bind(entity.source.as_str())
+            .execute(&mut *tx)
+            .await?;
+
+            ids.insert(entity.name.as_str(), id);
+        }
+
+        for relationship in &extraction.relationships {
+            let (Some(from), Some(to)) = (
+                ids.get(relationship.from.as_str()),
+                ids.get(relationship.to.as_str()),
+            ) else {
+                continue;
+            };
             sqlx::query(
                 r#"
-                SELECT id, title, slug, body, content_type, status, author_id, metadata,
-                       created_at, updated_at, published_at
-                FROM content
-                ORDER BY created_at DESC
-                LIMIT $1 OFFSET $2
+                INSERT INTO kg_relationships
+                    (from_entity_id, to_entity_id, relationship_type, weight, content_id)
+                VALUES ($1, $2, $3, $4, $5)
                 "#,
             )
-            .bind(page_size)
-            .bind(offset)
-        };
+            .bind(from)
+            .bind(to)
+            .bind(&relationship.relationship_type)
+            .bind(relationship.weight)
+            .bind(content_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
 
-        let rows = query.fetch_all(self.db.pool()).await?;
-        let items: Vec<Content> = rows.into_iter().map(|row| self.row_to_content(row)).collect();
+    /// Entities a content item mentions, most salient first
+    pub async fn content_entities(&self, content_id: Uuid) -> anyhow::Result<Vec<ContentEntityResponse>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.id, e.name, e.entity_type, ce.mentions, ce.salience::FLOAT8 AS salience,
+                   ce.source
+            FROM kg_content_entities ce
+            JOIN kg_entities e ON e.id = ce.entity_id
+            WHERE ce.content_id = $1
+            ORDER BY ce.salience DESC, e.name
+            "#,
+        )
+        .bind(content_id)
+        .fetch_all(self.db.pool())
+        .await?;
 
-        // Get total count
-        let count_query = if let Some(status) = status_filter {
-            sqlx::query_scalar("SELECT COUNT(*) FROM content WHERE status = $1")
-                .bind(status)
-        } else {
-            sqlx::query_scalar("SELECT COUNT(*) FROM content")
-        };
+        Ok(rows
+            .into_iter()
+            .map(|row| ContentEntityResponse {
+                entity: self.row_to_entity(&row),
+                mentions: row.get("mentions"),
+                salience: row.get("salience"),
+                source: row.get("source"),
+            })
+            .collect())
+    }
 
-        let total: i64 = count_query.fetch_one(self.db.pool()).await?;
+    pub async fn find(&self, id: Uuid) -> anyhow::Result<Option<EntityResponse>> {
+        let row = sqlx::query("SELECT id, name, entity_type FROM kg_entities WHERE id = $1")
+            .bind(id)
+            .fetch_optional(self.db.pool())
+            .await?;
 
-        Ok((items, total))
+        Ok(row.map(|row| self.row_to_entity(&row)))
     }
 
-    pub async fn update(
+    /// Entities whose name contains `query`, most linked first
+    pub async fn search(
         &self,
-        id: Uuid,
-        title: Option<&str>,
-        slug: Option<&str>,
-        body: Option<&str>,
-        content_type: Option<&str>,
-        metadata: Option<serde_json::Value>,
-    ) -> anyhow::Result<Content> {
-        // Build dynamic update query
-        let mut query = String::from("UPDATE content SET updated_at = NOW()");
-        let mut bind_count = 1;
+        query: Option<&str>,
+        entity_type: Option<&str>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<EntityResponse>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.id, e.name, e.entity_type
+            FROM kg_entities e
+            LEFT JOIN kg_content_entities ce ON ce.entity_id = e.id
+            WHERE ($1::TEXT IS NULL OR e.name ILIKE '%' || $1 || '%')
+              AND ($2::TEXT IS NULL OR e.entity_type = $2)
+            GROUP BY e.id, e.name, e.entity_type
+            ORDER BY COUNT(ce.content_id) DESC, e.name
+            LIMIT $3
+            "#,
+        )
+        .bind(query)
+        .bind(entity_type)
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await?;
 
-        if title.is_some() {
-            query.push_str(&format!(", title = ${}", bind_count));
-            bind_count += 1;
-        }
-        if slug.is_some() {
-            query.push_str(&format!(", slug = ${}", bind_count));
-            bind_count += 1;
-        }
-        if body.is_some() {
-            query.push_str(&format!(", body = ${}", bind_count));
-            bind_count += 1;
-        }
-        if content_type.is_some() {
-            query.push_str(&format!(", content_type = ${}", bind_count));
-            bind_count += 1;
-        }
-        if metadata.is_some() {
-            query.push_str(&format!(", metadata = ${}", bind_count));
-            bind_count += 1;
-        }
+        Ok(rows.iter().map(|row| self.row_to_entity(row)).collect())
+    }
 
-        query.push_str(&format!(" WHERE id = ${}", bind_count));
-        query.push_str(" RETURNING id, title, slug, body, content_type, status, author_id, metadata, created_at, updated_at, published_at");
+    /// Entities related to one, in either direction, with the relationship
+    /// weights summed over the content they were found in
+    pub async fn neighbors(
+        &self,
+        entity_id: Uuid,
+        relationship_type: Option<&str>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<EntityNeighborResponse>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.id, e.name, e.entity_type, r.relationship_type,
+                   SUM(r.weight)::FLOAT8 AS weight, COUNT(DISTINCT r.content_id) AS contents
+            FROM kg_relationships r
+            JOIN kg_entities e ON e.id = CASE WHEN r.from_entity_id = $1
+                                              THEN r.to_entity_id ELSE r.from_entity_id END
+            WHERE (r.from_entity_id = $1 OR r.to_entity_id = $1)
+              AND ($2::TEXT IS NULL OR r.relationship_type = $2)
+            GROUP BY e.id, e.name, e.entity_type, r.relationship_type
+            ORDER BY weight DESC, e.name
+            LIMIT $3
+            "#,
+        )
+        .bind(entity_id)
+        .bind(relationship_type)
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await?;
 
-        let mut sql_query = sqlx::query(&query);
+        Ok(rows
+            .into_iter()
+            .map(|row| EntityNeighborResponse {
+                entity: self.row_to_entity(&row),
+                relationship_type: row.get("relationship_type"),
+                weight: row.get("weight"),
+                contents: row.get("contents"),
+            })
+            .collect())
+    }
 
-        if let Some(t) = title {
-            sql_query = sql_query.bind(t);
-        }
-        if let Some(s) = slug {
-            sql_query = sql_query.bind(s);
-        }
-        if let Some(b) = body {
-            sql_query = sql_query.bind(b);
-        }
-        if let Some(ct) = content_type {
-            sql_query = sql_query.bind(ct);
-        }
-        if let Some(m) = metadata {
-            sql_query = sql_query.bind(m);
+    /// Fewest hops from one entity to another, following relationships in
+    /// either direction. Breadth-first, one query per hop.
+    pub async fn shortest_path(
+        &self,
+        from: Uuid,
+        to: Uuid,
+        max_depth: usize,
+    ) -> anyhow::Result<Option<Vec<EntityPathStep>>> {
+        // Entity -> the entity it was reached from and the relationship used
+        let mut parents: HashMap<Uuid, Option<(Uuid, String)>> = HashMap::from([(from, None)]);
+        let mut frontier = vec![from];
+
+        for _ in 0..max_depth {
+            if frontier.is_empty() || parents.contains_key(&to) {
+                break;
+            }
+            let rows = sqlx::query(
+                r#"
+                SELECT from_entity_id, to_entity_id, relationship_type
+                FROM kg_relationships
+                WHERE from_entity_id = ANY($1) OR to_entity_id = ANY($1)
+                ORDER BY weight DESC
+                "#,
+            )
+            .bind(&frontier)
+            .fetch_all(self.db.pool())
+            .await?;
+
+            let current: HashSet<Uuid> = frontier.drain(..).collect();
+            for row in rows {
+                let ends: [Uuid; 2] = [row.get("from_entity_id"), row.get("to_entity_id")];
+                let relationship_type: String = row.get("relationship_type");
+                for (near, far) in [(ends[0], ends[1]), (ends[1], ends[0])] {
+                    if current.contains(&near) && !parents.contains_key(&far) {
+                        parents.insert(far, Some((near, relationship_type.clone())));
+                        frontier.push(far);
+                    }
+                }
+            }
         }
 
-        sql_query = sql_query.bind(id);
+        if !parents.contains_key(&to) {
+            return Ok(None);
+        }
 
-        let row = sql_query.fetch_one(self.db.pool()).await?;
-        Ok(self.row_to_content(row))
-    }
+        let mut hops = Vec::new();
+        let mut at = to;
+        while let Some(Some((previous, relationship_type))) = parents.get(&at) {
+            hops.push((at, Some(relationship_type.clone())));
+            at = *previous;
+        }
+        hops.push((from, None));
+        hops.reverse();
 
-    pub async fn delete(&self, id: Uuid) -> anyhow::Result<()> {
-        sqlx::query("DELETE FROM content WHERE id = $1")
-            .bind(id)
-            .execute(self.db.pool())
+        let ids: Vec<Uuid> = hops.iter().map(|(id, _)| *id).collect();
+        let rows = sqlx::query("SELECT id, name, entity_type FROM kg_entities WHERE id = ANY($1)")
+            .bind(&ids)
+            .fetch_all(self.db.pool())
             .await?;
-        Ok(())
+        let mut entities: HashMap<Uuid, EntityResponse> = rows
+            .iter()
+            .map(|row| (row.get("id"), self.row_to_entity(row)))
+            .collect();
+
+        Ok(Some(
+            hops.into_iter()
+                .filter_map(|(id, relationship_type)| {
+                    entities.remove(&id).map(|entity| EntityPathStep {
+                        entity,
+                        relationship_type,
+                    })
+                })
+                .collect(),
+        ))
     }
 
-    pub async fn publish(&self, id: Uuid) -> anyhow::Result<Content> {
-        let row = sqlx::query(
+    /// Names of a content item's most salient entities
+    pub async fn salient_names(&self, content_id: Uuid, limit: i64) -> anyhow::Result<Vec<String>> {
+        let names = sqlx::query_scalar(
             r#"
-            UPDATE content
-            SET status = 'published', published_at = NOW()
-            WHERE id = $1
-            RETURNING id, title, slug, body, content_type, status, author_id, metadata,
-                      created_at, updated_at, published_at
+            SELECT e.name
+            FROM kg_content_entities ce
+            JOIN kg_entities e ON e.id = ce.entity_id
+            WHERE ce.content_id = $1
+            ORDER BY ce.salience DESC, e.name
+            LIMIT $2
             "#,
         )
-        .bind(id)
-        .fetch_one(self.db.pool())
+        .bind(content_id)
+        .bind(limit)
+        .fetch_all(self.db.pool())
         .await?;
 
-        Ok(self.row_to_content(row))
+        Ok(names)
     }
 
-    pub async fn unpublish(&self, id: Uuid) -> anyhow::Result<Content> {
-        let row = sqlx::query(
+    /// Names of entities that other content relates to this item's entities
+    /// but that this item doesn't mention, strongest first
+    pub async fn related_names(&self, content_id: Uuid, limit: i64) -> anyhow::Result<Vec<String>> {
+        let rows = sqlx::query(
             r#"
-            UPDATE content
-            SET status = 'draft', published_at = NULL
-            WHERE id = $1
-            RETURNING id, title, slug, body, content_type, status, author_id, metadata,
-                      created_at, updated_at, published_at
+            SELECT e.name, SUM(r.weight)::FLOAT8 AS strength
+            FROM kg_content_entities ce
+            JOIN kg_relationships r
+              ON r.from_entity_id = ce.entity_id OR r.to_entity_id = ce.entity_id
+            JOIN kg_entities e ON e.id = CASE WHEN r.from_entity_id = ce.entity_id
+                                              THEN r.to_entity_id ELSE r.from_entity_id END
+            WHERE ce.content_id = $1
+              AND r.content_id IS DISTINCT FROM $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM kg_content_entities own
+                  WHERE own.content_id = $1 AND own.entity_id = e.id
+              )
+            GROUP BY e.id, e.name
+            ORDER BY strength DESC, e.name
+            LIMIT $2
             "#,
         )
-        .bind(id)
-        .fetch_one(self.db.pool())
+        .bind(content_id)
+        .bind(limit)
+        .fetch_all(self.db.pool())
         .await?;
 
-        Ok(self.row_to_content(row))
+        Ok(rows.into_iter().map(|row| row.get("name")).collect())
     }
 
-    fn row_to_content(&self, row: sqlx::postgres::PgRow) -> Content {
-        Content {
+    fn row_to_entity(&self, row: &sqlx::postgres::PgRow) -> EntityResponse {
+        EntityResponse {
             id: row.get("id"),
-            title: row.get("title"),
-            slug: row.get("slug"),
-            body: row.get("body"),
-            content_type: row.get("content_type"),
-            status: row.get("status"),
-            author_id: row.get("author_id"),
-            metadata: row.get("metadata"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-            published_at: row.get("published_at"),
+            name: row.get("name"),
+            entity_type: row.get("entity_type"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filters(query: &ContentListQuery) -> String {
+        let mut builder = QueryBuilder::new("SELECT id FROM content");
+        push_content_filters(&mut builder, query);
+        builder.sql().to_string()
+    }
+
+    #[test]
+    fn test_tag_and_keyword_filters_match_whole_values() {
+        let query = ContentListQuery {
+            tag: Some("coffee".to_string()),
+            keyword: Some("cold brew".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            filters(&query),
+            "SELECT id FROM content WHERE TRUE \
+             AND metadata @> jsonb_build_object('tags', jsonb_build_array($1::TEXT)) \
+             AND metadata @> jsonb_build_object('keywords', jsonb_build_array($2::TEXT))"
+        );
+    }
+
+    #[test]
+    fn test_no_filters() {
+        assert_eq!(
+            filters(&ContentListQuery::default()),
+            "SELECT id FROM content WHERE TRUE"
+        );
+    }
+}
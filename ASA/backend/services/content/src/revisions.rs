@@ -0,0 +1,307 @@
+use std::time::Duration;
+
+use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+
+use crate::models::{DiffOp, FieldChange, MetadataChange, RevisionDiffResponse, WordChange};
+use crate::repository::RevisionRecord;
+
+/// Long bodies that differ throughout fall back to a coarser diff rather
+/// than holding up the request
+const DIFF_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// What changed from one revision to another: top-level fields, the body
+/// word by word and metadata value by value
+pub fn diff(from: &RevisionRecord, to: &RevisionRecord) -> RevisionDiffResponse {
+    let fields = [
+        ("title", from.title.as_str(), to.title.as_str()),
+        ("slug", from.slug.as_str(), to.slug.as_str()),
+        ("content_type", from.content_type.id(), to.content_type.id()),
+        ("status", from.status.as_str(), to.status.as_str()),
+    ]
+    .into_iter()
+    .filter(|(_, before, after)| before != after)
+    .map(|(field, before, after)| FieldChange {
+        field: field.to_string(),
+        before: before.to_string(),
+        after: after.to_string(),
+    })
+    .collect();
+
+    let (body, words_added, words_removed) = diff_words(&from.body, &to.body);
+
+    let mut metadata = Vec::new();
+    diff_values(
+        "",
+        from.metadata.as_ref().unwrap_or(&Value::Null),
+        to.metadata.as_ref().unwrap_or(&Value::Null),
+        &mut metadata,
+    );
+
+    RevisionDiffResponse {
+        content_id: to.content_id,
+        from: from.revision,
+        to: to.revision,
+        fields,
+        body,
+        words_added,
+        words_removed,
+        metadata,
+        aeo_score_before: from.aeo_score.as_ref().map(|score| score.overall),
+        aeo_score_after: to.aeo_score.as_ref().map(|score| score.overall),
+    }
+}
+
+/// Runs of kept, added and removed text, with the number of words added
+/// and removed
+fn diff_words(before: &str, after: &str) -> (Vec<WordChange>, usize, usize) {
+    let diff = TextDiff::configure()
+        .timeout(DIFF_TIMEOUT)
+        .diff_words(before, after);
+
+    let mut changes: Vec<WordChange> = Vec::new();
+    let (mut added, mut removed) = (0, 0);
+    for change in diff.iter_all_changes() {
+        let op = match change.tag() {
+            ChangeTag::Equal => DiffOp::Equal,
+            ChangeTag::Insert => DiffOp::Insert,
+            ChangeTag::Delete => DiffOp::Delete,
+        };
+        let text = change.value();
+        if !text.trim().is_empty() {
+            match op {
+                DiffOp::Insert => added += 1,
+                DiffOp::Delete => removed += 1,
+                DiffOp::Equal => {}
+            }
+        }
+
+        match changes.last_mut() {
+            Some(last) if last.op == op => last.text.push_str(text),
+            _ => changes.push(WordChange {
+                op,
+                text: text.to_string(),
+            }),
+        }
+    }
+
+    (changes, added, removed)
+}
+
+/// Changed values between two metadata documents. Objects are compared key
+/// by key; anything else, arrays included, is one value.
+fn diff_values(path: &str, before: &Value, after: &Value, changes: &mut Vec<MetadataChange>) {
+    if before == after {
+        return;
+    }
+
+    if let (Value::Object(before), Value::Object(after)) = (before, after) {
+        let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let path = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+            match (before.get(key), after.get(key)) {
+                (Some(before), Some(after)) => diff_values(&path, before, after, changes),
+                (before, after) => changes.push(MetadataChange {
+                    path,
+                    before: before.cloned(),
+                    after: after.cloned(),
+                }),
+            }
+        }
+        return;
+    }
+
+    changes.push(MetadataChange {
+        path: path.to_string(), // "" for the whole document
+        before: Some(before.clone()).filter(|value| !value.is_null()),
+        after: Some(after.clone()).filter(|value| !value.is_null()),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asa_models::ContentType;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    /// Body runs as (op, text) pairs
+    fn runs(before: &str, after: &str) -> (Vec<(DiffOp, String)>, usize, usize) {
+        let (changes, added, removed) = diff_words(before, after);
+        let runs = changes
+            .into_iter()
+            .map(|change| (change.op, change.text))
+            .collect();
+        (runs, added, removed)
+    }
+
+    fn metadata(before: Value, after: Value) -> Vec<(String, Option<Value>, Option<Value>)> {
+        let mut changes = Vec::new();
+        diff_values("", &before, &after, &mut changes);
+        changes
+            .into_iter()
+            .map(|change| (change.path, change.before, change.after))
+            .collect()
+    }
+
+    fn record(revision: i32, title: &str, body: &str, metadata: Value) -> RevisionRecord {
+        RevisionRecord {
+            content_id: Uuid::nil(),
+            revision,
+            event: "updated".to_string(),
+            title: title.to_string(),
+            slug: "aeo".to_string(),
+            body: body.to_string(),
+            content_type: ContentType::Article,
+            status: "draft".to_string(),
+            metadata: Some(metadata),
+            aeo_score: None,
+            restored_from: None,
+            created_by: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_unchanged_body_is_one_equal_run() {
+        let (runs, added, removed) = runs(
+            "Answer engines cite sources.",
+            "Answer engines cite sources.",
+        );
+        assert_eq!(
+            runs,
+            vec![(DiffOp::Equal, "Answer engines cite sources.".to_string())]
+        );
+        assert_eq!((added, removed), (0, 0));
+    }
+
+    #[test]
+    fn test_inserted_words() {
+        let (runs, added, removed) = runs(
+            "Engines cite sources.",
+            "Engines often cite trusted sources.",
+        );
+        assert_eq!(
+            runs,
+            vec![
+                (DiffOp::Equal, "Engines ".to_string()),
+                (DiffOp::Insert, "often ".to_string()),
+                (DiffOp::Equal, "cite ".to_string()),
+                (DiffOp::Insert, "trusted ".to_string()),
+                (DiffOp::Equal, "sources.".to_string()),
+            ]
+        );
+        assert_eq!((added, removed), (2, 0));
+    }
+
+    #[test]
+    fn test_deleted_words() {
+        let (runs, added, removed) = runs("Engines often cite sources.", "Engines cite sources.");
+        assert_eq!(
+            runs,
+            vec![
+                (DiffOp::Equal, "Engines ".to_string()),
+                (DiffOp::Delete, "often ".to_string()),
+                (DiffOp::Equal, "cite sources.".to_string()),
+            ]
+        );
+        assert_eq!((added, removed), (0, 1));
+    }
+
+    #[test]
+    fn test_replaced_words() {
+        let (runs, added, removed) =
+            runs("Engines cite old sources.", "Engines cite fresh sources.");
+        assert_eq!(
+            runs,
+            vec![
+                (DiffOp::Equal, "Engines cite ".to_string()),
+                (DiffOp::Delete, "old".to_string()),
+                (DiffOp::Insert, "fresh".to_string()),
+                (DiffOp::Equal, " sources.".to_string()),
+            ]
+        );
+        assert_eq!((added, removed), (1, 1));
+    }
+
+    #[test]
+    fn test_metadata_added_removed_and_changed_keys() {
+        let changes = metadata(
+            json!({ "language": "en", "keywords": ["aeo"], "recipe": { "yield": "2" } }),
+            json!({ "keywords": ["aeo", "seo"], "recipe": { "yield": "4" }, "tags": ["guide"] }),
+        );
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    "/keywords".to_string(),
+                    Some(json!(["aeo"])),
+                    Some(json!(["aeo", "seo"]))
+                ),
+                ("/language".to_string(), Some(json!("en")), None),
+                (
+                    "/recipe/yield".to_string(),
+                    Some(json!("2")),
+                    Some(json!("4"))
+                ),
+                ("/tags".to_string(), None, Some(json!(["guide"]))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_metadata_pointer_escaping_and_unchanged() {
+        assert!(metadata(json!({ "a": 1 }), json!({ "a": 1 })).is_empty());
+        assert_eq!(
+            metadata(json!({}), json!({ "a/b~c": 1 })),
+            vec![("/a~1b~0c".to_string(), None, Some(json!(1)))]
+        );
+        // A document that wasn't an object is replaced whole
+        assert_eq!(
+            metadata(Value::Null, json!({ "a": 1 })),
+            vec![(String::new(), None, Some(json!({ "a": 1 })))]
+        );
+    }
+
+    #[test]
+    fn test_diff_reports_changed_fields_only() {
+        let from = record(
+            1,
+            "AEO",
+            "Engines cite sources.",
+            json!({ "language": "en" }),
+        );
+        let mut to = record(
+            2,
+            "AEO basics",
+            "Engines cite sources.",
+            json!({ "language": "de" }),
+        );
+        to.content_type = ContentType::Guide;
+
+        let diff = diff(&from, &to);
+        let fields: Vec<_> = diff
+            .fields
+            .iter()
+            .map(|change| {
+                (
+                    change.field.as_str(),
+                    change.before.as_str(),
+                    change.after.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("title", "AEO", "AEO basics"),
+                ("content_type", "article", "guide")
+            ]
+        );
+        assert_eq!((diff.from, diff.to), (1, 2));
+        assert_eq!((diff.words_added, diff.words_removed), (0, 0));
+        assert_eq!(diff.metadata.len(), 1);
+    }
+}
@@ -0,0 +1,471 @@
+use asa_models::aeo::platform::{OptimizationRule, RuleKind};
+use asa_models::aeo::profile::PlatformProfile;
+use regex::Regex;
+use similar::TextDiff;
+
+use crate::document::{Document, SourceFormat, Span};
+use crate::models::AppliedRule;
+
+const FAQ_HEADING: &str = "Frequently Asked Questions";
+
+#[derive(Debug, thiserror::Error)]
+pub enum RuleError {
+    #[error("rule '{rule}' has an invalid regex: {source}")]
+    InvalidPattern {
+        rule: String,
+        #[source]
+        source: regex::Error,
+    },
+    #[error("rule '{rule}' uses unknown transform '{transform}'")]
+    UnknownTransform { rule: String, transform: String },
+}
+
+/// Built-in document transforms available to `RuleKind::Structural` rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructuralTransform {
+    /// Insert a one-sentence summary right after the H1
+    TldrAfterH1,
+    /// Group runs of question H2 sections under an FAQ heading
+    QuestionHeadingsToFaq,
+}
+
+impl StructuralTransform {
+    pub const ALL: [StructuralTransform; 2] = [Self::TldrAfterH1, Self::QuestionHeadingsToFaq];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TldrAfterH1 => "tldr_after_h1",
+            Self::QuestionHeadingsToFaq => "question_headings_to_faq",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::TldrAfterH1 => "Insert a TL;DR after the H1",
+            Self::QuestionHeadingsToFaq => "Group question headings into an FAQ block",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.name() == name)
+    }
+}
+
+/// A rule after validation, ready to run
+enum CompiledRule<'a> {
+    Regex(&'a OptimizationRule, Regex),
+    Structural(&'a OptimizationRule, StructuralTransform),
+}
+
+pub struct RewriteResult {
+    pub body: String,
+    pub applied: Vec<AppliedRule>,
+    /// Unified diff from the original body to `body`
+    pub diff: String,
+}
+
+/// Deterministic, auditable rewrites driven by `OptimizationRule`s
+#[derive(Clone, Default)]
+pub struct RuleEngine;
+
+impl RuleEngine {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Check that a rule can be compiled
+    pub fn validate(&self, rule: &OptimizationRule) -> Result<(), RuleError> {
+        compile(rule).map(|_| ())
+    }
+
+    /// Apply the enabled rules in order. Rules that change nothing are not
+    /// reported as applied.
+    pub fn apply(&self, body: &str, rules: &[OptimizationRule]) -> Result<RewriteResult, RuleError> {
+        let compiled = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .map(compile)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut current = body.to_string();
+        let mut applied = Vec::new();
+
+        for rule in compiled {
+            let (name, next, changes) = match rule {
+                CompiledRule::Regex(rule, regex) => {
+                    let changes = regex.find_iter(&current).count();
+                    let next = regex.replace_all(&current, rule.replacement.as_str()).into_owned();
+                    (&rule.name, next, changes)
+                }
+                CompiledRule::Structural(rule, transform) => {
+                    let (next, changes) = apply_transform(&current, transform);
+                    (&rule.name, next, changes)
+                }
+            };
+
+            if changes > 0 && next != current {
+                applied.push(AppliedRule {
+                    name: name.clone(),
+                    changes,
+                });
+                current = next;
+            }
+        }
+
+        let diff = TextDiff::from_lines(body, &current)
+            .unified_diff()
+            .context_radius(2)
+            .header("original", "optimized")
+            .to_string();
+
+        Ok(RewriteResult {
+            body: current,
+            applied,
+            diff,
+        })
+    }
+
+    /// Rules applied when a platform has no custom strategy: blank-line
+    /// cleanup followed by the profile's structural transforms
+    pub fn default_rules(&self, profile: &PlatformProfile) -> Vec<OptimizationRule> {
+        let mut rules = vec![OptimizationRule {
+            name: "collapse_blank_lines".to_string(),
+            description: "Collapse runs of blank lines".to_string(),
+            kind: RuleKind::Regex,
+            pattern: r"\n{3,}".to_string(),
+            replacement: "\n\n".to_string(),
+            enabled: true,
+        }];
+
+        rules.extend(profile.transforms.iter().map(|name| OptimizationRule {
+            name: name.clone(),
+            description: StructuralTransform::from_name(name)
+                .map_or("Unknown transform", |transform| transform.description())
+                .to_string(),
+            kind: RuleKind::Structural,
+            pattern: name.clone(),
+            replacement: String::new(),
+            enabled: true,
+        }));
+
+        rules
+    }
+}
+
+fn compile(rule: &OptimizationRule) -> Result<CompiledRule<'_>, RuleError> {
+    match rule.kind {
+        RuleKind::Regex => Regex::new(&rule.pattern)
+            .map(|regex| CompiledRule::Regex(rule, regex))
+            .map_err(|source| RuleError::InvalidPattern {
+                rule: rule.name.clone(),
+                source,
+            }),
+        RuleKind::Structural => StructuralTransform::from_name(&rule.pattern)
+            .map(|transform| CompiledRule::Structural(rule, transform))
+            .ok_or_else(|| RuleError::UnknownTransform {
+                rule: rule.name.clone(),
+                transform: rule.pattern.clone(),
+            }),
+    }
+}
+
+/// Returns the rewritten body and the number of edits made
+fn apply_transform(body: &str, transform: StructuralTransform) -> (String, usize) {
+    let doc = Document::parse(body);
+    match transform {
+        StructuralTransform::TldrAfterH1 => insert_tldr(body, &doc),
+        StructuralTransform::QuestionHeadingsToFaq => group_faq(body, &doc),
+    }
+}
+
+fn insert_tldr(body: &str, doc: &Document) -> (String, usize) {
+    let already_present = doc
+        .paragraphs()
+        .any(|(text, _)| text.trim_start_matches('*').starts_with("TL;DR"));
+    let h1 = doc.headings_at(1).next();
+    let summary = doc
+        .paragraphs()
+        .map(|(text, _)| first_sentence(text))
+        .find(|sentence| !sentence.is_empty());
+
+    let (Some((_, h1_span)), Some(summary), false) = (h1, summary, already_present) else {
+        return (body.to_string(), 0);
+    };
+
+    let (insert_at, block) = match doc.format {
+        SourceFormat::Markdown => (h1_span.end, format!("\n**TL;DR:** {}\n\n", summary)),
+        SourceFormat::Html => match body[h1_span.start..].find("</h1>") {
+            Some(offset) => (
+                h1_span.start + offset + "</h1>".len(),
+                format!("\n<p><strong>TL;DR:</strong> {}</p>", summary),
+            ),
+            None => return (body.to_string(), 0),
+        },
+    };
+
+    let mut out = String::with_capacity(body.len() + block.len());
+    out.push_str(body[..insert_at].trim_end_matches('\n'));
+    out.push('\n');
+    out.push_str(&block);
+    out.push_str(body[insert_at..].trim_start_matches('\n'));
+    (out, 1)
+}
+
+/// Runs of two or more consecutive H2 sections whose heading is a question are
+/// demoted to H3 and placed under a single FAQ H2; their subsections move
+/// down a level with them. Only heading markers are rewritten, so inline
+/// markup in the headings survives. Markdown only.
+fn group_faq(body: &str, doc: &Document) -> (String, usize) {
+    if doc.format != SourceFormat::Markdown
+        || doc.headings().any(|(_, text, _)| text.eq_ignore_ascii_case(FAQ_HEADING))
+    {
+        return (body.to_string(), 0);
+    }
+
+    let headings: Vec<(u8, &str, &Span)> = doc.headings().collect();
+    let is_question = |level: u8, text: &str| level == 2 && text.ends_with('?');
+
+    // Indices into `headings` of the top-level (H1/H2) sections
+    let sections: Vec<usize> = (0..headings.len())
+        .filter(|&index| headings[index].0 <= 2)
+        .collect();
+
+    let mut edits: Vec<(Span, String)> = Vec::new();
+    let mut changes = 0;
+    let mut i = 0;
+    while i < sections.len() {
+        let run_end = sections[i..]
+            .iter()
+            .position(|&index| !is_question(headings[index].0, headings[index].1))
+            .map_or(sections.len(), |offset| i + offset);
+
+        if run_end - i >= 2 {
+            let faq_at = headings[sections[i]].2.start;
+            edits.push((faq_at..faq_at, format!("## {}\n\n", FAQ_HEADING)));
+
+            // Each question and every heading under it, up to the next H1/H2
+            let first = sections[i];
+            let last = sections.get(run_end).copied().unwrap_or(headings.len());
+            for &(level, _, span) in &headings[first..last] {
+                if let Some(edit) = demote(body, level, span) {
+                    edits.push(edit);
+                    changes += 1;
+                }
+            }
+        }
+        i = run_end.max(i + 1);
+    }
+
+    if changes == 0 {
+        return (body.to_string(), 0);
+    }
+
+    let mut out = String::with_capacity(body.len() + FAQ_HEADING.len() + edits.len() + 8);
+    let mut cursor = 0;
+    for (span, replacement) in edits {
+        out.push_str(&body[cursor..span.start]);
+        out.push_str(&replacement);
+        cursor = span.end;
+    }
+    out.push_str(&body[cursor..]);
+
+    (out, changes)
+}
+
+/// The edit moving a heading one level down. ATX headings get another `#`;
+/// setext headings, which only go to H2, are rewritten as ATX with their
+/// text as written. H6 has nowhere to go.
+fn demote(body: &str, level: u8, span: &Span) -> Option<(Span, String)> {
+    if level >= 6 {
+        return None;
+    }
+    let source = &body[span.clone()];
+    let indent = source.len() - source.trim_start().len();
+
+    if source.trim_start().starts_with('#') {
+        let at = span.start + indent;
+        return Some((at..at, "#".to_string()));
+    }
+
+    let trimmed = source.trim_end();
+    let text = trimmed.rsplit_once('\n').map_or(trimmed, |(text, _)| text);
+    let newline = &source[trimmed.len()..];
+    Some((
+        span.clone(),
+        format!("{} {}{}", "#".repeat(level as usize + 1), text.trim(), newline),
+    ))
+}
+
+fn first_sentence(text: &str) -> String {
+    let end = text
+        .char_indices()
+        .find(|(i, c)| {
+            matches!(c, '.' | '!' | '?')
+                && text[i + c.len_utf8()..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace)
+        })
+        .map_or(text.len(), |(i, c)| i + c.len_utf8());
+
+    text[..end].trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, kind: RuleKind, pattern: &str, replacement: &str) -> OptimizationRule {
+        OptimizationRule {
+            name: name.to_string(),
+            description: String::new(),
+            kind,
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            enabled: true,
+        }
+    }
+
+    fn faq(body: &str) -> (String, usize) {
+        apply_transform(body, StructuralTransform::QuestionHeadingsToFaq)
+    }
+
+    #[test]
+    fn test_regex_rules_apply_in_order_with_diff() {
+        let rules = [
+            rule("one", RuleKind::Regex, "colour", "color"),
+            rule("noop", RuleKind::Regex, "absent", "x"),
+            rule("two", RuleKind::Regex, "color", "hue"),
+        ];
+
+        let result = RuleEngine::new()
+            .apply("a colour\nb colour\n", &rules)
+            .unwrap();
+        assert_eq!(result.body, "a hue\nb hue\n");
+        let applied: Vec<_> = result
+            .applied
+            .iter()
+            .map(|r| (r.name.as_str(), r.changes))
+            .collect();
+        assert_eq!(applied, vec![("one", 2), ("two", 2)]);
+        assert!(result.diff.contains("-a colour\n") && result.diff.contains("+a hue\n"));
+    }
+
+    #[test]
+    fn test_disabled_and_invalid_rules() {
+        let mut disabled = rule("off", RuleKind::Regex, "a", "b");
+        disabled.enabled = false;
+        let result = RuleEngine::new().apply("a", &[disabled]).unwrap();
+        assert_eq!(result.body, "a");
+        assert!(result.applied.is_empty());
+
+        let engine = RuleEngine::new();
+        assert!(matches!(
+            engine.validate(&rule("bad", RuleKind::Regex, "(", "")),
+            Err(RuleError::InvalidPattern { .. })
+        ));
+        assert!(matches!(
+            engine.validate(&rule("bad", RuleKind::Structural, "shuffle", "")),
+            Err(RuleError::UnknownTransform { .. })
+        ));
+    }
+
+    #[test]
+    fn test_tldr_after_h1() {
+        let (body, changes) = apply_transform(
+            "# Title\n\nAEO is optimization for answers. It matters.\n",
+            StructuralTransform::TldrAfterH1,
+        );
+        assert_eq!(changes, 1);
+        assert_eq!(
+            body,
+            "# Title\n\n**TL;DR:** AEO is optimization for answers.\n\nAEO is optimization for answers. It matters.\n"
+        );
+
+        // Already summarized
+        let (_, changes) = apply_transform(&body, StructuralTransform::TldrAfterH1);
+        assert_eq!(changes, 0);
+
+        let (body, changes) = apply_transform(
+            "<h1>Title</h1><p>Short answer. More.</p>",
+            StructuralTransform::TldrAfterH1,
+        );
+        assert_eq!(changes, 1);
+        assert!(body.starts_with("<h1>Title</h1>\n\n<p><strong>TL;DR:</strong> Short answer.</p>"));
+    }
+
+    #[test]
+    fn test_faq_groups_question_runs() {
+        let (body, changes) =
+            faq("# T\n\n## What is it?\n\nA.\n\n## Why?\n\nB.\n\n## Next\n\nC.\n");
+        assert_eq!(changes, 2);
+        assert_eq!(
+            body,
+            "# T\n\n## Frequently Asked Questions\n\n### What is it?\n\nA.\n\n### Why?\n\nB.\n\n## Next\n\nC.\n"
+        );
+    }
+
+    #[test]
+    fn test_faq_demotes_subsections_with_their_question() {
+        let (body, _) = faq(
+            "## How?\n\n### Step one\n\n#### Detail\n\n## Who?\n\nX.\n\n## After\n\n### Kept\n",
+        );
+        let doc = Document::parse(&body);
+        let headings: Vec<_> = doc
+            .headings()
+            .map(|(level, text, _)| (level, text))
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                (2, FAQ_HEADING),
+                (3, "How?"),
+                (4, "Step one"),
+                (5, "Detail"),
+                (3, "Who?"),
+                (2, "After"),
+                (3, "Kept"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_faq_keeps_inline_markup() {
+        let (body, _) = faq(
+            "## Is **AEO** new?\n\nNo.\n\n## Where is [the spec](https://example.com)?\n\nHere.\n",
+        );
+        assert!(body.contains("\n### Is **AEO** new?\n"));
+        assert!(body.contains("\n### Where is [the spec](https://example.com)?\n"));
+    }
+
+    #[test]
+    fn test_faq_rewrites_setext_questions() {
+        let (body, changes) = faq("What?\n-----\n\nA.\n\nWhy?\n----\n\nB.\n");
+        assert_eq!(changes, 2);
+        assert_eq!(
+            body,
+            "## Frequently Asked Questions\n\n### What?\n\nA.\n\n### Why?\n\nB.\n"
+        );
+    }
+
+    #[test]
+    fn test_faq_leaves_other_documents_alone() {
+        for body in [
+            "## Only one?\n\nA.\n\n## Statement\n",
+            "## Frequently Asked Questions\n\n## A?\n\n## B?\n",
+            "<h2>A?</h2><h2>B?</h2>",
+        ] {
+            assert_eq!(faq(body), (body.to_string(), 0));
+        }
+    }
+
+    #[test]
+    fn test_first_sentence() {
+        assert_eq!(
+            first_sentence("Version 1.5 shipped. Then more."),
+            "Version 1.5 shipped."
+        );
+        assert_eq!(first_sentence("Really?"), "Really?");
+        assert_eq!(first_sentence("No stop"), "No stop");
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use tokio::time::{interval, MissedTickBehavior};
+
+use crate::events::ContentEvent;
+use crate::handlers::{after_transition, AppState};
+use crate::repository::ContentRepository;
+
+/// Poll for scheduled publishes and unpublishes and carry them out. Safe to
+/// run on every replica; see `ContentRepository::run_due_schedules`.
+pub async fn run(state: AppState) {
+    let config = state.config.scheduler.clone();
+    if config.interval_secs == 0 {
+        tracing::info!("Publishing scheduler disabled");
+        return;
+    }
+
+    let mut ticker = interval(Duration::from_secs(config.interval_secs));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        ticker.tick().await;
+        if let Err(err) = run_due(&state, config.batch_size).await {
+            tracing::warn!("Scheduled publishing failed: {:?}", err);
+        }
+    }
+}
+
+/// Everything due now, in batches
+async fn run_due(state: &AppState, batch_size: i64) -> anyhow::Result<()> {
+    let repo = ContentRepository::new(state.db_pool.clone());
+
+    loop {
+        let fired = repo.run_due_schedules(batch_size).await?;
+        let claimed = fired.len();
+
+        for transition in fired {
+            let Some(mut content) = transition.content else {
+                tracing::info!(
+                    "Dropped scheduled {} of {}: content was {}",
+                    transition.action.id(),
+                    transition.content_id,
+                    transition.from.id()
+                );
+                continue;
+            };
+
+            let to = content.status;
+            tracing::info!(
+                "Scheduled {} of {} carried out ({} -> {})",
+                transition.action.id(),
+                content.id,
+                transition.from.id(),
+                to.id()
+            );
+
+            let event = ContentEvent::status_changed(
+                content.id,
+                &content.slug,
+                transition.action,
+                transition.from,
+                to,
+            )
+            .by(transition.scheduled_by)
+            .scheduled_for(transition.scheduled_for);
+            // The transition is committed; a failed follow-up must not hold
+            // back the rest of the batch
+            if let Err(err) = after_transition(state, &repo, &mut content, event).await {
+                tracing::error!(
+                    "Follow-up to scheduled {} of {} failed: {:?}",
+                    transition.action.id(),
+                    content.id,
+                    err
+                );
+            }
+        }
+
+        if (claimed as i64) < batch_size {
+            return Ok(());
+        }
+    }
+}
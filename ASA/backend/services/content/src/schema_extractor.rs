@@ -0,0 +1,689 @@
+use asa_models::aeo::schema::{
+    ArticleSchema, FAQPageSchema, HowToSchema, OfferSchema, ProductSchema, SchemaType,
+    ServiceSchema, SCHEMA_CONTEXT,
+};
+use asa_models::content::{Content, ContentType};
+use serde::Deserialize;
+
+use crate::config::SchemaConfig;
+use crate::document::Document;
+
+/// Longest description taken from the body
+const DESCRIPTION_CHARS: usize = 160;
+
+/// What a HowTo needs besides its steps
+#[derive(Debug, Default)]
+pub struct HowToDetails {
+    pub tools: Vec<String>,
+    pub supplies: Vec<String>,
+    /// ISO 8601 duration, e.g. "PT1H30M"
+    pub total_time: Option<String>,
+}
+
+/// `metadata.product` on product content
+#[derive(Debug, Deserialize)]
+struct ProductMetadata {
+    name: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    image: Vec<String>,
+    price: serde_json::Value,
+    currency: Option<String>,
+    availability: Option<String>,
+    url: Option<String>,
+    brand: Option<String>,
+}
+
+/// `metadata.service` on service content
+#[derive(Debug, Deserialize)]
+struct ServiceMetadata {
+    name: Option<String>,
+    description: Option<String>,
+    provider: Option<String>,
+    service_type: Option<String>,
+    area_served: Option<Vec<String>>,
+}
+
+/// Picks the schema.org types that describe a content item and fills them
+/// from its body and metadata. Serialization is left to
+/// `asa_models::aeo::SchemaGenerator`.
+pub struct SchemaExtractor {
+    config: SchemaConfig,
+}
+
+impl SchemaExtractor {
+    pub fn new(config: SchemaConfig) -> Self {
+        Self { config }
+    }
+
+    /// Every schema that applies to a content item: always an Article, plus
+    /// FAQPage for Q&A blocks, HowTo for numbered steps in how-to content,
+    /// Product/Service from the content's metadata, and any other
+    /// metadata-driven type (Recipe, Event, ...) under `metadata.<content type>`
+    pub fn for_content(&self, content: &Content, author_name: &str) -> Vec<SchemaType> {
+        let doc = Document::parse(&content.body);
+        let description = describe(&doc);
+
+        let mut article = ArticleSchema::new(
+            content.title.clone(),
+            description.clone(),
+            author_name.to_string(),
+        )
+        .published(content.published_at.unwrap_or(content.created_at))
+        .modified(content.updated_at)
+        .publisher(
+            self.config.publisher_name.clone(),
+            self.config.publisher_logo.clone(),
+        )
+        .url(self.absolute(&format!("/{}", content.slug)));
+        for image in self.images(content, &doc) {
+            article = article.image(image);
+        }
+
+        let mut schemas = vec![SchemaType::Article(article)];
+        schemas.extend(self.structure(
+            &content.title,
+            &description,
+            &content.body,
+            is_howto(content.content_type, &content.title),
+        ));
+
+        if let Some(howto) = schemas.iter_mut().find_map(|schema| match schema {
+            SchemaType::HowTo(howto) => Some(howto),
+            _ => None,
+        }) {
+            let total_time = metadata_field(content, "total_time").and_then(|value| value.as_str());
+            if let Some(total_time) = total_time.and_then(iso_duration) {
+                howto.total_time = Some(total_time);
+            }
+        }
+
+        if let Some(product) = metadata_field(content, "product") {
+            match serde_json::from_value(product.clone()) {
+                Ok(product) => schemas.push(self.product(content, &description, product)),
+                Err(err) => tracing::warn!("Ignoring invalid product metadata on {}: {}", content.id, err),
+            }
+        }
+        if let Some(service) = metadata_field(content, "service") {
+            match serde_json::from_value(service.clone()) {
+                Ok(service) => schemas.push(self.service(content, &description, service)),
+                Err(err) => tracing::warn!("Ignoring invalid service metadata on {}: {}", content.id, err),
+            }
+        }
+
+        // Product and Service take the flat keys handled above
+        for content_type in ContentType::all().into_iter().filter(|content_type| {
+            content_type.uses_metadata()
+                && !matches!(content_type, ContentType::Product | ContentType::Service)
+        }) {
+            let Some(properties) = metadata_field(content, content_type.id()) else {
+                continue;
+            };
+            match self.schema_from_metadata(content, &description, content_type, properties) {
+                Ok(schema) => schemas.push(schema),
+                Err(err) => tracing::warn!(
+                    "Ignoring invalid {} metadata on {}: {}",
+                    content_type.id(),
+                    content.id,
+                    err
+                ),
+            }
+        }
+
+        schemas
+    }
+
+    /// FAQPage for Q&A blocks in `body` and, when `howto` is set, HowTo for
+    /// its numbered steps
+    pub fn structure(
+        &self,
+        title: &str,
+        description: &str,
+        body: &str,
+        howto: bool,
+    ) -> Vec<SchemaType> {
+        let mut schemas = Vec::new();
+
+        let faqs = self.extract_faqs(body);
+        if !faqs.is_empty() {
+            let faq = faqs
+                .into_iter()
+                .fold(FAQPageSchema::new(), |faq, (question, answer)| {
+                    faq.question(question, answer)
+                });
+            schemas.push(SchemaType::FAQPage(faq));
+        }
+
+        let steps = self.extract_steps(body);
+        if howto && steps.len() >= 2 {
+            let details = self.extract_howto_details(body);
+            let mut schema = steps
+                .iter()
+                .map(|step| step_parts(step))
+                .fold(
+                    HowToSchema::new(title.to_string(), description.to_string()),
+                    |schema, (name, text)| schema.step(name, text),
+                )
+                .tools(details.tools)
+                .supplies(details.supplies);
+            if let Some(total_time) = details.total_time {
+                schema = schema.total_time(total_time);
+            }
+            schemas.push(SchemaType::HowTo(schema));
+        }
+
+        schemas
+    }
+
+    fn product(&self, content: &Content, description: &str, product: ProductMetadata) -> SchemaType {
+        let price = match product.price {
+            serde_json::Value::String(price) => price,
+            price => price.to_string(),
+        };
+
+        let mut offer = OfferSchema::new(price, product.currency.unwrap_or_else(|| "USD".to_string()));
+        if let Some(availability) = product.availability {
+            offer = offer.availability(availability);
+        }
+        if let Some(url) = product.url {
+            offer = offer.url(self.absolute(&url));
+        }
+
+        let mut schema = ProductSchema::new(
+            product.name.unwrap_or_else(|| content.title.clone()),
+            product.description.unwrap_or_else(|| description.to_string()),
+            offer,
+        );
+        for image in product.image {
+            schema = schema.image(self.absolute(&image));
+        }
+        if let Some(brand) = product.brand {
+            schema = schema.brand(brand);
+        }
+        SchemaType::Product(schema)
+    }
+
+    fn service(&self, content: &Content, description: &str, service: ServiceMetadata) -> SchemaType {
+        let schema = ServiceSchema::new(
+            service.name.unwrap_or_else(|| content.title.clone()),
+            service.description.unwrap_or_else(|| description.to_string()),
+            service
+                .provider
+                .unwrap_or_else(|| self.config.publisher_name.clone()),
+            service
+                .service_type
+                .unwrap_or_else(|| content.content_type.id().to_string()),
+        );
+        SchemaType::Service(schema.area_served(service.area_served.unwrap_or_default()))
+    }
+
+    /// A schema from schema.org properties in the metadata, e.g.
+    /// `{"recipeIngredient": [...], "totalTime": "PT30M"}` under
+    /// `metadata.recipe`. The type and context are filled in, and name and
+    /// description default to the content's.
+    fn schema_from_metadata(
+        &self,
+        content: &Content,
+        description: &str,
+        content_type: ContentType,
+        properties: &serde_json::Value,
+    ) -> Result<SchemaType, serde_json::Error> {
+        let mut properties = match properties {
+            serde_json::Value::Object(properties) => properties.clone(),
+            _ => {
+                return Err(serde::de::Error::custom(format!(
+                    "expected an object of {} properties",
+                    content_type.schema_type()
+                )))
+            }
+        };
+        properties.insert("@type".to_string(), content_type.schema_type().into());
+        properties.insert("@context".to_string(), SCHEMA_CONTEXT.into());
+        properties
+            .entry("name")
+            .or_insert_with(|| content.title.clone().into());
+        properties
+            .entry("description")
+            .or_insert_with(|| description.into());
+
+        serde_json::from_value(serde_json::Value::Object(properties))
+    }
+
+    /// `metadata.image` (a URL or a list of them), then images in the body
+    fn images(&self, content: &Content, doc: &Document) -> Vec<String> {
+        let metadata = match metadata_field(content, "image") {
+            Some(serde_json::Value::String(url)) => vec![url.clone()],
+            Some(serde_json::Value::Array(urls)) => urls
+                .iter()
+                .filter_map(|url| url.as_str().map(String::from))
+                .collect(),
+            _ => vec![],
+        };
+
+        let mut images: Vec<String> = Vec::new();
+        for url in metadata.iter().chain(doc.images.iter().map(|image| &image.src)) {
+            let url = self.absolute(url);
+            if !images.contains(&url) {
+                images.push(url);
+            }
+        }
+        images
+    }
+
+    /// Site-relative URLs resolved against the configured site URL
+    fn absolute(&self, url: &str) -> String {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            url.to_string()
+        } else {
+            format!(
+                "{}/{}",
+                self.config.site_url.trim_end_matches('/'),
+                url.trim_start_matches('/')
+            )
+        }
+    }
+
+    fn extract_faqs(&self, content: &str) -> Vec<(String, String)> {
+        let mut faqs = Vec::new();
+        let lines: Vec<&str> = content.lines().collect();
+
+        for (i, line) in lines.iter().enumerate() {
+            let line = line.trim();
+
+            // Look for question patterns
+            if line.ends_with('?') {
+                // The next few lines, after any blank ones, are the answer
+                let answer = lines[i + 1..]
+                    .iter()
+                    .map(|line| line.trim())
+                    .skip_while(|line| line.is_empty())
+                    .take(4)
+                    .take_while(|line| {
+                        !line.is_empty() && !line.ends_with('?') && !line.starts_with('#')
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                if !answer.is_empty() {
+                    faqs.push((
+                        line.trim_start_matches(&['#', ' ', '*', '-'][..]).to_string(),
+                        answer,
+                    ));
+                }
+            }
+        }
+
+        faqs
+    }
+
+    fn extract_steps(&self, content: &str) -> Vec<String> {
+        let mut steps = Vec::new();
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+
+            // Look for numbered steps
+            if trimmed.starts_with(|c: char| c.is_numeric()) {
+                if let Some(pos) = trimmed.find('.') {
+                    let step = trimmed[pos + 1..].trim();
+                    if !step.is_empty() {
+                        steps.push(step.to_string());
+                    }
+                }
+            }
+        }
+
+        steps
+    }
+
+    /// Tools and supplies listed under a matching heading, and a
+    /// "Total time: ..." line
+    fn extract_howto_details(&self, content: &str) -> HowToDetails {
+        enum List {
+            Tools,
+            Supplies,
+            Other,
+        }
+
+        let mut details = HowToDetails::default();
+        let mut list = List::Other;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            let label = trimmed
+                .trim_start_matches('#')
+                .trim_matches(|c: char| c == '*' || c == ':' || c.is_whitespace())
+                .to_lowercase();
+
+            if trimmed.starts_with('#') || (trimmed.starts_with("**") && (trimmed.ends_with(':') || trimmed.ends_with(":**"))) {
+                list = if label.contains("tool") || label.contains("equipment") {
+                    List::Tools
+                } else if ["suppl", "material", "ingredient", "you'll need", "you need"]
+                    .iter()
+                    .any(|word| label.contains(word))
+                {
+                    List::Supplies
+                } else {
+                    List::Other
+                };
+                continue;
+            }
+
+            if let Some(time) = label.strip_prefix("total time") {
+                details.total_time = iso_duration(time.trim_start_matches(['*', ':', ' ']));
+                continue;
+            }
+
+            let Some(item) = trimmed
+                .strip_prefix("- ")
+                .or_else(|| trimmed.strip_prefix("* "))
+                .map(|item| item.replace("**", "").trim().to_string())
+            else {
+                continue;
+            };
+            match list {
+                List::Tools => details.tools.push(item),
+                List::Supplies => details.supplies.push(item),
+                List::Other => {}
+            }
+        }
+
+        details
+    }
+}
+
+/// The first paragraph, cut at a word boundary to fit a description
+pub fn describe(doc: &Document) -> String {
+    let Some((text, _)) = doc.paragraphs().next() else {
+        return String::new();
+    };
+    if text.chars().count() <= DESCRIPTION_CHARS {
+        return text.to_string();
+    }
+
+    let cut: String = text.chars().take(DESCRIPTION_CHARS).collect();
+    let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(head, _)| head);
+    format!("{}...", cut.trim_end_matches(|c: char| c.is_ascii_punctuation()))
+}
+
+fn metadata_field<'a>(content: &'a Content, key: &str) -> Option<&'a serde_json::Value> {
+    content
+        .metadata
+        .extra
+        .get(key)
+        .filter(|value| !value.is_null())
+}
+
+/// Whether numbered steps in content of this type describe a procedure
+pub fn is_howto(content_type: ContentType, title: &str) -> bool {
+    matches!(content_type, ContentType::Tutorial | ContentType::Guide)
+        || title.to_lowercase().starts_with("how to")
+}
+
+/// A step's short name and full text: a bold lead-in or the text before a
+/// colon names the step, otherwise the step is its own name
+fn step_parts(step: &str) -> (String, String) {
+    let text = step.replace("**", "");
+    let name = step
+        .strip_prefix("**")
+        .and_then(|rest| rest.split_once("**"))
+        .map(|(name, _)| name.to_string())
+        .or_else(|| text.split_once(':').map(|(name, _)| name.to_string()))
+        .unwrap_or_else(|| text.clone());
+
+    (name.trim().to_string(), text.trim().to_string())
+}
+
+/// "1 hour 30 minutes" as "PT1H30M"; an ISO 8601 duration is kept as is
+fn iso_duration(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.starts_with('P') && !text.contains(' ') {
+        return Some(text.to_string());
+    }
+
+    let (mut days, mut hours, mut minutes) = (0u32, 0u32, 0u32);
+    let mut number = None;
+    for word in text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty())
+    {
+        let digits = word.chars().take_while(char::is_ascii_digit).count();
+        if digits > 0 {
+            number = word[..digits].parse::<u32>().ok();
+        }
+        let unit = word[digits..].trim_end_matches('.').to_lowercase();
+        if unit.is_empty() {
+            continue;
+        }
+        let Some(n) = number.take() else {
+            continue;
+        };
+        match unit.chars().next() {
+            Some('d') => days += n,
+            Some('h') => hours += n,
+            Some('m') => minutes += n,
+            _ => {}
+        }
+    }
+
+    if days + hours + minutes == 0 {
+        return None;
+    }
+    let mut duration = String::from("P");
+    if days > 0 {
+        duration.push_str(&format!("{}D", days));
+    }
+    if hours + minutes > 0 {
+        duration.push('T');
+        if hours > 0 {
+            duration.push_str(&format!("{}H", hours));
+        }
+        if minutes > 0 {
+            duration.push_str(&format!("{}M", minutes));
+        }
+    }
+    Some(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    const TUTORIAL: &str = r##"Pour-over coffee brings out the flavor of a light roast. This guide walks through one cup.
+
+## Tools
+
+- Gooseneck kettle
+- **Paper filter**
+
+## Supplies
+
+- 20 g of coffee
+- 320 g of water
+
+Total time: 1 hour 5 minutes
+
+## Steps
+
+1. **Rinse** the filter with hot water.
+2. Bloom: pour 40 g of water and wait.
+3. Pour the rest in slow circles.
+
+## FAQ
+
+What grind should I use?
+A medium-fine grind, like table salt.
+
+Can I reuse the filter?
+
+No.
+"##;
+
+    fn extractor() -> SchemaExtractor {
+        SchemaExtractor::new(SchemaConfig {
+            site_url: "https://example.com/".to_string(),
+            publisher_name: "Example Coffee".to_string(),
+            publisher_logo: "https://example.com/logo.png".to_string(),
+        })
+    }
+
+    fn tutorial() -> Content {
+        let mut content = Content::new(
+            "Brewing pour-over coffee",
+            "brewing-pour-over-coffee",
+            TUTORIAL,
+            ContentType::Tutorial,
+            Uuid::new_v4(),
+        );
+        content.metadata.extra = serde_json::json!({
+            "image": "/img/pour-over.jpg",
+            "product": {"price": 12.5, "currency": "EUR", "url": "/shop/beans", "brand": "Acme"},
+            "service": "not an object",
+            "recipe": ["not", "an", "object"],
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        content
+    }
+
+    #[test]
+    fn test_schemas_for_tutorial() {
+        let schemas = extractor().for_content(&tutorial(), "Jane Smith");
+        let types: Vec<&str> = schemas.iter().map(SchemaType::type_name).collect();
+        // Invalid service and recipe metadata is left out
+        assert_eq!(types, vec!["Article", "FAQPage", "HowTo", "Product"]);
+
+        let SchemaType::Article(article) = &schemas[0] else {
+            unreachable!()
+        };
+        assert_eq!(
+            article.description,
+            "Pour-over coffee brings out the flavor of a light roast. This guide walks through one cup."
+        );
+        assert_eq!(article.author.name, "Jane Smith");
+        assert_eq!(
+            article.main_entity_of_page.as_deref(),
+            Some("https://example.com/brewing-pour-over-coffee")
+        );
+        assert_eq!(article.image, vec!["https://example.com/img/pour-over.jpg"]);
+
+        let SchemaType::Product(product) = &schemas[3] else {
+            unreachable!()
+        };
+        assert_eq!(product.name, "Brewing pour-over coffee");
+        assert_eq!(
+            (
+                product.offers.price.as_str(),
+                product.offers.price_currency.as_str()
+            ),
+            ("12.5", "EUR")
+        );
+        assert_eq!(
+            product.offers.url.as_deref(),
+            Some("https://example.com/shop/beans")
+        );
+    }
+
+    #[test]
+    fn test_faq_and_howto_from_body() {
+        let schemas = extractor().structure("Brewing", "One cup", TUTORIAL, true);
+
+        let SchemaType::FAQPage(faq) = &schemas[0] else {
+            unreachable!()
+        };
+        let questions: Vec<(&str, &str)> = faq
+            .main_entity
+            .iter()
+            .map(|item| (item.name.as_str(), item.accepted_answer.text.as_str()))
+            .collect();
+        assert_eq!(
+            questions,
+            vec![
+                (
+                    "What grind should I use?",
+                    "A medium-fine grind, like table salt."
+                ),
+                ("Can I reuse the filter?", "No."),
+            ]
+        );
+
+        let SchemaType::HowTo(howto) = &schemas[1] else {
+            unreachable!()
+        };
+        let steps: Vec<(&str, &str)> = howto
+            .step
+            .iter()
+            .map(|step| (step.name.as_str(), step.text.as_str()))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("Rinse", "Rinse the filter with hot water."),
+                ("Bloom", "Bloom: pour 40 g of water and wait."),
+                (
+                    "Pour the rest in slow circles.",
+                    "Pour the rest in slow circles."
+                ),
+            ]
+        );
+        assert_eq!(
+            howto.tool.as_deref(),
+            Some(&["Gooseneck kettle".to_string(), "Paper filter".to_string()][..])
+        );
+        assert_eq!(
+            howto.supply.as_deref(),
+            Some(&["20 g of coffee".to_string(), "320 g of water".to_string()][..])
+        );
+        assert_eq!(howto.total_time.as_deref(), Some("PT1H5M"));
+
+        // Numbered lists outside how-to content are not steps
+        let schemas = extractor().structure("Brewing", "One cup", TUTORIAL, false);
+        assert_eq!(schemas.len(), 1);
+    }
+
+    #[test]
+    fn test_howto_total_time_from_metadata() {
+        let mut content = tutorial();
+        content
+            .metadata
+            .extra
+            .insert("total_time".to_string(), "45 minutes".into());
+        let schemas = extractor().for_content(&content, "Jane Smith");
+        let howto = schemas
+            .iter()
+            .find_map(|schema| match schema {
+                SchemaType::HowTo(howto) => Some(howto),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(howto.total_time.as_deref(), Some("PT45M"));
+    }
+
+    #[test]
+    fn test_is_howto() {
+        assert!(is_howto(ContentType::Guide, "Coffee basics"));
+        assert!(is_howto(ContentType::Article, "How to brew coffee"));
+        assert!(!is_howto(ContentType::Article, "Coffee basics"));
+    }
+
+    #[test]
+    fn test_iso_duration() {
+        assert_eq!(
+            iso_duration("1 hour 30 minutes").as_deref(),
+            Some("PT1H30M")
+        );
+        assert_eq!(iso_duration("2 days, 4h").as_deref(), Some("P2DT4H"));
+        assert_eq!(iso_duration("PT20M").as_deref(), Some("PT20M"));
+        assert_eq!(iso_duration("a while"), None);
+    }
+
+    #[test]
+    fn test_describe_cuts_at_a_word() {
+        let long = "word ".repeat(50);
+        let description = describe(&Document::parse(&long));
+        assert!(description.ends_with("word..."));
+        assert!(description.chars().count() <= DESCRIPTION_CHARS + 3);
+    }
+}
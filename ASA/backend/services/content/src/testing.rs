@@ -0,0 +1,149 @@
+//! Test support: a local HTTP server standing in for the LLM provider APIs
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Router,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::config::LlmConfig;
+
+/// A canned reply, sent once
+#[derive(Debug, Clone)]
+pub struct StubResponse {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+impl StubResponse {
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        Self {
+            status,
+            headers: vec![("content-type", "application/json".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Server-sent events, one `data:` line per payload
+    pub fn events<'a>(payloads: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("content-type", "text/event-stream".to_string())],
+            body: payloads
+                .into_iter()
+                .map(|payload| format!("data: {}\n\n", payload))
+                .collect(),
+        }
+    }
+
+    pub fn header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+}
+
+/// A request the stub received
+#[derive(Debug, Clone)]
+pub struct StubRequest {
+    pub path: String,
+    pub headers: HeaderMap,
+    pub body: serde_json::Value,
+}
+
+struct Exchange {
+    responses: VecDeque<StubResponse>,
+    requests: Vec<StubRequest>,
+}
+
+/// Replies to requests on any path with the given responses in order,
+/// and with a 500 once they run out
+pub struct StubServer {
+    url: String,
+    exchange: Arc<Mutex<Exchange>>,
+}
+
+impl StubServer {
+    pub async fn start(responses: impl IntoIterator<Item = StubResponse>) -> Self {
+        let exchange = Arc::new(Mutex::new(Exchange {
+            responses: responses.into_iter().collect(),
+            requests: Vec::new(),
+        }));
+        let app = Router::new().fallback(reply).with_state(exchange.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        Self { url, exchange }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn requests(&self) -> Vec<StubRequest> {
+        self.exchange.lock().unwrap().requests.clone()
+    }
+}
+
+async fn reply(
+    State(exchange): State<Arc<Mutex<Exchange>>>,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let mut exchange = exchange.lock().unwrap();
+    exchange.requests.push(StubRequest {
+        path: uri.path().to_string(),
+        headers,
+        body: serde_json::from_slice(&body).unwrap_or_default(),
+    });
+
+    let Some(stub) = exchange.responses.pop_front() else {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "no stub response left").into_response();
+    };
+    let status = StatusCode::from_u16(stub.status).unwrap();
+    let mut response = (status, stub.body).into_response();
+    for (name, value) in stub.headers {
+        response.headers_mut().insert(name, value.parse().unwrap());
+    }
+    response
+}
+
+/// Provider settings for tests: no retries, `fixture` by default and, given
+/// a stub, the OpenAI, Anthropic and compatible backends all pointed at it
+pub fn llm_config(default_provider: &str, stub: Option<&StubServer>) -> LlmConfig {
+    LlmConfig {
+        default_provider: default_provider.to_string(),
+        platform_providers: HashMap::new(),
+        openai_model: "gpt-test".to_string(),
+        anthropic_model: "claude-test".to_string(),
+        openai_base_url: stub.map(|stub| stub.url().to_string()),
+        anthropic_base_url: stub.map(|stub| stub.url().to_string()),
+        compatible_base_url: stub.map(|stub| stub.url().to_string()),
+        compatible_api_key: None,
+        compatible_model: "llama-test".to_string(),
+        timeout_secs: 5,
+        max_retries: 0,
+        pricing: HashMap::from([(
+            "gpt-test".to_string(),
+            crate::llm::ModelPrice {
+                prompt: 1.0,
+                completion: 2.0,
+            },
+        )]),
+    }
+}
@@ -6,6 +6,7 @@ pub struct Config {
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
     pub meilisearch: MeiliSearchConfig,
+    pub platform_profiles: Option<String>, // extra/override platform profiles (TOML or JSON)
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -50,6 +51,7 @@ impl Config {
                 api_key: std::env::var("MEILISEARCH_API_KEY")
                     .unwrap_or_else(|_| "masterKey".to_string()),
             },
+            platform_profiles: std::env::var("PLATFORM_PROFILES").ok(),
         })
     }
 }
@@ -11,12 +11,15 @@ use crate::{
     models::*,
 };
 use asa_database::{PostgresPool, RedisClient};
+use asa_models::aeo::profile::PlatformRegistry;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: PostgresPool,
     pub redis_client: RedisClient,
     pub indexer: SearchIndexer,
+    pub platforms: Arc<PlatformRegistry>,
     pub config: Config,
 }
 
@@ -150,7 +153,7 @@ pub async fn aeo_search(
     let platform_optimization = PlatformOptimization {
         platform: payload.platform.clone(),
         relevance_score: calculate_platform_relevance(&payload.platform, featured_content.as_ref()),
-        optimization_tips: get_platform_tips(&payload.platform),
+        optimization_tips: get_platform_tips(&state.platforms, &payload.platform),
     };
 
     Ok(Json(AEOSearchResponse {
@@ -309,22 +312,11 @@ fn calculate_platform_relevance(_platform: &str, _content: Option<&SearchResult>
     85.0
 }
 
-fn get_platform_tips(platform: &str) -> Vec<String> {
-    match platform.to_lowercase().as_str() {
-        "chatgpt" => vec![
-            "Content is well-structured with clear sections".to_string(),
-            "Includes practical examples".to_string(),
-        ],
-        "claude" => vec![
-            "Comprehensive coverage with depth".to_string(),
-            "Multiple perspectives included".to_string(),
-        ],
-        "perplexity" => vec![
-            "Strong citation foundation".to_string(),
-            "Authoritative sources referenced".to_string(),
-        ],
-        _ => vec!["Content optimized for AI platforms".to_string()],
-    }
+fn get_platform_tips(platforms: &PlatformRegistry, platform: &str) -> Vec<String> {
+    platforms
+        .get(platform)
+        .map(|profile| profile.tips.clone())
+        .unwrap_or_else(|| vec!["Content optimized for AI platforms".to_string()])
 }
 
 // Error handling
@@ -2,7 +2,9 @@ use axum::{
     routing::{get, post, put},
     Router,
 };
+use asa_models::aeo::profile::PlatformRegistry;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 
 mod config;
@@ -31,11 +33,18 @@ async fn main() -> anyhow::Result<()> {
     // Initialize MeiliSearch
     let indexer = indexer::SearchIndexer::new(&config.meilisearch.url, &config.meilisearch.api_key).await?;
 
+    // Platform profiles for AEO search tips
+    let platforms = Arc::new(match &config.platform_profiles {
+        Some(path) => PlatformRegistry::builtin_with(path)?,
+        None => PlatformRegistry::builtin().clone(),
+    });
+
     // Create shared state
     let state = handlers::AppState {
         db_pool,
         redis_client,
         indexer,
+        platforms,
         config: config.clone(),
     };
 
@@ -2,8 +2,14 @@ pub mod schema;
 pub mod citation;
 pub mod platform;
 pub mod optimization;
+pub mod profile;
+pub mod validation;
+pub mod parser;
 
 pub use schema::*;
 pub use citation::*;
 pub use platform::*;
 pub use optimization::*;
+pub use profile::*;
+pub use validation::*;
+pub use parser::*;
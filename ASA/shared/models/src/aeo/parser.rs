@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use super::schema::{SchemaType, SCHEMA_CONTEXT};
+
+/// Nested `@id` references followed before giving up, so a long chain of
+/// nodes can't blow the stack
+const MAX_DEPTH: usize = 32;
+
+/// Reads JSON-LD as published on real pages into `SchemaType` values.
+///
+/// Pages use `@graph`, several blocks, arrays for `@type` and nodes that
+/// refer to each other by `@id`, none of which the serialize-first
+/// `SchemaType` accepts directly. Items that don't fit a typed struct are
+/// kept as `SchemaType::Unknown` so nothing found on the page is dropped.
+pub struct SchemaParser;
+
+impl SchemaParser {
+    /// Every top-level item in a JSON-LD document: a node, an array of
+    /// nodes or an `@graph`, nested in any combination. Pass the blocks of
+    /// a page as one array so references between them resolve.
+    pub fn parse(document: &Value) -> Vec<SchemaType> {
+        let mut nodes = Vec::new();
+        collect_nodes(document, &mut nodes);
+
+        let mut index = HashMap::new();
+        for node in &nodes {
+            index_nodes(node, &mut index);
+        }
+
+        nodes
+            .into_iter()
+            .filter(|node| !is_reference(node))
+            .map(|node| {
+                let node = match node.get("@id").and_then(Value::as_str) {
+                    Some(id) => index.get(id).map_or(node, |merged| merged),
+                    None => node,
+                };
+                let mut seen = Vec::new();
+                typed(resolve(node, &index, &mut seen))
+            })
+            .collect()
+    }
+}
+
+/// Top-level nodes, with arrays and `@graph` flattened
+fn collect_nodes<'a>(value: &'a Value, nodes: &mut Vec<&'a Value>) {
+    match value {
+        Value::Array(values) => values.iter().for_each(|value| collect_nodes(value, nodes)),
+        Value::Object(object) => match object.get("@graph") {
+            Some(graph) => collect_nodes(graph, nodes),
+            None => nodes.push(value),
+        },
+        _ => {}
+    }
+}
+
+/// Node definitions by `@id`, including nested ones. Definitions of the same
+/// id are merged, the first value of a property winning.
+fn index_nodes(value: &Value, index: &mut HashMap<String, Value>) {
+    match value {
+        Value::Array(values) => values.iter().for_each(|value| index_nodes(value, index)),
+        Value::Object(object) => {
+            if let Some(id) = object.get("@id").and_then(Value::as_str) {
+                if !is_reference(value) {
+                    let node = index
+                        .entry(id.to_string())
+                        .or_insert_with(|| Value::Object(Map::new()));
+                    if let Value::Object(node) = node {
+                        for (key, property) in object {
+                            node.entry(key.clone()).or_insert_with(|| property.clone());
+                        }
+                    }
+                }
+            }
+            object.values().for_each(|value| index_nodes(value, index));
+        }
+        _ => {}
+    }
+}
+
+/// `value` with references to indexed nodes replaced by the nodes. A
+/// reference back to a node being resolved is left as is.
+fn resolve(value: &Value, index: &HashMap<String, Value>, seen: &mut Vec<String>) -> Value {
+    match value {
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| resolve(value, index, seen))
+                .collect(),
+        ),
+        Value::Object(object) => {
+            let id = object.get("@id").and_then(Value::as_str);
+            if let Some(id) = id.filter(|_| is_reference(value)) {
+                return match index.get(id) {
+                    Some(node) if seen.len() < MAX_DEPTH && !seen.iter().any(|seen| seen == id) => {
+                        resolve(node, index, seen)
+                    }
+                    _ => value.clone(),
+                };
+            }
+
+            if let Some(id) = id {
+                seen.push(id.to_string());
+            }
+            let resolved = object
+                .iter()
+                .map(|(key, value)| (key.clone(), resolve(value, index, seen)))
+                .collect();
+            if id.is_some() {
+                seen.pop();
+            }
+            Value::Object(resolved)
+        }
+        _ => value.clone(),
+    }
+}
+
+/// The typed schema for a node, trying each of its types in order, or the
+/// node itself when none fits
+fn typed(node: Value) -> SchemaType {
+    for name in types(&node) {
+        let mut candidate = normalize(&node);
+        if let Value::Object(object) = &mut candidate {
+            object.insert("@type".to_string(), Value::String(name));
+            object.insert("@context".to_string(), Value::String(SCHEMA_CONTEXT.to_string()));
+        }
+        if let Ok(schema) = serde_json::from_value::<SchemaType>(candidate) {
+            if !matches!(schema, SchemaType::Unknown(_)) {
+                return schema;
+            }
+        }
+    }
+    SchemaType::Unknown(node)
+}
+
+/// A node's types without vocabulary prefixes, e.g. "Recipe" for
+/// "http://schema.org/Recipe"
+fn types(node: &Value) -> Vec<String> {
+    match node.get("@type") {
+        Some(Value::String(name)) => vec![type_name(name)],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).map(type_name).collect(),
+        _ => vec![],
+    }
+}
+
+fn type_name(name: &str) -> String {
+    ["https://schema.org/", "http://schema.org/", "schema:"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name)
+        .to_string()
+}
+
+/// Nested types reduced to a single prefix-free name, as the typed structs
+/// expect
+fn normalize(value: &Value) -> Value {
+    match value {
+        Value::Array(values) => Value::Array(values.iter().map(normalize).collect()),
+        Value::Object(object) => {
+            let name = types(value).into_iter().next();
+            Value::Object(
+                object
+                    .iter()
+                    .map(|(key, value)| {
+                        let value = match (key.as_str(), &name) {
+                            ("@type", Some(name)) => Value::String(name.clone()),
+                            _ => normalize(value),
+                        };
+                        (key.clone(), value)
+                    })
+                    .collect(),
+            )
+        }
+        _ => value.clone(),
+    }
+}
+
+/// A node object with nothing but an `@id`
+fn is_reference(value: &Value) -> bool {
+    value
+        .as_object()
+        .is_some_and(|object| object.contains_key("@id") && object.keys().all(|key| key == "@id"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_graph_with_references() {
+        let document = json!({
+            "@context": "https://schema.org",
+            "@graph": [
+                {
+                    "@type": ["BlogPosting", "Article"],
+                    "@id": "https://example.com/post#article",
+                    "headline": "What is AEO?",
+                    "description": "An introduction",
+                    "image": ["https://example.com/cover.png"],
+                    "datePublished": "2024-03-01T09:00:00Z",
+                    "author": {"@id": "https://example.com/#jane"},
+                    "publisher": {"@id": "https://example.com/#org"}
+                },
+                {"@type": "Person", "@id": "https://example.com/#jane", "name": "Jane"},
+                {
+                    "@type": "Organization",
+                    "@id": "https://example.com/#org",
+                    "name": "Example",
+                    "url": "https://example.com",
+                    "logo": {"@type": "ImageObject", "url": "https://example.com/logo.png"}
+                },
+                {"@id": "https://example.com/#org"}
+            ]
+        });
+
+        let schemas = SchemaParser::parse(&document);
+        let types: Vec<_> = schemas.iter().map(SchemaType::type_name).collect();
+        assert_eq!(types, ["Article", "Person", "Organization"]);
+
+        let SchemaType::Article(article) = &schemas[0] else {
+            panic!("expected an article, got {:?}", schemas[0]);
+        };
+        assert_eq!(article.author.name, "Jane");
+        assert_eq!(article.publisher.logo.url, "https://example.com/logo.png");
+    }
+
+    #[test]
+    fn test_unknown_and_cycles() {
+        let document = json!([
+            {
+                "@context": "https://schema.org",
+                "@type": "http://schema.org/Movie",
+                "@id": "#movie",
+                "name": "Up",
+                "director": {"@type": "Person", "@id": "#pete", "name": "Pete", "directed": {"@id": "#movie"}}
+            },
+            {"@context": "https://schema.org", "@type": "Product", "name": "No offers"},
+            "not a node"
+        ]);
+
+        let schemas = SchemaParser::parse(&document);
+        assert_eq!(schemas.len(), 2);
+        assert_eq!(schemas[0].type_name(), "http://schema.org/Movie");
+        let SchemaType::Unknown(movie) = &schemas[0] else {
+            panic!("expected unknown markup");
+        };
+        assert_eq!(movie["director"]["directed"], json!({"@id": "#movie"}));
+        // Doesn't fit ProductSchema, which requires offers
+        assert!(matches!(schemas[1], SchemaType::Unknown(_)));
+        assert_eq!(schemas[1].type_name(), "Product");
+    }
+}
@@ -1,8 +1,16 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+
+use super::profile::PlatformRegistry;
 
 /// AI Platforms for AEO optimization (from book chapters 1-3)
+///
+/// Deserializes from any id, display name or alias in the bundled profiles
+/// ("chatgpt", "Bing AI", "copilot", ...) and rejects everything else.
+/// Serializes as the id, the same key stored rows and score maps use.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum AIPlatform {
     /// ChatGPT (OpenAI) - Requires clear structure, code examples
     ChatGPT,
@@ -28,93 +36,101 @@ impl AIPlatform {
         ]
     }
 
-    /// Get optimization requirements for this platform (Chapter 5)
-    pub fn optimization_requirements(&self) -> PlatformRequirements {
+    /// Key of this platform's profile in the registry
+    pub fn id(&self) -> &'static str {
         match self {
-            Self::ChatGPT => PlatformRequirements {
-                structure: "Clear headers and subheaders",
-                format: "Numbered lists and bullet points",
-                examples: "Concrete implementations and code snippets",
-                special: "Include step-by-step instructions",
-            },
-            Self::Claude => PlatformRequirements {
-                structure: "Comprehensive explanations with context",
-                format: "Well-structured paragraphs with depth",
-                examples: "Address edge cases and nuances",
-                special: "Highlight safety and ethical considerations",
-            },
-            Self::Perplexity => PlatformRequirements {
-                structure: "Citations and authoritative sources",
-                format: "Statistics and hard data",
-                examples: "Latest industry updates and research",
-                special: "Multiple perspectives and comparisons",
-            },
-            Self::Gemini => PlatformRequirements {
-                structure: "Image descriptions and multimedia",
-                format: "Google-friendly formatting",
-                examples: "Local relevance and geographic context",
-                special: "User testimonials and reviews",
-            },
-            Self::Bing => PlatformRequirements {
-                structure: "Direct, clear answers",
-                format: "Show expert credentials",
-                examples: "Recent updates and freshness signals",
-                special: "Interactive elements and engagement",
-            },
+            Self::ChatGPT => "chatgpt",
+            Self::Claude => "claude",
+            Self::Perplexity => "perplexity",
+            Self::Gemini => "gemini",
+            Self::Bing => "bing",
         }
     }
 
-    /// Get ideal content length for platform
-    pub fn ideal_content_length(&self) -> ContentLength {
+    /// Display name, as in the bundled profiles
+    pub fn name(&self) -> &'static str {
         match self {
-            Self::ChatGPT => ContentLength {
-                min_words: 500,
-                max_words: 2000,
-                ideal_words: 1000,
-            },
-            Self::Claude => ContentLength {
-                min_words: 800,
-                max_words: 3000,
-                ideal_words: 1500,
-            },
-            Self::Perplexity => ContentLength {
-                min_words: 600,
-                max_words: 2500,
-                ideal_words: 1200,
-            },
-            Self::Gemini => ContentLength {
-                min_words: 400,
-                max_words: 1800,
-                ideal_words: 900,
-            },
-            Self::Bing => ContentLength {
-                min_words: 500,
-                max_words: 2000,
-                ideal_words: 1000,
-            },
+            Self::ChatGPT => "ChatGPT",
+            Self::Claude => "Claude",
+            Self::Perplexity => "Perplexity",
+            Self::Gemini => "Gemini",
+            Self::Bing => "Bing AI",
         }
     }
+
+    /// Get optimization requirements for this platform from a registry (Chapter 5)
+    pub fn optimization_requirements<'a>(
+        &self,
+        registry: &'a PlatformRegistry,
+    ) -> Option<PlatformRequirements<'a>> {
+        let requirements = &registry.profile(self)?.requirements;
+        Some(PlatformRequirements {
+            structure: &requirements.structure,
+            format: &requirements.format,
+            examples: &requirements.examples,
+            special: &requirements.special,
+        })
+    }
+
+    /// Get ideal content length for platform from a registry
+    pub fn ideal_content_length(&self, registry: &PlatformRegistry) -> Option<ContentLength> {
+        registry.profile(self).map(|profile| profile.content_length)
+    }
+}
+
+/// Parses against the bundled profiles only. Services that load extra
+/// profiles resolve names with `PlatformRegistry::get` instead.
+impl FromStr for AIPlatform {
+    type Err = UnknownPlatform;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PlatformRegistry::builtin()
+            .get(s)
+            .and_then(|profile| Self::all().into_iter().find(|p| p.id() == profile.id))
+            .ok_or_else(|| UnknownPlatform(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for AIPlatform {
+    type Error = UnknownPlatform;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<AIPlatform> for String {
+    fn from(platform: AIPlatform) -> Self {
+        platform.id().to_string()
+    }
+}
+
+/// A platform name that matches no known platform or alias
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown platform '{0}', expected one of: {}", valid_platform_names())]
+pub struct UnknownPlatform(pub String);
+
+fn valid_platform_names() -> String {
+    AIPlatform::all()
+        .iter()
+        .map(|platform| platform.id())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl fmt::Display for AIPlatform {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::ChatGPT => write!(f, "ChatGPT"),
-            Self::Claude => write!(f, "Claude"),
-            Self::Perplexity => write!(f, "Perplexity"),
-            Self::Gemini => write!(f, "Gemini"),
-            Self::Bing => write!(f, "Bing AI"),
-        }
+        write!(f, "{}", self.name())
     }
 }
 
 /// Platform-specific optimization requirements
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PlatformRequirements {
-    pub structure: &'static str,
-    pub format: &'static str,
-    pub examples: &'static str,
-    pub special: &'static str,
+#[derive(Debug, Clone, Serialize)]
+pub struct PlatformRequirements<'a> {
+    pub structure: &'a str,
+    pub format: &'a str,
+    pub examples: &'a str,
+    pub special: &'a str,
 }
 
 /// Content length recommendations
@@ -128,7 +144,7 @@ pub struct ContentLength {
 /// Optimization strategy per platform (Chapter 4-5)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlatformStrategy {
-    pub platform: AIPlatform,
+    pub platform: String, // profile id, bundled or loaded
     pub priority: u8,  // 1-10
     pub active: bool,
     pub custom_rules: Vec<OptimizationRule>,
@@ -139,11 +155,25 @@ pub struct PlatformStrategy {
 pub struct OptimizationRule {
     pub name: String,
     pub description: String,
+    #[serde(default)]
+    pub kind: RuleKind,
+    /// Regex for `RuleKind::Regex`, transform name for `RuleKind::Structural`
     pub pattern: String,
     pub replacement: String,
     pub enabled: bool,
 }
 
+/// How an optimization rule's pattern is interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// Regex find-and-replace over the body
+    #[default]
+    Regex,
+    /// Named document transform, e.g. "tldr_after_h1"
+    Structural,
+}
+
 /// Platform performance metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlatformMetrics {
@@ -162,7 +192,9 @@ mod tests {
     #[test]
     fn test_platform_requirements() {
         let chatgpt = AIPlatform::ChatGPT;
-        let reqs = chatgpt.optimization_requirements();
+        let reqs = chatgpt
+            .optimization_requirements(PlatformRegistry::builtin())
+            .unwrap();
 
         assert_eq!(reqs.structure, "Clear headers and subheaders");
     }
@@ -170,13 +202,64 @@ mod tests {
     #[test]
     fn test_content_length() {
         let claude = AIPlatform::Claude;
-        let length = claude.ideal_content_length();
+        let length = claude
+            .ideal_content_length(PlatformRegistry::builtin())
+            .unwrap();
 
         assert_eq!(length.ideal_words, 1500);
         assert!(length.min_words < length.ideal_words);
         assert!(length.ideal_words < length.max_words);
     }
 
+    #[test]
+    fn test_rule_kind_defaults_to_regex() {
+        let rule: OptimizationRule = serde_json::from_str(
+            r#"{"name":"n","description":"d","pattern":"a","replacement":"b","enabled":true}"#,
+        )
+        .unwrap();
+        assert_eq!(rule.kind, RuleKind::Regex);
+
+        let json = serde_json::to_value(OptimizationRule {
+            kind: RuleKind::Structural,
+            ..rule
+        })
+        .unwrap();
+        assert_eq!(json["kind"], "structural");
+    }
+
+    #[test]
+    fn test_platform_aliases() {
+        assert_eq!("bing ai".parse::<AIPlatform>().unwrap(), AIPlatform::Bing);
+        assert_eq!("Copilot".parse::<AIPlatform>().unwrap(), AIPlatform::Bing);
+        assert_eq!("gpt".parse::<AIPlatform>().unwrap(), AIPlatform::ChatGPT);
+        assert_eq!("Claude".parse::<AIPlatform>().unwrap(), AIPlatform::Claude);
+
+        let err = "chatgtp".parse::<AIPlatform>().unwrap_err();
+        assert!(err.to_string().contains("expected one of: chatgpt, claude"));
+    }
+
+    #[test]
+    fn test_platform_serde() {
+        let platform: AIPlatform = serde_json::from_str(r#""perplexity""#).unwrap();
+        assert_eq!(platform, AIPlatform::Perplexity);
+        assert_eq!(serde_json::to_string(&platform).unwrap(), r#""perplexity""#);
+        for platform in AIPlatform::all() {
+            let json = serde_json::to_value(platform).unwrap();
+            assert_eq!(json, platform.id());
+            assert_eq!(serde_json::from_value::<AIPlatform>(json).unwrap(), platform);
+        }
+        assert!(serde_json::from_str::<AIPlatform>(r#""bard ai""#).is_err());
+    }
+
+    #[test]
+    fn test_names_match_bundled_profiles() {
+        for platform in AIPlatform::all() {
+            let profile = PlatformRegistry::builtin().profile(&platform).unwrap();
+            assert_eq!(platform.name(), profile.name);
+            assert_eq!(platform.to_string(), profile.name);
+        }
+    }
+
     #[test]
     fn test_all_platforms() {
         let platforms = AIPlatform::all();
@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use super::platform::{AIPlatform, ContentLength};
+
+/// Profiles bundled with the crate
+const BUILTIN_PROFILES: &str = include_str!("platforms.toml");
+
+/// The profiles a service loaded at startup, see `PlatformRegistry::install`
+static INSTALLED: OnceLock<PlatformRegistry> = OnceLock::new();
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("failed to read platform profiles: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid platform profiles TOML: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("invalid platform profiles JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid platform profile '{platform}': {reason}")]
+    Invalid { platform: String, reason: String },
+}
+
+/// Everything the services know about one answer engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformProfile {
+    /// Stable lowercase key, e.g. "chatgpt"
+    pub id: String,
+    /// Display name
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub content_length: ContentLength,
+    pub requirements: ProfileRequirements,
+    /// Prompt guidance for content generation
+    pub guidance: String,
+    pub tips: Vec<String>,
+    /// Platform category scoring rules
+    pub scoring: Vec<ScoringRule>,
+    /// Structural rewrite transforms applied by default
+    #[serde(default)]
+    pub transforms: Vec<String>,
+}
+
+/// Owned counterpart of `PlatformRequirements`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileRequirements {
+    pub structure: String,
+    pub format: String,
+    pub examples: String,
+    pub special: String,
+}
+
+/// Pass/fail platform rule: full weight when the signal is found
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoringRule {
+    pub id: String,
+    /// Share of the platform category, 0-1
+    pub weight: f64,
+    pub description: String,
+    pub suggestion: String,
+    #[serde(flatten)]
+    pub signal: Signal,
+    /// Reported as an improvement when the rule fails
+    #[serde(default)]
+    pub improvement: Option<ImprovementHint>,
+}
+
+/// What a scoring rule looks for in the document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "signal", rename_all = "snake_case")]
+pub enum Signal {
+    Terms { terms: Vec<String> },
+    NumberedLists,
+    CodeBlocks,
+    ExternalLinks,
+    Images {
+        #[serde(default)]
+        terms: Vec<String>,
+    },
+    Headings { level: u8 },
+    WordCount {
+        #[serde(default)]
+        min: Option<usize>,
+        #[serde(default)]
+        max: Option<usize>,
+    },
+    DataPoints,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImprovementHint {
+    pub category: String,
+    pub impact: String, // high, medium, low
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProfileFile {
+    platforms: Vec<PlatformProfile>,
+}
+
+/// Platform profiles looked up by id, name or alias
+#[derive(Debug, Clone)]
+pub struct PlatformRegistry {
+    profiles: Vec<PlatformProfile>,
+}
+
+impl PlatformRegistry {
+    /// The bundled profiles
+    pub fn builtin() -> &'static PlatformRegistry {
+        static BUILTIN: OnceLock<PlatformRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            PlatformRegistry::from_toml(BUILTIN_PROFILES).expect("bundled platforms.toml is valid")
+        })
+    }
+
+    /// Make these the profiles `PlatformId` resolves against. Services call
+    /// this once at startup with the profiles they loaded; the first call wins.
+    pub fn install(self) -> &'static PlatformRegistry {
+        INSTALLED.get_or_init(|| self)
+    }
+
+    /// The installed profiles, or the bundled ones if none were installed
+    pub fn installed() -> &'static PlatformRegistry {
+        INSTALLED.get().unwrap_or_else(|| Self::builtin())
+    }
+
+    pub fn from_toml(source: &str) -> Result<Self, RegistryError> {
+        let file: ProfileFile = toml::from_str(source)?;
+        Self::new(file.platforms)
+    }
+
+    pub fn from_json(source: &str) -> Result<Self, RegistryError> {
+        let file: ProfileFile = serde_json::from_str(source)?;
+        Self::new(file.platforms)
+    }
+
+    /// Read a `.json` or `.toml` profile file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RegistryError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&source),
+            _ => Self::from_toml(&source),
+        }
+    }
+
+    /// The bundled profiles with `path` layered on top: profiles with a known
+    /// id replace the bundled one, new ids are added
+    pub fn builtin_with(path: impl AsRef<Path>) -> Result<Self, RegistryError> {
+        let overrides = Self::load(path)?;
+        let mut profiles = Self::builtin().profiles.clone();
+        for profile in overrides.profiles {
+            match profiles.iter_mut().find(|p| p.id == profile.id) {
+                Some(existing) => *existing = profile,
+                None => profiles.push(profile),
+            }
+        }
+        Self::new(profiles)
+    }
+
+    fn new(profiles: Vec<PlatformProfile>) -> Result<Self, RegistryError> {
+        let mut keys = HashSet::new();
+        for profile in &profiles {
+            let invalid = |reason: String| RegistryError::Invalid {
+                platform: profile.id.clone(),
+                reason,
+            };
+
+            let names = [&profile.id, &profile.name].into_iter().chain(&profile.aliases);
+            for key in names.map(|name| name.to_lowercase()).collect::<HashSet<_>>() {
+                if !keys.insert(key.clone()) {
+                    return Err(invalid(format!("'{}' is used by another platform", key)));
+                }
+            }
+
+            let length = &profile.content_length;
+            if !(length.min_words <= length.ideal_words && length.ideal_words <= length.max_words) {
+                return Err(invalid("content_length must satisfy min <= ideal <= max".to_string()));
+            }
+
+            let total: f64 = profile.scoring.iter().map(|rule| rule.weight).sum();
+            if profile.scoring.is_empty() || (total - 1.0).abs() > 1e-6 {
+                return Err(invalid(format!("scoring weights add up to {}, expected 1.0", total)));
+            }
+        }
+
+        Ok(Self { profiles })
+    }
+
+    pub fn profiles(&self) -> &[PlatformProfile] {
+        &self.profiles
+    }
+
+    /// Case-insensitive lookup by id, display name or alias
+    pub fn get(&self, name: &str) -> Option<&PlatformProfile> {
+        let name = name.trim();
+        self.profiles.iter().find(|profile| {
+            profile.id.eq_ignore_ascii_case(name)
+                || profile.name.eq_ignore_ascii_case(name)
+                || profile.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+        })
+    }
+
+    pub fn profile(&self, platform: &AIPlatform) -> Option<&PlatformProfile> {
+        self.profiles.iter().find(|profile| profile.id == platform.id())
+    }
+}
+
+/// A platform the installed registry knows, held as its profile id.
+///
+/// Deserializes from any id, display name or alias in
+/// `PlatformRegistry::installed()`, including loaded profiles, so request
+/// bodies naming an unknown platform are rejected before a handler runs.
+/// Serializes as the id.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PlatformId(String);
+
+impl PlatformId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// This platform's profile in the installed registry
+    pub fn profile(&self) -> &'static PlatformProfile {
+        PlatformRegistry::installed()
+            .get(&self.0)
+            .expect("platform ids come from the installed registry")
+    }
+}
+
+impl FromStr for PlatformId {
+    type Err = UnknownPlatformId;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PlatformRegistry::installed()
+            .get(s)
+            .map(|profile| Self(profile.id.clone()))
+            .ok_or_else(|| UnknownPlatformId(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for PlatformId {
+    type Error = UnknownPlatformId;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<PlatformId> for String {
+    fn from(platform: PlatformId) -> Self {
+        platform.0
+    }
+}
+
+impl fmt::Display for PlatformId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A platform name that matches no profile in the installed registry
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown platform '{0}', expected one of: {}", installed_platform_ids())]
+pub struct UnknownPlatformId(pub String);
+
+fn installed_platform_ids() -> String {
+    PlatformRegistry::installed()
+        .profiles()
+        .iter()
+        .map(|profile| profile.id.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_covers_every_platform() {
+        let registry = PlatformRegistry::builtin();
+        for platform in AIPlatform::all() {
+            let profile = registry.profile(&platform).unwrap();
+            assert_eq!(profile.name, platform.to_string());
+        }
+    }
+
+    #[test]
+    fn test_lookup_by_alias() {
+        let registry = PlatformRegistry::builtin();
+        assert_eq!(registry.get("Copilot").unwrap().id, "bing");
+        assert_eq!(registry.get("bing ai").unwrap().id, "bing");
+        assert!(registry.get("unknown").is_none());
+    }
+
+    #[test]
+    fn test_signal_fields_are_flattened() {
+        let rule = &PlatformRegistry::builtin().get("bing").unwrap().scoring[0];
+        assert_eq!(
+            rule.signal,
+            Signal::WordCount {
+                min: Some(300),
+                max: Some(1000)
+            }
+        );
+    }
+
+    #[test]
+    fn test_json_profiles() {
+        let mut profile = serde_json::to_value(PlatformRegistry::builtin().get("claude").unwrap()).unwrap();
+        profile["id"] = "meta".into();
+        profile["name"] = "Meta AI".into();
+        profile["aliases"] = serde_json::json!(["llama"]);
+
+        let registry =
+            PlatformRegistry::from_json(&serde_json::json!({ "platforms": [profile] }).to_string()).unwrap();
+        assert_eq!(registry.get("llama").unwrap().name, "Meta AI");
+    }
+
+    #[test]
+    fn test_platform_id_resolves_installed_profiles() {
+        // The bundled profiles plus one loaded at startup
+        let mut meta = PlatformRegistry::builtin().get("claude").unwrap().clone();
+        meta.id = "meta".to_string();
+        meta.name = "Meta AI".to_string();
+        meta.aliases = vec!["llama".to_string()];
+        let mut profiles = PlatformRegistry::builtin().profiles().to_vec();
+        profiles.push(meta);
+        PlatformRegistry::new(profiles).unwrap().install();
+
+        let loaded: PlatformId = serde_json::from_str(r#""Llama""#).unwrap();
+        assert_eq!(loaded.as_str(), "meta");
+        assert_eq!(loaded.profile().name, "Meta AI");
+        let bundled: PlatformId = serde_json::from_str(r#""Copilot""#).unwrap();
+        assert_eq!(serde_json::to_string(&bundled).unwrap(), r#""bing""#);
+
+        let err = serde_json::from_str::<PlatformId>(r#""grok""#).unwrap_err();
+        assert!(err.to_string().contains("unknown platform 'grok'"));
+        assert!(err.to_string().contains("meta"));
+    }
+
+    #[test]
+    fn test_rejects_bad_weights() {
+        let source = BUILTIN_PROFILES.replacen("weight = 0.3", "weight = 0.5", 1);
+        assert!(matches!(
+            PlatformRegistry::from_toml(&source),
+            Err(RegistryError::Invalid { .. })
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use uuid::Uuid;
+
+pub const SCHEMA_CONTEXT: &str = "https://schema.org";
 
 /// Schema.org types for AEO optimization (Chapter 8)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +16,53 @@ pub enum SchemaType {
     Person(PersonSchema),
     WebPage(WebPageSchema),
     BreadcrumbList(BreadcrumbListSchema),
+    Recipe(RecipeSchema),
+    Event(EventSchema),
+    LocalBusiness(LocalBusinessSchema),
+    Review(ReviewSchema),
+    VideoObject(VideoObjectSchema),
+    QAPage(QAPageSchema),
+    SoftwareApplication(SoftwareApplicationSchema),
+    Course(CourseSchema),
+    Dataset(DatasetSchema),
+    /// Markup of a type not modelled above, or that doesn't fit its typed
+    /// struct, kept as found
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+impl SchemaType {
+    /// The schema.org `@type`; for unknown markup, its first `@type` or
+    /// "Thing" when it has none
+    pub fn type_name(&self) -> &str {
+        match self {
+            Self::Article(_) => "Article",
+            Self::FAQPage(_) => "FAQPage",
+            Self::HowTo(_) => "HowTo",
+            Self::Product(_) => "Product",
+            Self::Service(_) => "Service",
+            Self::Organization(_) => "Organization",
+            Self::Person(_) => "Person",
+            Self::WebPage(_) => "WebPage",
+            Self::BreadcrumbList(_) => "BreadcrumbList",
+            Self::Recipe(_) => "Recipe",
+            Self::Event(_) => "Event",
+            Self::LocalBusiness(_) => "LocalBusiness",
+            Self::Review(_) => "Review",
+            Self::VideoObject(_) => "VideoObject",
+            Self::QAPage(_) => "QAPage",
+            Self::SoftwareApplication(_) => "SoftwareApplication",
+            Self::Course(_) => "Course",
+            Self::Dataset(_) => "Dataset",
+            Self::Unknown(value) => match value.get("@type") {
+                Some(serde_json::Value::String(name)) => name,
+                Some(serde_json::Value::Array(names)) => {
+                    names.iter().find_map(|name| name.as_str()).unwrap_or("Thing")
+                }
+                _ => "Thing",
+            },
+        }
+    }
 }
 
 /// Article schema for blog posts and guides
@@ -36,29 +84,47 @@ pub struct ArticleSchema {
 }
 
 impl ArticleSchema {
+    /// An article published now by the default publisher; use the builder
+    /// methods to set the real dates, publisher, images and URL
     pub fn new(headline: String, description: String, author_name: String) -> Self {
         Self {
-            context: "https://schema.org".to_string(),
+            context: SCHEMA_CONTEXT.to_string(),
             headline,
             description,
-            author: AuthorSchema {
-                schema_type: "Person".to_string(),
-                name: author_name,
-            },
+            author: AuthorSchema::person(author_name),
             date_published: Utc::now(),
             date_modified: None,
             image: vec![],
-            publisher: PublisherSchema {
-                schema_type: "Organization".to_string(),
-                name: "ASA Platform".to_string(),
-                logo: LogoSchema {
-                    schema_type: "ImageObject".to_string(),
-                    url: "/logo.png".to_string(),
-                },
-            },
+            publisher: PublisherSchema::new("ASA Platform".to_string(), "/logo.png".to_string()),
             main_entity_of_page: None,
         }
     }
+
+    pub fn published(mut self, published: DateTime<Utc>) -> Self {
+        self.date_published = published;
+        self
+    }
+
+    pub fn modified(mut self, modified: DateTime<Utc>) -> Self {
+        self.date_modified = Some(modified);
+        self
+    }
+
+    pub fn image(mut self, url: String) -> Self {
+        self.image.push(url);
+        self
+    }
+
+    pub fn publisher(mut self, name: String, logo_url: String) -> Self {
+        self.publisher = PublisherSchema::new(name, logo_url);
+        self
+    }
+
+    /// Canonical URL of the page the article is the main entity of
+    pub fn url(mut self, url: String) -> Self {
+        self.main_entity_of_page = Some(url);
+        self
+    }
 }
 
 /// FAQ Page schema for Q&A content (highly valuable for AEO)
@@ -89,11 +155,16 @@ pub struct FAQAnswer {
 impl FAQPageSchema {
     pub fn new() -> Self {
         Self {
-            context: "https://schema.org".to_string(),
+            context: SCHEMA_CONTEXT.to_string(),
             main_entity: vec![],
         }
     }
 
+    pub fn question(mut self, question: String, answer: String) -> Self {
+        self.add_question(question, answer);
+        self
+    }
+
     pub fn add_question(&mut self, question: String, answer: String) {
         self.main_entity.push(FAQItem {
             schema_type: "Question".to_string(),
@@ -106,6 +177,12 @@ impl FAQPageSchema {
     }
 }
 
+impl Default for FAQPageSchema {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// HowTo schema for tutorial content
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HowToSchema {
@@ -138,7 +215,7 @@ pub struct HowToStep {
 impl HowToSchema {
     pub fn new(name: String, description: String) -> Self {
         Self {
-            context: "https://schema.org".to_string(),
+            context: SCHEMA_CONTEXT.to_string(),
             name,
             description,
             step: vec![],
@@ -148,14 +225,43 @@ impl HowToSchema {
         }
     }
 
+    pub fn step(mut self, name: String, text: String) -> Self {
+        self.add_step(name, text);
+        self
+    }
+
+    /// Left out of the markup when empty
+    pub fn tools(mut self, tools: Vec<String>) -> Self {
+        self.tool = Some(tools).filter(|tools| !tools.is_empty());
+        self
+    }
+
+    /// Left out of the markup when empty
+    pub fn supplies(mut self, supplies: Vec<String>) -> Self {
+        self.supply = Some(supplies).filter(|supplies| !supplies.is_empty());
+        self
+    }
+
+    /// ISO 8601 duration, e.g. "PT1H30M"
+    pub fn total_time(mut self, total_time: String) -> Self {
+        self.total_time = Some(total_time);
+        self
+    }
+
     pub fn add_step(&mut self, name: String, text: String) {
-        self.step.push(HowToStep {
+        self.step.push(HowToStep::new(name, text));
+    }
+}
+
+impl HowToStep {
+    pub fn new(name: String, text: String) -> Self {
+        Self {
             schema_type: "HowToStep".to_string(),
             name,
             text,
             image: None,
             url: None,
-        });
+        }
     }
 }
 
@@ -182,7 +288,74 @@ pub struct OfferSchema {
     #[serde(rename = "priceCurrency")]
     pub price_currency: String,
     pub availability: String,  // "https://schema.org/InStock"
-    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl ProductSchema {
+    pub fn new(name: String, description: String, offers: OfferSchema) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            description,
+            image: vec![],
+            offers,
+            aggregate_rating: None,
+            brand: None,
+        }
+    }
+
+    pub fn image(mut self, url: String) -> Self {
+        self.image.push(url);
+        self
+    }
+
+    pub fn brand(mut self, name: String) -> Self {
+        self.brand = Some(BrandSchema {
+            schema_type: "Brand".to_string(),
+            name,
+        });
+        self
+    }
+
+    pub fn rating(mut self, rating_value: f32, review_count: u32) -> Self {
+        self.aggregate_rating = Some(AggregateRatingSchema::new(rating_value, review_count));
+        self
+    }
+}
+
+impl AggregateRatingSchema {
+    pub fn new(rating_value: f32, review_count: u32) -> Self {
+        Self {
+            schema_type: "AggregateRating".to_string(),
+            rating_value,
+            review_count,
+        }
+    }
+}
+
+impl OfferSchema {
+    /// An in-stock offer
+    pub fn new(price: String, price_currency: String) -> Self {
+        Self {
+            schema_type: "Offer".to_string(),
+            price,
+            price_currency,
+            availability: "https://schema.org/InStock".to_string(),
+            url: None,
+        }
+    }
+
+    /// A schema.org availability URL, e.g. "https://schema.org/PreOrder"
+    pub fn availability(mut self, availability: String) -> Self {
+        self.availability = availability;
+        self
+    }
+
+    pub fn url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -223,6 +396,33 @@ pub struct ProviderSchema {
     pub name: String,
 }
 
+impl ProviderSchema {
+    pub fn organization(name: String) -> Self {
+        Self {
+            schema_type: "Organization".to_string(),
+            name,
+        }
+    }
+}
+
+impl ServiceSchema {
+    pub fn new(name: String, description: String, provider_name: String, service_type: String) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            description,
+            provider: ProviderSchema::organization(provider_name),
+            service_type,
+            area_served: None,
+        }
+    }
+
+    pub fn area_served(mut self, areas: Vec<String>) -> Self {
+        self.area_served = Some(areas).filter(|areas| !areas.is_empty());
+        self
+    }
+}
+
 /// Organization schema
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrganizationSchema {
@@ -249,6 +449,40 @@ pub struct ContactPointSchema {
     pub email: Option<String>,
 }
 
+impl OrganizationSchema {
+    pub fn new(name: String, url: String, logo: String) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            url,
+            logo,
+            same_as: None,
+            contact_point: None,
+        }
+    }
+
+    /// Social media and other profiles of the organization
+    pub fn same_as(mut self, profiles: Vec<String>) -> Self {
+        self.same_as = Some(profiles).filter(|profiles| !profiles.is_empty());
+        self
+    }
+
+    pub fn contact_point(
+        mut self,
+        telephone: String,
+        contact_type: String,
+        email: Option<String>,
+    ) -> Self {
+        self.contact_point = Some(ContactPointSchema {
+            schema_type: "ContactPoint".to_string(),
+            telephone,
+            contact_type,
+            email,
+        });
+        self
+    }
+}
+
 /// Person schema for author profiles
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersonSchema {
@@ -265,6 +499,33 @@ pub struct PersonSchema {
     pub same_as: Option<Vec<String>>,
 }
 
+impl PersonSchema {
+    pub fn new(name: String) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            job_title: None,
+            url: None,
+            same_as: None,
+        }
+    }
+
+    pub fn job_title(mut self, job_title: String) -> Self {
+        self.job_title = Some(job_title);
+        self
+    }
+
+    pub fn url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    pub fn same_as(mut self, profiles: Vec<String>) -> Self {
+        self.same_as = Some(profiles).filter(|profiles| !profiles.is_empty());
+        self
+    }
+}
+
 /// WebPage schema
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebPageSchema {
@@ -279,6 +540,25 @@ pub struct WebPageSchema {
     pub date_modified: DateTime<Utc>,
 }
 
+impl WebPageSchema {
+    pub fn new(
+        name: String,
+        description: String,
+        url: String,
+        date_published: DateTime<Utc>,
+        date_modified: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            description,
+            url,
+            date_published,
+            date_modified,
+        }
+    }
+}
+
 /// Breadcrumb navigation schema
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BreadcrumbListSchema {
@@ -297,6 +577,808 @@ pub struct BreadcrumbItem {
     pub item: String,  // URL
 }
 
+impl BreadcrumbListSchema {
+    pub fn new() -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            item_list_element: vec![],
+        }
+    }
+
+    /// Append a crumb; positions are numbered from 1 in order of addition
+    pub fn item(mut self, name: String, url: String) -> Self {
+        self.item_list_element.push(BreadcrumbItem {
+            schema_type: "ListItem".to_string(),
+            position: self.item_list_element.len() as u32 + 1,
+            name,
+            item: url,
+        });
+        self
+    }
+}
+
+impl Default for BreadcrumbListSchema {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recipe schema with ingredients and instructions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeSchema {
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub image: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<AuthorSchema>,
+    #[serde(rename = "prepTime", skip_serializing_if = "Option::is_none")]
+    pub prep_time: Option<String>,  // ISO 8601 duration
+    #[serde(rename = "cookTime", skip_serializing_if = "Option::is_none")]
+    pub cook_time: Option<String>,
+    #[serde(rename = "totalTime", skip_serializing_if = "Option::is_none")]
+    pub total_time: Option<String>,
+    #[serde(rename = "recipeYield", skip_serializing_if = "Option::is_none")]
+    pub recipe_yield: Option<String>,
+    #[serde(rename = "recipeCategory", skip_serializing_if = "Option::is_none")]
+    pub recipe_category: Option<String>,
+    #[serde(rename = "recipeCuisine", skip_serializing_if = "Option::is_none")]
+    pub recipe_cuisine: Option<String>,
+    #[serde(rename = "recipeIngredient", default, skip_serializing_if = "Vec::is_empty")]
+    pub recipe_ingredient: Vec<String>,
+    #[serde(rename = "recipeInstructions", default, skip_serializing_if = "Vec::is_empty")]
+    pub recipe_instructions: Vec<HowToStep>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nutrition: Option<NutritionSchema>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NutritionSchema {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "NutritionInformation"
+    pub calories: String,  // e.g. "240 calories"
+}
+
+impl RecipeSchema {
+    pub fn new(name: String, description: String) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            description,
+            image: vec![],
+            author: None,
+            prep_time: None,
+            cook_time: None,
+            total_time: None,
+            recipe_yield: None,
+            recipe_category: None,
+            recipe_cuisine: None,
+            recipe_ingredient: vec![],
+            recipe_instructions: vec![],
+            nutrition: None,
+        }
+    }
+
+    pub fn image(mut self, url: String) -> Self {
+        self.image.push(url);
+        self
+    }
+
+    pub fn author(mut self, name: String) -> Self {
+        self.author = Some(AuthorSchema::person(name));
+        self
+    }
+
+    /// ISO 8601 durations; the total is the sum unless set separately
+    pub fn times(mut self, prep_time: String, cook_time: String) -> Self {
+        self.prep_time = Some(prep_time);
+        self.cook_time = Some(cook_time);
+        self
+    }
+
+    pub fn total_time(mut self, total_time: String) -> Self {
+        self.total_time = Some(total_time);
+        self
+    }
+
+    pub fn recipe_yield(mut self, recipe_yield: String) -> Self {
+        self.recipe_yield = Some(recipe_yield);
+        self
+    }
+
+    pub fn category(mut self, category: String, cuisine: Option<String>) -> Self {
+        self.recipe_category = Some(category);
+        self.recipe_cuisine = cuisine;
+        self
+    }
+
+    pub fn ingredient(mut self, ingredient: String) -> Self {
+        self.recipe_ingredient.push(ingredient);
+        self
+    }
+
+    pub fn instruction(mut self, name: String, text: String) -> Self {
+        self.recipe_instructions.push(HowToStep::new(name, text));
+        self
+    }
+
+    pub fn calories(mut self, calories: String) -> Self {
+        self.nutrition = Some(NutritionSchema {
+            schema_type: "NutritionInformation".to_string(),
+            calories,
+        });
+        self
+    }
+}
+
+/// Event schema for in-person, online and mixed events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventSchema {
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "startDate")]
+    pub start_date: DateTime<Utc>,
+    #[serde(rename = "endDate", skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<DateTime<Utc>>,
+    #[serde(rename = "eventStatus", skip_serializing_if = "Option::is_none")]
+    pub event_status: Option<String>,  // "https://schema.org/EventScheduled"
+    #[serde(rename = "eventAttendanceMode", skip_serializing_if = "Option::is_none")]
+    pub event_attendance_mode: Option<String>,  // "https://schema.org/OfflineEventAttendanceMode"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<PlaceSchema>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub image: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offers: Option<OfferSchema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organizer: Option<ProviderSchema>,
+}
+
+/// A physical `Place` or, for online events, a `VirtualLocation` with a URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaceSchema {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "Place" or "VirtualLocation"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<PostalAddressSchema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl EventSchema {
+    /// A scheduled event
+    pub fn new(name: String, start_date: DateTime<Utc>) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            description: None,
+            start_date,
+            end_date: None,
+            event_status: Some("https://schema.org/EventScheduled".to_string()),
+            event_attendance_mode: None,
+            location: None,
+            image: vec![],
+            offers: None,
+            organizer: None,
+        }
+    }
+
+    pub fn description(mut self, description: String) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn end_date(mut self, end_date: DateTime<Utc>) -> Self {
+        self.end_date = Some(end_date);
+        self
+    }
+
+    /// An in-person event at a venue
+    pub fn venue(mut self, name: String, address: PostalAddressSchema) -> Self {
+        self.location = Some(PlaceSchema {
+            schema_type: "Place".to_string(),
+            name: Some(name),
+            address: Some(address),
+            url: None,
+        });
+        self.event_attendance_mode =
+            Some("https://schema.org/OfflineEventAttendanceMode".to_string());
+        self
+    }
+
+    /// An online event
+    pub fn online(mut self, url: String) -> Self {
+        self.location = Some(PlaceSchema {
+            schema_type: "VirtualLocation".to_string(),
+            name: None,
+            address: None,
+            url: Some(url),
+        });
+        self.event_attendance_mode =
+            Some("https://schema.org/OnlineEventAttendanceMode".to_string());
+        self
+    }
+
+    pub fn image(mut self, url: String) -> Self {
+        self.image.push(url);
+        self
+    }
+
+    pub fn offers(mut self, offers: OfferSchema) -> Self {
+        self.offers = Some(offers);
+        self
+    }
+
+    pub fn organizer(mut self, name: String) -> Self {
+        self.organizer = Some(ProviderSchema::organization(name));
+        self
+    }
+}
+
+/// Local business schema with address, coordinates and opening hours
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalBusinessSchema {
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub address: PostalAddressSchema,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geo: Option<GeoCoordinatesSchema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub telephone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub image: Vec<String>,
+    #[serde(rename = "priceRange", skip_serializing_if = "Option::is_none")]
+    pub price_range: Option<String>,  // e.g. "$$"
+    #[serde(rename = "openingHours", default, skip_serializing_if = "Vec::is_empty")]
+    pub opening_hours: Vec<String>,  // e.g. "Mo-Fr 09:00-17:00"
+    #[serde(rename = "openingHoursSpecification", default, skip_serializing_if = "Vec::is_empty")]
+    pub opening_hours_specification: Vec<OpeningHoursSpecification>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PostalAddressSchema {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "PostalAddress"
+    #[serde(rename = "streetAddress", skip_serializing_if = "Option::is_none")]
+    pub street_address: Option<String>,
+    #[serde(rename = "addressLocality", skip_serializing_if = "Option::is_none")]
+    pub address_locality: Option<String>,
+    #[serde(rename = "addressRegion", skip_serializing_if = "Option::is_none")]
+    pub address_region: Option<String>,
+    #[serde(rename = "postalCode", skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    #[serde(rename = "addressCountry", skip_serializing_if = "Option::is_none")]
+    pub address_country: Option<String>,  // ISO 3166-1 alpha-2
+}
+
+impl PostalAddressSchema {
+    pub fn new(
+        street_address: String,
+        address_locality: String,
+        postal_code: String,
+        address_country: String,
+    ) -> Self {
+        Self {
+            schema_type: "PostalAddress".to_string(),
+            street_address: Some(street_address),
+            address_locality: Some(address_locality),
+            address_region: None,
+            postal_code: Some(postal_code),
+            address_country: Some(address_country),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoCoordinatesSchema {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "GeoCoordinates"
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpeningHoursSpecification {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "OpeningHoursSpecification"
+    #[serde(rename = "dayOfWeek")]
+    pub day_of_week: Vec<String>,  // "Monday", ...
+    pub opens: String,  // "09:00"
+    pub closes: String,
+}
+
+impl LocalBusinessSchema {
+    pub fn new(name: String, address: PostalAddressSchema) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            description: None,
+            address,
+            geo: None,
+            telephone: None,
+            url: None,
+            image: vec![],
+            price_range: None,
+            opening_hours: vec![],
+            opening_hours_specification: vec![],
+        }
+    }
+
+    pub fn description(mut self, description: String) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn geo(mut self, latitude: f64, longitude: f64) -> Self {
+        self.geo = Some(GeoCoordinatesSchema {
+            schema_type: "GeoCoordinates".to_string(),
+            latitude,
+            longitude,
+        });
+        self
+    }
+
+    pub fn telephone(mut self, telephone: String) -> Self {
+        self.telephone = Some(telephone);
+        self
+    }
+
+    pub fn url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    pub fn image(mut self, url: String) -> Self {
+        self.image.push(url);
+        self
+    }
+
+    pub fn price_range(mut self, price_range: String) -> Self {
+        self.price_range = Some(price_range);
+        self
+    }
+
+    /// Opening hours on the given days, times as "HH:MM"
+    pub fn open(mut self, days: Vec<String>, opens: String, closes: String) -> Self {
+        self.opening_hours_specification.push(OpeningHoursSpecification {
+            schema_type: "OpeningHoursSpecification".to_string(),
+            day_of_week: days,
+            opens,
+            closes,
+        });
+        self
+    }
+}
+
+/// Review schema for a rated item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewSchema {
+    #[serde(rename = "@context")]
+    pub context: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "itemReviewed")]
+    pub item_reviewed: ItemReviewedSchema,
+    #[serde(rename = "reviewRating")]
+    pub review_rating: RatingSchema,
+    pub author: AuthorSchema,
+    #[serde(rename = "reviewBody", skip_serializing_if = "Option::is_none")]
+    pub review_body: Option<String>,
+    #[serde(rename = "datePublished", skip_serializing_if = "Option::is_none")]
+    pub date_published: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemReviewedSchema {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "Product", "LocalBusiness", "Book", ...
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatingSchema {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "Rating"
+    #[serde(rename = "ratingValue")]
+    pub rating_value: f32,
+    #[serde(rename = "bestRating", skip_serializing_if = "Option::is_none")]
+    pub best_rating: Option<f32>,
+    #[serde(rename = "worstRating", skip_serializing_if = "Option::is_none")]
+    pub worst_rating: Option<f32>,
+}
+
+impl ReviewSchema {
+    /// A review rating the item out of 5
+    pub fn new(item_type: String, item_name: String, rating_value: f32, author_name: String) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name: None,
+            item_reviewed: ItemReviewedSchema {
+                schema_type: item_type,
+                name: item_name,
+            },
+            review_rating: RatingSchema {
+                schema_type: "Rating".to_string(),
+                rating_value,
+                best_rating: Some(5.0),
+                worst_rating: Some(1.0),
+            },
+            author: AuthorSchema::person(author_name),
+            review_body: None,
+            date_published: None,
+        }
+    }
+
+    pub fn name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Rating scale, when it is not 1 to 5
+    pub fn scale(mut self, worst_rating: f32, best_rating: f32) -> Self {
+        self.review_rating.worst_rating = Some(worst_rating);
+        self.review_rating.best_rating = Some(best_rating);
+        self
+    }
+
+    pub fn body(mut self, review_body: String) -> Self {
+        self.review_body = Some(review_body);
+        self
+    }
+
+    pub fn published(mut self, published: DateTime<Utc>) -> Self {
+        self.date_published = Some(published);
+        self
+    }
+}
+
+/// VideoObject schema for embedded or hosted videos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoObjectSchema {
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "thumbnailUrl")]
+    pub thumbnail_url: Vec<String>,
+    #[serde(rename = "uploadDate")]
+    pub upload_date: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,  // ISO 8601 duration
+    #[serde(rename = "contentUrl", skip_serializing_if = "Option::is_none")]
+    pub content_url: Option<String>,
+    #[serde(rename = "embedUrl", skip_serializing_if = "Option::is_none")]
+    pub embed_url: Option<String>,
+}
+
+impl VideoObjectSchema {
+    pub fn new(
+        name: String,
+        description: String,
+        thumbnail_url: String,
+        upload_date: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            description,
+            thumbnail_url: vec![thumbnail_url],
+            upload_date,
+            duration: None,
+            content_url: None,
+            embed_url: None,
+        }
+    }
+
+    pub fn duration(mut self, duration: String) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn content_url(mut self, url: String) -> Self {
+        self.content_url = Some(url);
+        self
+    }
+
+    pub fn embed_url(mut self, url: String) -> Self {
+        self.embed_url = Some(url);
+        self
+    }
+}
+
+/// QAPage schema for a single question with community answers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QAPageSchema {
+    #[serde(rename = "@context")]
+    pub context: String,
+    #[serde(rename = "mainEntity")]
+    pub main_entity: QuestionSchema,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionSchema {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "Question"
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(rename = "answerCount")]
+    pub answer_count: u32,
+    #[serde(rename = "acceptedAnswer", skip_serializing_if = "Option::is_none")]
+    pub accepted_answer: Option<AnswerSchema>,
+    #[serde(rename = "suggestedAnswer", default, skip_serializing_if = "Vec::is_empty")]
+    pub suggested_answer: Vec<AnswerSchema>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerSchema {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "Answer"
+    pub text: String,
+    #[serde(rename = "upvoteCount", skip_serializing_if = "Option::is_none")]
+    pub upvote_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl AnswerSchema {
+    pub fn new(text: String) -> Self {
+        Self {
+            schema_type: "Answer".to_string(),
+            text,
+            upvote_count: None,
+            url: None,
+        }
+    }
+}
+
+impl QAPageSchema {
+    pub fn new(question: String) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            main_entity: QuestionSchema {
+                schema_type: "Question".to_string(),
+                name: question,
+                text: None,
+                answer_count: 0,
+                accepted_answer: None,
+                suggested_answer: vec![],
+            },
+        }
+    }
+
+    /// Full text of the question, when the name is a summary
+    pub fn text(mut self, text: String) -> Self {
+        self.main_entity.text = Some(text);
+        self
+    }
+
+    pub fn accepted_answer(mut self, answer: AnswerSchema) -> Self {
+        if self.main_entity.accepted_answer.replace(answer).is_none() {
+            self.main_entity.answer_count += 1;
+        }
+        self
+    }
+
+    pub fn suggested_answer(mut self, answer: AnswerSchema) -> Self {
+        self.main_entity.suggested_answer.push(answer);
+        self.main_entity.answer_count += 1;
+        self
+    }
+}
+
+/// SoftwareApplication schema for apps and tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoftwareApplicationSchema {
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "applicationCategory")]
+    pub application_category: String,  // e.g. "BusinessApplication"
+    #[serde(rename = "operatingSystem", skip_serializing_if = "Option::is_none")]
+    pub operating_system: Option<String>,
+    #[serde(rename = "softwareVersion", skip_serializing_if = "Option::is_none")]
+    pub software_version: Option<String>,
+    #[serde(rename = "downloadUrl", skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offers: Option<OfferSchema>,
+    #[serde(rename = "aggregateRating", skip_serializing_if = "Option::is_none")]
+    pub aggregate_rating: Option<AggregateRatingSchema>,
+}
+
+impl SoftwareApplicationSchema {
+    pub fn new(name: String, application_category: String) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            description: None,
+            application_category,
+            operating_system: None,
+            software_version: None,
+            download_url: None,
+            offers: None,
+            aggregate_rating: None,
+        }
+    }
+
+    pub fn description(mut self, description: String) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn operating_system(mut self, operating_system: String) -> Self {
+        self.operating_system = Some(operating_system);
+        self
+    }
+
+    pub fn version(mut self, version: String) -> Self {
+        self.software_version = Some(version);
+        self
+    }
+
+    pub fn download_url(mut self, url: String) -> Self {
+        self.download_url = Some(url);
+        self
+    }
+
+    pub fn offers(mut self, offers: OfferSchema) -> Self {
+        self.offers = Some(offers);
+        self
+    }
+
+    pub fn rating(mut self, rating_value: f32, review_count: u32) -> Self {
+        self.aggregate_rating = Some(AggregateRatingSchema::new(rating_value, review_count));
+        self
+    }
+}
+
+/// Course schema for training content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CourseSchema {
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub name: String,
+    pub description: String,
+    pub provider: ProviderSchema,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offers: Option<OfferSchema>,
+    #[serde(rename = "hasCourseInstance", default, skip_serializing_if = "Vec::is_empty")]
+    pub has_course_instance: Vec<CourseInstanceSchema>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CourseInstanceSchema {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "CourseInstance"
+    #[serde(rename = "courseMode")]
+    pub course_mode: String,  // "Online", "Onsite" or "Blended"
+    #[serde(rename = "courseWorkload", skip_serializing_if = "Option::is_none")]
+    pub course_workload: Option<String>,  // ISO 8601 duration
+}
+
+impl CourseSchema {
+    pub fn new(name: String, description: String, provider_name: String) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            description,
+            provider: ProviderSchema::organization(provider_name),
+            offers: None,
+            has_course_instance: vec![],
+        }
+    }
+
+    pub fn offers(mut self, offers: OfferSchema) -> Self {
+        self.offers = Some(offers);
+        self
+    }
+
+    pub fn instance(mut self, course_mode: String, course_workload: Option<String>) -> Self {
+        self.has_course_instance.push(CourseInstanceSchema {
+            schema_type: "CourseInstance".to_string(),
+            course_mode,
+            course_workload,
+        });
+        self
+    }
+}
+
+/// Dataset schema for published data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetSchema {
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator: Option<ProviderSchema>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub distribution: Vec<DataDownloadSchema>,
+    #[serde(rename = "temporalCoverage", skip_serializing_if = "Option::is_none")]
+    pub temporal_coverage: Option<String>,  // ISO 8601 interval, e.g. "2020-01-01/2020-12-31"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataDownloadSchema {
+    #[serde(rename = "@type")]
+    pub schema_type: String,  // "DataDownload"
+    #[serde(rename = "encodingFormat")]
+    pub encoding_format: String,  // MIME type, e.g. "text/csv"
+    #[serde(rename = "contentUrl")]
+    pub content_url: String,
+}
+
+impl DatasetSchema {
+    pub fn new(name: String, description: String) -> Self {
+        Self {
+            context: SCHEMA_CONTEXT.to_string(),
+            name,
+            description,
+            url: None,
+            keywords: vec![],
+            license: None,
+            creator: None,
+            distribution: vec![],
+            temporal_coverage: None,
+        }
+    }
+
+    pub fn url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    pub fn keywords(mut self, keywords: Vec<String>) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    pub fn license(mut self, license: String) -> Self {
+        self.license = Some(license);
+        self
+    }
+
+    pub fn creator(mut self, name: String) -> Self {
+        self.creator = Some(ProviderSchema::organization(name));
+        self
+    }
+
+    pub fn download(mut self, encoding_format: String, content_url: String) -> Self {
+        self.distribution.push(DataDownloadSchema {
+            schema_type: "DataDownload".to_string(),
+            encoding_format,
+            content_url,
+        });
+        self
+    }
+
+    pub fn temporal_coverage(mut self, coverage: String) -> Self {
+        self.temporal_coverage = Some(coverage);
+        self
+    }
+}
+
 // Helper types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthorSchema {
@@ -305,6 +1387,15 @@ pub struct AuthorSchema {
     pub name: String,
 }
 
+impl AuthorSchema {
+    pub fn person(name: String) -> Self {
+        Self {
+            schema_type: "Person".to_string(),
+            name,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PublisherSchema {
     #[serde(rename = "@type")]
@@ -313,6 +1404,19 @@ pub struct PublisherSchema {
     pub logo: LogoSchema,
 }
 
+impl PublisherSchema {
+    pub fn new(name: String, logo_url: String) -> Self {
+        Self {
+            schema_type: "Organization".to_string(),
+            name,
+            logo: LogoSchema {
+                schema_type: "ImageObject".to_string(),
+                url: logo_url,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogoSchema {
     #[serde(rename = "@type")]
@@ -324,6 +1428,29 @@ pub struct LogoSchema {
 pub struct SchemaGenerator;
 
 impl SchemaGenerator {
+    /// One schema as a JSON-LD object
+    pub fn to_value(schema: &SchemaType) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(schema)
+    }
+
+    /// Several schemas as a single JSON-LD document: one `@context` and the
+    /// schemas as nodes of its `@graph`
+    pub fn graph(schemas: &[SchemaType]) -> Result<serde_json::Value, serde_json::Error> {
+        let mut graph = Vec::with_capacity(schemas.len());
+        for schema in schemas {
+            let mut node = Self::to_value(schema)?;
+            if let Some(node) = node.as_object_mut() {
+                node.remove("@context");
+            }
+            graph.push(node);
+        }
+
+        Ok(serde_json::json!({
+            "@context": SCHEMA_CONTEXT,
+            "@graph": graph,
+        }))
+    }
+
     /// Generate JSON-LD script tag
     pub fn to_json_ld(schema: &SchemaType) -> Result<String, serde_json::Error> {
         let json = serde_json::to_string_pretty(schema)?;
@@ -400,4 +1527,155 @@ mod tests {
         assert!(json_ld.contains(r#"<script type="application/ld+json">"#));
         assert!(json_ld.contains("Test"));
     }
+
+    #[test]
+    fn test_article_builder() {
+        let published = "2024-03-01T09:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let modified = "2024-03-05T12:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let article = SchemaType::Article(
+            ArticleSchema::new("AEO".to_string(), "Desc".to_string(), "Jane".to_string())
+                .published(published)
+                .modified(modified)
+                .image("https://example.com/aeo.png".to_string())
+                .publisher("Example".to_string(), "https://example.com/logo.png".to_string())
+                .url("https://example.com/aeo".to_string()),
+        );
+
+        let json = SchemaGenerator::to_value(&article).unwrap();
+        assert_eq!(json["@type"], "Article");
+        assert_eq!(json["@context"], SCHEMA_CONTEXT);
+        assert_eq!(json["author"]["name"], "Jane");
+        assert_eq!(json["datePublished"], "2024-03-01T09:00:00Z");
+        assert_eq!(json["dateModified"], "2024-03-05T12:30:00Z");
+        assert_eq!(json["image"][0], "https://example.com/aeo.png");
+        assert_eq!(json["publisher"]["logo"]["url"], "https://example.com/logo.png");
+        assert_eq!(json["mainEntityOfPage"], "https://example.com/aeo");
+    }
+
+    #[test]
+    fn test_howto_builder_skips_empty_lists() {
+        let howto = HowToSchema::new("Brew".to_string(), "Coffee".to_string())
+            .step("Grind".to_string(), "Grind the beans".to_string())
+            .tools(vec!["Grinder".to_string()])
+            .supplies(vec![])
+            .total_time("PT10M".to_string());
+
+        let json = SchemaGenerator::to_value(&SchemaType::HowTo(howto)).unwrap();
+        assert_eq!(json["step"][0]["@type"], "HowToStep");
+        assert_eq!(json["tool"][0], "Grinder");
+        assert!(json.get("supply").is_none());
+        assert_eq!(json["totalTime"], "PT10M");
+    }
+
+    #[test]
+    fn test_graph() {
+        let schemas = vec![
+            SchemaType::FAQPage(
+                FAQPageSchema::new().question("What is AEO?".to_string(), "Answers".to_string()),
+            ),
+            SchemaType::Product(
+                ProductSchema::new(
+                    "Widget".to_string(),
+                    "A widget".to_string(),
+                    OfferSchema::new("9.99".to_string(), "EUR".to_string()),
+                )
+                .brand("Acme".to_string()),
+            ),
+            SchemaType::BreadcrumbList(
+                BreadcrumbListSchema::new()
+                    .item("Home".to_string(), "https://example.com".to_string())
+                    .item("Blog".to_string(), "https://example.com/blog".to_string()),
+            ),
+        ];
+
+        let graph = SchemaGenerator::graph(&schemas).unwrap();
+        assert_eq!(graph["@context"], SCHEMA_CONTEXT);
+        let nodes = graph["@graph"].as_array().unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert!(nodes.iter().all(|node| node.get("@context").is_none()));
+        assert_eq!(nodes[1]["offers"]["priceCurrency"], "EUR");
+        assert!(nodes[1]["offers"].get("url").is_none());
+        assert_eq!(nodes[2]["itemListElement"][1]["position"], 2);
+
+        let types: Vec<_> = schemas.iter().map(SchemaType::type_name).collect();
+        assert_eq!(types, ["FAQPage", "Product", "BreadcrumbList"]);
+    }
+
+    #[test]
+    fn test_new_types_round_trip() {
+        let date = DateTime::parse_from_rfc3339("2024-05-01T18:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let address = PostalAddressSchema::new(
+            "1 Main St".to_string(),
+            "Springfield".to_string(),
+            "12345".to_string(),
+            "US".to_string(),
+        );
+        let schemas = vec![
+            SchemaType::Recipe(
+                RecipeSchema::new("Pancakes".to_string(), "Fluffy pancakes".to_string())
+                    .times("PT10M".to_string(), "PT15M".to_string())
+                    .total_time("PT25M".to_string())
+                    .ingredient("2 eggs".to_string())
+                    .instruction("Mix".to_string(), "Whisk everything together".to_string())
+                    .calories("240 calories".to_string()),
+            ),
+            SchemaType::Event(
+                EventSchema::new("AEO Summit".to_string(), date)
+                    .venue("Town Hall".to_string(), address.clone())
+                    .offers(OfferSchema::new("20.00".to_string(), "USD".to_string())),
+            ),
+            SchemaType::LocalBusiness(
+                LocalBusinessSchema::new("Corner Cafe".to_string(), address)
+                    .geo(40.7, -74.0)
+                    .open(vec!["Monday".to_string()], "08:00".to_string(), "17:00".to_string()),
+            ),
+            SchemaType::Review(
+                ReviewSchema::new("Product".to_string(), "Widget".to_string(), 4.5, "Jane".to_string())
+                    .body("Works well".to_string())
+                    .published(date),
+            ),
+            SchemaType::VideoObject(
+                VideoObjectSchema::new(
+                    "Intro to AEO".to_string(),
+                    "A short intro".to_string(),
+                    "https://example.com/thumb.jpg".to_string(),
+                    date,
+                )
+                .duration("PT4M".to_string()),
+            ),
+            SchemaType::QAPage(
+                QAPageSchema::new("How do I start with AEO?".to_string())
+                    .accepted_answer(AnswerSchema::new("Answer questions directly.".to_string()))
+                    .suggested_answer(AnswerSchema::new("Add FAQ schema.".to_string())),
+            ),
+            SchemaType::SoftwareApplication(
+                SoftwareApplicationSchema::new("ASA".to_string(), "BusinessApplication".to_string())
+                    .operating_system("Web".to_string())
+                    .rating(4.8, 120),
+            ),
+            SchemaType::Course(
+                CourseSchema::new("AEO 101".to_string(), "Basics".to_string(), "ASA".to_string())
+                    .instance("Online".to_string(), Some("PT3H".to_string())),
+            ),
+            SchemaType::Dataset(
+                DatasetSchema::new("Citations".to_string(), "AI citations by platform".to_string())
+                    .download("text/csv".to_string(), "https://example.com/data.csv".to_string()),
+            ),
+        ];
+
+        for schema in &schemas {
+            let json = SchemaGenerator::to_value(schema).unwrap();
+            assert_eq!(json["@type"], schema.type_name());
+            let parsed: SchemaType = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(parsed.type_name(), schema.type_name());
+            assert_eq!(SchemaGenerator::to_value(&parsed).unwrap(), json);
+        }
+
+        let qa = SchemaGenerator::to_value(&schemas[5]).unwrap();
+        assert_eq!(qa["mainEntity"]["answerCount"], 2);
+        let event = SchemaGenerator::to_value(&schemas[1]).unwrap();
+        assert_eq!(event["location"]["address"]["addressCountry"], "US");
+    }
 }
@@ -0,0 +1,866 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::schema::SCHEMA_CONTEXT;
+
+/// How serious a diagnostic is: errors make an item ineligible for rich
+/// results, warnings only limit how it can be shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem found in a JSON-LD document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// JSON path of the offending value, e.g. `$.offers.priceCurrency`
+    pub path: String,
+    pub schema_type: Option<String>,
+    pub message: String,
+}
+
+/// A typed item found in the document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedItem {
+    pub path: String,
+    pub schema_type: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub items: Vec<DetectedItem>,
+    pub errors: Vec<Diagnostic>,
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    pub fn error(&mut self, path: &str, schema_type: Option<&str>, message: impl Into<String>) {
+        self.push(Severity::Error, path, schema_type, message.into());
+    }
+
+    pub fn warning(&mut self, path: &str, schema_type: Option<&str>, message: impl Into<String>) {
+        self.push(Severity::Warning, path, schema_type, message.into());
+    }
+
+    fn push(&mut self, severity: Severity, path: &str, schema_type: Option<&str>, message: String) {
+        let diagnostic = Diagnostic {
+            severity,
+            path: path.to_string(),
+            schema_type: schema_type.map(String::from),
+            message,
+        };
+        match severity {
+            Severity::Error => self.errors.push(diagnostic),
+            Severity::Warning => self.warnings.push(diagnostic),
+        }
+        self.valid = self.errors.is_empty();
+    }
+}
+
+/// Required and recommended properties of a schema.org type
+struct TypeRules {
+    required: &'static [&'static str],
+    recommended: &'static [&'static str],
+}
+
+/// Checks JSON-LD against the properties answer engines and rich results
+/// rely on, per schema.org type
+pub struct SchemaValidator;
+
+impl SchemaValidator {
+    /// Validate a JSON-LD document: a single node, an array of nodes, or an
+    /// object with an `@graph`. Null entries in an array are skipped.
+    pub fn validate(document: &Value) -> ValidationReport {
+        let mut report = ValidationReport {
+            valid: true,
+            ..Default::default()
+        };
+
+        match document {
+            Value::Array(nodes) => {
+                for (i, node) in nodes.iter().enumerate() {
+                    if !node.is_null() {
+                        Self::check_document(&index(ROOT, i), node, &mut report);
+                    }
+                }
+            }
+            node => Self::check_document(ROOT, node, &mut report),
+        }
+
+        if report.items.is_empty() && report.errors.is_empty() {
+            report.error(ROOT, None, "No schema.org items found");
+        }
+        report
+    }
+
+    /// A top-level node: it must declare the schema.org context, either
+    /// itself or on its enclosing `@graph`
+    fn check_document(path: &str, node: &Value, report: &mut ValidationReport) {
+        let Some(object) = node.as_object() else {
+            report.error(path, None, format!("Expected a JSON-LD object, found {}", kind(node)));
+            return;
+        };
+
+        match object.get("@context") {
+            None => report.error(path, None, "Missing @context, expected \"https://schema.org\""),
+            Some(context) if !is_schema_context(context) => report.error(
+                &property(path, "@context"),
+                None,
+                format!("Unexpected @context {}, expected \"https://schema.org\"", context),
+            ),
+            Some(_) => {}
+        }
+
+        match object.get("@graph") {
+            Some(Value::Array(nodes)) => {
+                let graph = property(path, "@graph");
+                for (i, node) in nodes.iter().enumerate() {
+                    Self::check_node(&index(&graph, i), node, report);
+                }
+            }
+            Some(other) => report.error(
+                &property(path, "@graph"),
+                None,
+                format!("@graph must be an array, found {}", kind(other)),
+            ),
+            None => Self::check_node(path, node, report),
+        }
+    }
+
+    fn check_node(path: &str, node: &Value, report: &mut ValidationReport) {
+        let Some(object) = node.as_object() else {
+            report.error(path, None, format!("Expected an object, found {}", kind(node)));
+            return;
+        };
+
+        let types: Vec<&str> = match object.get("@type") {
+            Some(Value::String(schema_type)) => vec![schema_type],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        if types.is_empty() {
+            // A bare reference to a node defined elsewhere is fine
+            if !object.contains_key("@id") {
+                report.error(path, None, "Missing @type");
+            }
+            return;
+        }
+
+        for schema_type in types {
+            let schema_type = schema_type.trim_start_matches("schema:");
+            report.items.push(DetectedItem {
+                path: path.to_string(),
+                schema_type: schema_type.to_string(),
+            });
+
+            let Some(rules) = rules(schema_type) else {
+                report.warning(
+                    path,
+                    Some(schema_type),
+                    format!("{} is not a type this validator checks", schema_type),
+                );
+                continue;
+            };
+
+            for name in rules.required {
+                if is_missing(node.get(name)) {
+                    report.error(
+                        &property(path, name),
+                        Some(schema_type),
+                        format!("Missing required property \"{}\"", name),
+                    );
+                }
+            }
+            for name in rules.recommended {
+                if is_missing(node.get(name)) {
+                    report.warning(
+                        &property(path, name),
+                        Some(schema_type),
+                        format!("Missing recommended property \"{}\"", name),
+                    );
+                }
+            }
+
+            check_type(schema_type, path, node, report);
+        }
+    }
+}
+
+const ROOT: &str = "$";
+
+fn rules(schema_type: &str) -> Option<TypeRules> {
+    let rules = match schema_type {
+        "Article" | "NewsArticle" | "BlogPosting" | "TechArticle" | "ScholarlyArticle" => {
+            TypeRules {
+                required: &["headline", "image", "author", "datePublished"],
+                recommended: &["dateModified", "publisher", "description", "mainEntityOfPage"],
+            }
+        }
+        "FAQPage" => TypeRules {
+            required: &["mainEntity"],
+            recommended: &[],
+        },
+        "HowTo" => TypeRules {
+            required: &["name", "step"],
+            recommended: &["description", "totalTime", "image", "supply", "tool"],
+        },
+        "Product" => TypeRules {
+            required: &["name"],
+            recommended: &["image", "description", "brand", "offers"],
+        },
+        "Service" => TypeRules {
+            required: &["name"],
+            recommended: &["description", "provider", "serviceType", "areaServed"],
+        },
+        "Organization" | "Corporation" => TypeRules {
+            required: &["name"],
+            recommended: &["url", "logo", "sameAs"],
+        },
+        "Person" => TypeRules {
+            required: &["name"],
+            recommended: &["url", "sameAs"],
+        },
+        "WebPage" => TypeRules {
+            required: &["name"],
+            recommended: &["description", "url", "datePublished", "dateModified"],
+        },
+        "BreadcrumbList" => TypeRules {
+            required: &["itemListElement"],
+            recommended: &[],
+        },
+        "Recipe" => TypeRules {
+            required: &["name", "image"],
+            recommended: &[
+                "description",
+                "author",
+                "prepTime",
+                "cookTime",
+                "totalTime",
+                "recipeYield",
+                "recipeIngredient",
+                "recipeInstructions",
+                "nutrition",
+            ],
+        },
+        "Event" => TypeRules {
+            required: &["name", "startDate", "location"],
+            recommended: &["description", "endDate", "eventStatus", "image", "offers", "organizer"],
+        },
+        "LocalBusiness" | "Restaurant" | "Store" => TypeRules {
+            required: &["name", "address"],
+            recommended: &["telephone", "url", "geo", "openingHoursSpecification", "image", "priceRange"],
+        },
+        "Review" => TypeRules {
+            required: &["itemReviewed", "reviewRating", "author"],
+            recommended: &["reviewBody", "datePublished"],
+        },
+        "VideoObject" => TypeRules {
+            required: &["name", "thumbnailUrl", "uploadDate"],
+            recommended: &["description", "duration", "contentUrl", "embedUrl"],
+        },
+        "QAPage" => TypeRules {
+            required: &["mainEntity"],
+            recommended: &[],
+        },
+        "SoftwareApplication" | "MobileApplication" | "WebApplication" => TypeRules {
+            required: &["name", "offers"],
+            recommended: &["applicationCategory", "operatingSystem", "aggregateRating"],
+        },
+        "Course" => TypeRules {
+            required: &["name", "description"],
+            recommended: &["provider", "offers", "hasCourseInstance"],
+        },
+        "Dataset" => TypeRules {
+            required: &["name", "description"],
+            recommended: &["url", "keywords", "license", "creator", "distribution"],
+        },
+        _ => return None,
+    };
+    Some(rules)
+}
+
+/// Checks beyond property presence
+fn check_type(schema_type: &str, path: &str, node: &Value, report: &mut ValidationReport) {
+    let t = Some(schema_type);
+    match schema_type {
+        "Article" | "NewsArticle" | "BlogPosting" | "TechArticle" | "ScholarlyArticle" => {
+            if let Some(headline) = node.get("headline").and_then(Value::as_str) {
+                if headline.chars().count() > 110 {
+                    report.warning(
+                        &property(path, "headline"),
+                        t,
+                        "Headline is longer than 110 characters and may be truncated",
+                    );
+                }
+            }
+            check_urls(&property(path, "image"), node.get("image"), t, report);
+            for name in ["datePublished", "dateModified"] {
+                check_date(&property(path, name), node.get(name), t, report);
+            }
+        }
+        "FAQPage" => {
+            let questions = property(path, "mainEntity");
+            for_each(&questions, node.get("mainEntity"), |path, question| {
+                if is_missing(question.get("name")) {
+                    report.error(&property(path, "name"), t, "Question is missing its \"name\"");
+                }
+                let answer = property(path, "acceptedAnswer");
+                match question.get("acceptedAnswer") {
+                    None => report.error(&answer, t, "Question is missing \"acceptedAnswer\""),
+                    Some(accepted) => for_each(&answer, Some(accepted), |path, accepted| {
+                        if is_missing(accepted.get("text")) {
+                            report.error(&property(path, "text"), t, "Answer is missing its \"text\"");
+                        }
+                    }),
+                }
+            });
+        }
+        "HowTo" => {
+            let steps = property(path, "step");
+            for_each(&steps, node.get("step"), |path, step| {
+                // A step can be a HowToSection grouping further steps
+                if step.is_string() || !is_missing(step.get("itemListElement")) {
+                    return;
+                }
+                if is_missing(step.get("text")) {
+                    report.error(&property(path, "text"), t, "Step is missing its \"text\"");
+                }
+            });
+            check_duration(path, "totalTime", node, t, report);
+        }
+        "Product" => {
+            if ["offers", "review", "aggregateRating"]
+                .iter()
+                .all(|name| is_missing(node.get(name)))
+            {
+                report.error(
+                    path,
+                    t,
+                    "Product needs at least one of \"offers\", \"review\" or \"aggregateRating\"",
+                );
+            }
+            check_urls(&property(path, "image"), node.get("image"), t, report);
+            let offers = property(path, "offers");
+            for_each(&offers, node.get("offers"), |path, offer| check_offer(path, offer, report));
+        }
+        "Organization" | "Corporation" => {
+            check_urls(&property(path, "url"), node.get("url"), t, report);
+            check_urls(&property(path, "logo"), node.get("logo"), t, report);
+        }
+        "WebPage" => {
+            check_urls(&property(path, "url"), node.get("url"), t, report);
+            for name in ["datePublished", "dateModified"] {
+                check_date(&property(path, name), node.get(name), t, report);
+            }
+        }
+        "BreadcrumbList" => {
+            let items = property(path, "itemListElement");
+            let count = node
+                .get("itemListElement")
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+            let mut position = 0;
+            for_each(&items, node.get("itemListElement"), |path, item| {
+                position += 1;
+                match item.get("position").and_then(Value::as_u64) {
+                    None => report.error(
+                        &property(path, "position"),
+                        t,
+                        "ListItem needs an integer \"position\"",
+                    ),
+                    Some(found) if found != position => report.warning(
+                        &property(path, "position"),
+                        t,
+                        format!("Expected position {}, found {}", position, found),
+                    ),
+                    Some(_) => {}
+                }
+                if is_missing(item.get("name")) && is_missing(item.pointer("/item/name")) {
+                    report.error(&property(path, "name"), t, "ListItem is missing its \"name\"");
+                }
+                // The last crumb is the current page and may omit its URL
+                if position < count as u64 && is_missing(item.get("item")) {
+                    report.error(&property(path, "item"), t, "ListItem is missing its \"item\" URL");
+                }
+            });
+        }
+        "Recipe" => {
+            check_urls(&property(path, "image"), node.get("image"), t, report);
+            for name in ["prepTime", "cookTime", "totalTime"] {
+                check_duration(path, name, node, t, report);
+            }
+        }
+        "Event" => {
+            for name in ["startDate", "endDate"] {
+                check_date(&property(path, name), node.get(name), t, report);
+            }
+            let location = property(path, "location");
+            for_each(&location, node.get("location"), |path, place| {
+                let virtual_location = place.get("@type").and_then(Value::as_str) == Some("VirtualLocation");
+                if virtual_location && is_missing(place.get("url")) {
+                    report.error(&property(path, "url"), t, "VirtualLocation is missing its \"url\"");
+                } else if !virtual_location && !place.is_string() && is_missing(place.get("address")) {
+                    report.error(&property(path, "address"), t, "Place is missing its \"address\"");
+                }
+            });
+            let offers = property(path, "offers");
+            for_each(&offers, node.get("offers"), |path, offer| check_offer(path, offer, report));
+        }
+        "LocalBusiness" | "Restaurant" | "Store" => {
+            check_urls(&property(path, "url"), node.get("url"), t, report);
+            if let Some(geo) = node.get("geo") {
+                for (name, limit) in [("latitude", 90.0), ("longitude", 180.0)] {
+                    let valid = match geo.get(name) {
+                        Some(Value::Number(n)) => n.as_f64(),
+                        Some(Value::String(n)) => n.parse().ok(),
+                        _ => None,
+                    }
+                    .is_some_and(|n: f64| n.abs() <= limit);
+                    if !valid {
+                        report.error(
+                            &property(&property(path, "geo"), name),
+                            t,
+                            format!("{} must be a number between -{} and {}", name, limit, limit),
+                        );
+                    }
+                }
+            }
+        }
+        "Review" => {
+            if let Some(rating) = node.get("reviewRating") {
+                let path = property(path, "reviewRating");
+                let number = |name: &str| match rating.get(name) {
+                    Some(Value::Number(n)) => n.as_f64(),
+                    Some(Value::String(n)) => n.parse().ok(),
+                    _ => None,
+                };
+                match number("ratingValue") {
+                    None => report.error(&property(&path, "ratingValue"), t, "Rating needs a numeric \"ratingValue\""),
+                    Some(value) => {
+                        let best = number("bestRating").unwrap_or(5.0);
+                        let worst = number("worstRating").unwrap_or(1.0);
+                        if value < worst || value > best {
+                            report.error(
+                                &property(&path, "ratingValue"),
+                                t,
+                                format!("ratingValue {} is outside the scale {} to {}", value, worst, best),
+                            );
+                        }
+                    }
+                }
+            }
+            if is_missing(node.pointer("/itemReviewed/name")) {
+                report.error(&property(path, "itemReviewed"), t, "itemReviewed is missing its \"name\"");
+            }
+            check_date(&property(path, "datePublished"), node.get("datePublished"), t, report);
+        }
+        "VideoObject" => {
+            check_urls(&property(path, "thumbnailUrl"), node.get("thumbnailUrl"), t, report);
+            check_date(&property(path, "uploadDate"), node.get("uploadDate"), t, report);
+            check_duration(path, "duration", node, t, report);
+            if is_missing(node.get("contentUrl")) && is_missing(node.get("embedUrl")) {
+                report.warning(path, t, "Add \"contentUrl\" or \"embedUrl\" so the video can be fetched");
+            }
+        }
+        "QAPage" => {
+            let question = property(path, "mainEntity");
+            for_each(&question, node.get("mainEntity"), |path, question| {
+                if is_missing(question.get("name")) {
+                    report.error(&property(path, "name"), t, "Question is missing its \"name\"");
+                }
+                if question.get("answerCount").and_then(Value::as_u64).is_none() {
+                    report.error(&property(path, "answerCount"), t, "Question needs an integer \"answerCount\"");
+                }
+                if is_missing(question.get("acceptedAnswer")) && is_missing(question.get("suggestedAnswer")) {
+                    report.error(path, t, "Question needs an \"acceptedAnswer\" or \"suggestedAnswer\"");
+                }
+                for name in ["acceptedAnswer", "suggestedAnswer"] {
+                    for_each(&property(path, name), question.get(name), |path, answer| {
+                        if is_missing(answer.get("text")) {
+                            report.error(&property(path, "text"), t, "Answer is missing its \"text\"");
+                        }
+                    });
+                }
+            });
+        }
+        "SoftwareApplication" | "MobileApplication" | "WebApplication" => {
+            let offers = property(path, "offers");
+            for_each(&offers, node.get("offers"), |path, offer| check_offer(path, offer, report));
+        }
+        "Course" => {
+            let instances = property(path, "hasCourseInstance");
+            for_each(&instances, node.get("hasCourseInstance"), |path, instance| {
+                if is_missing(instance.get("courseMode")) {
+                    report.error(&property(path, "courseMode"), t, "CourseInstance is missing \"courseMode\"");
+                }
+                check_duration(path, "courseWorkload", instance, t, report);
+            });
+        }
+        "Dataset" => {
+            check_urls(&property(path, "url"), node.get("url"), t, report);
+            let distribution = property(path, "distribution");
+            for_each(&distribution, node.get("distribution"), |path, download| {
+                if is_missing(download.get("contentUrl")) {
+                    report.error(&property(path, "contentUrl"), t, "DataDownload is missing \"contentUrl\"");
+                }
+                if is_missing(download.get("encodingFormat")) {
+                    report.warning(&property(path, "encodingFormat"), t, "Missing recommended property \"encodingFormat\"");
+                }
+            });
+        }
+        _ => {}
+    }
+}
+
+fn check_offer(path: &str, offer: &Value, report: &mut ValidationReport) {
+    let t = Some("Offer");
+
+    if is_missing(offer.get("price")) && is_missing(offer.get("priceSpecification")) {
+        report.error(&property(path, "price"), t, "Offer is missing \"price\"");
+    } else if let Some(price) = offer.get("price") {
+        let numeric = match price {
+            Value::Number(_) => true,
+            Value::String(price) => price.parse::<f64>().is_ok(),
+            _ => false,
+        };
+        if !numeric {
+            report.error(
+                &property(path, "price"),
+                t,
+                format!("price {} is not a number; use \".\" for decimals and no currency symbol", price),
+            );
+        }
+    }
+
+    match offer.get("priceCurrency").and_then(Value::as_str) {
+        None => report.error(&property(path, "priceCurrency"), t, "Offer is missing \"priceCurrency\""),
+        Some(currency) if !ISO_4217.contains(&currency) => report.error(
+            &property(path, "priceCurrency"),
+            t,
+            format!("\"{}\" is not an ISO 4217 currency code", currency),
+        ),
+        Some(_) => {}
+    }
+
+    match offer.get("availability").and_then(Value::as_str) {
+        None => report.warning(&property(path, "availability"), t, "Missing recommended property \"availability\""),
+        Some(availability) => {
+            let value = availability
+                .strip_prefix("https://schema.org/")
+                .or_else(|| availability.strip_prefix("http://schema.org/"));
+            if !value.is_some_and(|value| AVAILABILITY.contains(&value)) {
+                report.error(
+                    &property(path, "availability"),
+                    t,
+                    format!(
+                        "\"{}\" is not a schema.org ItemAvailability URL such as \"https://schema.org/InStock\"",
+                        availability
+                    ),
+                );
+            }
+        }
+    }
+
+    check_urls(&property(path, "url"), offer.get("url"), t, report);
+}
+
+/// `node.<name>`, when present, is an ISO 8601 duration
+fn check_duration(path: &str, name: &str, node: &Value, schema_type: Option<&str>, report: &mut ValidationReport) {
+    let Some(value) = node.get(name) else {
+        return;
+    };
+    if !value.as_str().is_some_and(is_iso_duration) {
+        report.error(
+            &property(path, name),
+            schema_type,
+            format!("{} {} is not an ISO 8601 duration such as \"PT1H30M\"", name, value),
+        );
+    }
+}
+
+fn check_date(path: &str, value: Option<&Value>, schema_type: Option<&str>, report: &mut ValidationReport) {
+    let Some(value) = value else {
+        return;
+    };
+    let valid = value.as_str().is_some_and(|date| {
+        DateTime::parse_from_rfc3339(date).is_ok()
+            || NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S").is_ok()
+            || NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+    });
+    if !valid {
+        report.error(path, schema_type, format!("{} is not an ISO 8601 date", value));
+    }
+}
+
+/// URLs may be strings, ImageObject-like objects with a `url`, or arrays of either
+fn check_urls(path: &str, value: Option<&Value>, schema_type: Option<&str>, report: &mut ValidationReport) {
+    for_each(path, value, |path, value| {
+        let url = match value {
+            Value::String(url) => Some(url.as_str()),
+            Value::Object(object) => object.get("url").and_then(Value::as_str),
+            _ => None,
+        };
+        match url {
+            Some(url) if url.starts_with("https://") || url.starts_with("http://") => {}
+            Some(url) => report.warning(
+                path,
+                schema_type,
+                format!("\"{}\" is not an absolute URL", url),
+            ),
+            None if value.get("@id").is_some() => {}
+            None => report.error(path, schema_type, format!("Expected a URL, found {}", kind(value))),
+        }
+    });
+}
+
+/// Visit a value that may be given once or as an array
+fn for_each(path: &str, value: Option<&Value>, mut visit: impl FnMut(&str, &Value)) {
+    match value {
+        Some(Value::Array(values)) => {
+            for (i, value) in values.iter().enumerate() {
+                visit(&index(path, i), value);
+            }
+        }
+        Some(value) => visit(path, value),
+        None => {}
+    }
+}
+
+fn is_missing(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => true,
+        Some(Value::String(text)) => text.trim().is_empty(),
+        Some(Value::Array(values)) => values.is_empty(),
+        Some(_) => false,
+    }
+}
+
+fn is_schema_context(context: &Value) -> bool {
+    match context {
+        Value::String(context) => {
+            let context = context.trim_end_matches('/');
+            context == SCHEMA_CONTEXT || context == "http://schema.org"
+        }
+        Value::Array(contexts) => contexts.iter().any(is_schema_context),
+        // {"@vocab": "https://schema.org/"} and similar
+        Value::Object(context) => context.values().any(is_schema_context),
+        _ => false,
+    }
+}
+
+/// ISO 8601 duration such as "P3D", "PT1H30M" or "PT45.5S"
+pub fn is_iso_duration(duration: &str) -> bool {
+    let Some(rest) = duration.strip_prefix('P') else {
+        return false;
+    };
+    let (date, time) = match rest.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (rest, None),
+    };
+
+    fn components(part: &str, units: &str, fraction_last: bool) -> Option<usize> {
+        let mut remaining = units;
+        let mut count = 0;
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() || (fraction_last && c == '.') {
+                number.push(c);
+                continue;
+            }
+            let position = remaining.find(c)?;
+            if number.is_empty() || (number.contains('.') && c != 'S') {
+                return None;
+            }
+            remaining = &remaining[position + 1..];
+            number.clear();
+            count += 1;
+        }
+        number.is_empty().then_some(count)
+    }
+
+    let Some(date_parts) = components(date, "YMWD", false) else {
+        return false;
+    };
+    match time {
+        Some(time) => components(time, "HMS", true).is_some_and(|parts| parts > 0),
+        None => date_parts > 0,
+    }
+}
+
+fn property(path: &str, name: &str) -> String {
+    let plain = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        format!("{}.{}", path, name)
+    } else {
+        format!("{}['{}']", path, name)
+    }
+}
+
+fn index(path: &str, i: usize) -> String {
+    format!("{}[{}]", path, i)
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// schema.org ItemAvailability values
+const AVAILABILITY: [&str; 12] = [
+    "BackOrder",
+    "Discontinued",
+    "InStock",
+    "InStoreOnly",
+    "LimitedAvailability",
+    "MadeToOrder",
+    "OnlineOnly",
+    "OutOfStock",
+    "PreOrder",
+    "PreSale",
+    "Reserved",
+    "SoldOut",
+];
+
+/// Active ISO 4217 currency codes
+const ISO_4217: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUP", "CVE",
+    "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL",
+    "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR",
+    "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD",
+    "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK",
+    "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN", "NIO",
+    "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON",
+    "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD",
+    "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD",
+    "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES", "VND", "VUV",
+    "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XDR", "XOF", "XPD", "XPF",
+    "XPT", "XSU", "XUA", "YER", "ZAR", "ZMW", "ZWG",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aeo::schema::{
+        ArticleSchema, HowToSchema, OfferSchema, ProductSchema, SchemaGenerator, SchemaType,
+    };
+    use serde_json::json;
+
+    fn paths(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_article_needs_image() {
+        let article = SchemaType::Article(ArticleSchema::new(
+            "AEO".to_string(),
+            "Desc".to_string(),
+            "Jane".to_string(),
+        ));
+        let report = SchemaValidator::validate(&SchemaGenerator::to_value(&article).unwrap());
+
+        assert!(!report.valid);
+        assert_eq!(paths(&report.errors), ["$.image"]);
+        assert_eq!(report.items[0].schema_type, "Article");
+    }
+
+    #[test]
+    fn test_product_offer_checks() {
+        let product = SchemaType::Product(ProductSchema::new(
+            "Widget".to_string(),
+            "A widget".to_string(),
+            OfferSchema::new("$9.99".to_string(), "EURO".to_string())
+                .availability("InStock".to_string()),
+        ));
+        let graph = SchemaGenerator::graph(&[product]).unwrap();
+        let report = SchemaValidator::validate(&graph);
+
+        assert_eq!(
+            paths(&report.errors),
+            [
+                "$['@graph'][0].offers.price",
+                "$['@graph'][0].offers.priceCurrency",
+                "$['@graph'][0].offers.availability",
+            ]
+        );
+        assert!(report.errors.iter().all(|d| d.schema_type.as_deref() == Some("Offer")));
+    }
+
+    #[test]
+    fn test_howto_total_time() {
+        let howto = HowToSchema::new("Brew".to_string(), "Coffee".to_string())
+            .step("Grind".to_string(), "Grind the beans".to_string())
+            .total_time("20 minutes".to_string());
+        let report = SchemaValidator::validate(&SchemaGenerator::to_value(&SchemaType::HowTo(howto)).unwrap());
+
+        assert_eq!(paths(&report.errors), ["$.totalTime"]);
+        assert!(paths(&report.warnings).contains(&"$.image"));
+    }
+
+    #[test]
+    fn test_iso_duration() {
+        for valid in ["PT1H30M", "P3D", "P1Y2M", "PT45.5S", "P1DT2H"] {
+            assert!(is_iso_duration(valid), "{}", valid);
+        }
+        for invalid in ["P", "PT", "1H30M", "PT1.5H", "PT30M1H", "P1H", "PTM"] {
+            assert!(!is_iso_duration(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_document_shapes() {
+        let report = SchemaValidator::validate(&json!([
+            {"@context": "https://schema.org", "@type": "Person", "name": "Jane", "url": "https://jane.dev", "sameAs": ["https://x.com/jane"]},
+            null,
+            {"@type": ["Organization"], "name": "Acme", "url": "/", "logo": "https://acme.com/logo.png", "sameAs": []},
+            "text",
+        ]));
+
+        assert_eq!(paths(&report.errors), ["$[2]", "$[3]"]);
+        assert!(report.errors[0].message.contains("@context"));
+        assert!(paths(&report.warnings).contains(&"$[2].url"));
+        assert_eq!(report.items.len(), 2);
+
+        let report = SchemaValidator::validate(&json!({"@context": "https://schema.org"}));
+        assert_eq!(paths(&report.errors), ["$"]);
+        assert_eq!(report.errors[0].message, "Missing @type");
+    }
+
+    #[test]
+    fn test_new_type_checks() {
+        let report = SchemaValidator::validate(&json!({
+            "@context": "https://schema.org",
+            "@graph": [
+                {"@type": "Recipe", "name": "Pancakes", "image": "https://example.com/p.jpg", "cookTime": "15 minutes"},
+                {"@type": "Review", "itemReviewed": {"@type": "Product", "name": "Widget"},
+                 "reviewRating": {"@type": "Rating", "ratingValue": 7}, "author": {"@type": "Person", "name": "Jane"}},
+                {"@type": "QAPage", "mainEntity": {"@type": "Question", "name": "Why?", "answerCount": 0}},
+                {"@type": "LocalBusiness", "name": "Cafe", "address": {"@type": "PostalAddress"},
+                 "geo": {"@type": "GeoCoordinates", "latitude": 40.7, "longitude": -200}},
+            ]
+        }));
+
+        assert_eq!(
+            paths(&report.errors),
+            [
+                "$['@graph'][0].cookTime",
+                "$['@graph'][1].reviewRating.ratingValue",
+                "$['@graph'][2].mainEntity",
+                "$['@graph'][3].geo.longitude",
+            ]
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::str::FromStr;
 use uuid::Uuid;
 use crate::aeo::{SchemaType, AEOScore};
+use crate::user::UserRole;
 
 /// Content entity
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,9 +22,49 @@ pub struct Content {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub published_at: Option<DateTime<Utc>>,
+    /// The generation job the content was drafted from
+    #[serde(default)]
+    pub generation_job_id: Option<Uuid>,
+    #[serde(default)]
+    pub scheduled_publish_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub scheduled_unpublish_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Content {
+    /// A new draft, not yet saved
+    pub fn new(
+        title: impl Into<String>,
+        slug: impl Into<String>,
+        body: impl Into<String>,
+        content_type: ContentType,
+        author_id: Uuid,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            title: title.into(),
+            slug: slug.into(),
+            body: body.into(),
+            excerpt: None,
+            content_type,
+            status: ContentStatus::Draft,
+            author_id,
+            schema: Vec::new(),
+            aeo_score: None,
+            metadata: ContentMetadata::default(),
+            created_at: now,
+            updated_at: now,
+            published_at: None,
+            generation_job_id: None,
+            scheduled_publish_at: None,
+            scheduled_unpublish_at: None,
+        }
+    }
+}
+
+/// Serialized as its id, e.g. `"local_business"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ContentType {
     Article,
     Tutorial,
@@ -30,9 +72,121 @@ pub enum ContentType {
     FAQ,
     Product,
     Service,
+    Recipe,
+    Event,
+    LocalBusiness,
+    Review,
+    Video,
+    QA,
+    Software,
+    Course,
+    Dataset,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ContentType {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Article,
+            Self::Tutorial,
+            Self::Guide,
+            Self::FAQ,
+            Self::Product,
+            Self::Service,
+            Self::Recipe,
+            Self::Event,
+            Self::LocalBusiness,
+            Self::Review,
+            Self::Video,
+            Self::QA,
+            Self::Software,
+            Self::Course,
+            Self::Dataset,
+        ]
+    }
+
+    /// Stable id, as stored in `contents.content_type`
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Article => "article",
+            Self::Tutorial => "tutorial",
+            Self::Guide => "guide",
+            Self::FAQ => "faq",
+            Self::Product => "product",
+            Self::Service => "service",
+            Self::Recipe => "recipe",
+            Self::Event => "event",
+            Self::LocalBusiness => "local_business",
+            Self::Review => "review",
+            Self::Video => "video",
+            Self::QA => "qa",
+            Self::Software => "software",
+            Self::Course => "course",
+            Self::Dataset => "dataset",
+        }
+    }
+
+    /// The schema.org type that describes content of this type
+    pub fn schema_type(&self) -> &'static str {
+        match self {
+            Self::Article | Self::Guide => "Article",
+            Self::Tutorial => "HowTo",
+            Self::FAQ => "FAQPage",
+            Self::Product => "Product",
+            Self::Service => "Service",
+            Self::Recipe => "Recipe",
+            Self::Event => "Event",
+            Self::LocalBusiness => "LocalBusiness",
+            Self::Review => "Review",
+            Self::Video => "VideoObject",
+            Self::QA => "QAPage",
+            Self::Software => "SoftwareApplication",
+            Self::Course => "Course",
+            Self::Dataset => "Dataset",
+        }
+    }
+
+    /// Whether the schema is built from structured metadata rather than
+    /// from the body. These types read their properties from
+    /// `metadata.<id>`.
+    pub fn uses_metadata(&self) -> bool {
+        !matches!(self, Self::Article | Self::Tutorial | Self::Guide | Self::FAQ)
+    }
+}
+
+impl FromStr for ContentType {
+    type Err = UnknownContentType;
+
+    /// Accepts the id, case-insensitively, with `-` or ` ` for `_`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = s.trim().to_lowercase().replace(['-', ' '], "_");
+        Self::all()
+            .into_iter()
+            .find(|content_type| content_type.id() == id)
+            .ok_or_else(|| UnknownContentType(s.to_string()))
+    }
+}
+
+impl Serialize for ContentType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for ContentType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A content type name that matches no known content type
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown content type '{0}'")]
+pub struct UnknownContentType(pub String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ContentStatus {
     Draft,
     Review,
@@ -40,7 +194,142 @@ pub enum ContentStatus {
     Archived,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ContentStatus {
+    /// Stable id, as stored in `content.status`
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Review => "review",
+            Self::Published => "published",
+            Self::Archived => "archived",
+        }
+    }
+}
+
+impl FromStr for ContentStatus {
+    type Err = UnknownContentStatus;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Self::Draft, Self::Review, Self::Published, Self::Archived]
+            .into_iter()
+            .find(|status| status.id().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| UnknownContentStatus(s.to_string()))
+    }
+}
+
+/// A status name that matches no known status
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown content status '{0}'")]
+pub struct UnknownContentStatus(pub String);
+
+/// Editorial workflow steps. Authors write drafts and submit them for
+/// review; editors approve, send back, publish, archive and restore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowAction {
+    /// Draft -> Review
+    Submit,
+    /// Review -> Published
+    Approve,
+    /// Review -> Draft, with a comment saying what to change
+    RequestChanges,
+    /// Draft or Review -> Published, skipping review
+    Publish,
+    /// Published -> Draft
+    Unpublish,
+    /// Anything but Archived -> Archived
+    Archive,
+    /// Archived -> Draft
+    Restore,
+    /// A review comment; the status stays as it is
+    Comment,
+}
+
+impl WorkflowAction {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Submit,
+            Self::Approve,
+            Self::RequestChanges,
+            Self::Publish,
+            Self::Unpublish,
+            Self::Archive,
+            Self::Restore,
+            Self::Comment,
+        ]
+    }
+
+    /// Stable id, as recorded in the workflow audit
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Submit => "submit",
+            Self::Approve => "approve",
+            Self::RequestChanges => "request_changes",
+            Self::Publish => "publish",
+            Self::Unpublish => "unpublish",
+            Self::Archive => "archive",
+            Self::Restore => "restore",
+            Self::Comment => "comment",
+        }
+    }
+
+    /// The status content moves to, or None when the action doesn't apply
+    /// to content in `status`
+    pub fn next_status(&self, status: ContentStatus) -> Option<ContentStatus> {
+        use ContentStatus::*;
+
+        match (self, status) {
+            (Self::Submit, Draft) => Some(Review),
+            (Self::Approve, Review) => Some(Published),
+            (Self::RequestChanges, Review) => Some(Draft),
+            (Self::Publish, Draft | Review) => Some(Published),
+            (Self::Unpublish, Published) => Some(Draft),
+            (Self::Archive, Draft | Review | Published) => Some(Archived),
+            (Self::Restore, Archived) => Some(Draft),
+            (Self::Comment, status) => Some(status),
+            _ => None,
+        }
+    }
+
+    /// Whether a user with `role` may take the action. Authors only act on
+    /// their own content, and only to submit it or comment on it.
+    pub fn permits(&self, role: UserRole, own_content: bool) -> bool {
+        match role {
+            UserRole::Admin | UserRole::Editor => true,
+            UserRole::Author => own_content && matches!(self, Self::Submit | Self::Comment),
+            UserRole::Viewer => false,
+        }
+    }
+
+    /// Sending content back or commenting without saying anything is no use
+    pub fn requires_comment(&self) -> bool {
+        matches!(self, Self::RequestChanges | Self::Comment)
+    }
+}
+
+impl FromStr for WorkflowAction {
+    type Err = UnknownWorkflowAction;
+
+    /// Accepts the id, case-insensitively, with `-` for `_`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = s.trim().to_lowercase().replace('-', "_");
+        Self::all()
+            .into_iter()
+            .find(|action| action.id() == id)
+            .ok_or_else(|| UnknownWorkflowAction(s.to_string()))
+    }
+}
+
+/// An action name that matches no workflow action
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown workflow action '{0}'")]
+pub struct UnknownWorkflowAction(pub String);
+
+/// Average adult reading speed, for `ContentMetadata::reading_time_minutes`
+pub const WORDS_PER_MINUTE: usize = 200;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ContentMetadata {
     pub keywords: Vec<String>,
     pub entities: Vec<String>,
@@ -48,6 +337,19 @@ pub struct ContentMetadata {
     pub reading_time_minutes: u32,
     pub language: String,
     pub tags: Vec<String>,
+    /// Anything else, e.g. schema.org properties under the content type id
+    /// (`"recipe": {...}`) or `"image"`
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ContentMetadata {
+    /// Set the word count and the reading time that follows from it,
+    /// rounded up to whole minutes
+    pub fn set_word_count(&mut self, word_count: usize) {
+        self.word_count = word_count;
+        self.reading_time_minutes = word_count.div_ceil(WORDS_PER_MINUTE) as u32;
+    }
 }
 
 /// Content creation request
@@ -67,3 +369,114 @@ pub struct UpdateContentRequest {
     pub status: Option<ContentStatus>,
     pub keywords: Option<Vec<String>>,
 }
+
+/// Content types and statuses are stored as their ids in text columns
+#[cfg(feature = "sqlx")]
+mod sql {
+    use super::{ContentStatus, ContentType};
+    use sqlx::encode::IsNull;
+    use sqlx::error::BoxDynError;
+    use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+    use sqlx::{Decode, Encode, Postgres, Type};
+
+    macro_rules! text_column {
+        ($ty:ty) => {
+            impl Type<Postgres> for $ty {
+                fn type_info() -> PgTypeInfo {
+                    <&str as Type<Postgres>>::type_info()
+                }
+
+                fn compatible(ty: &PgTypeInfo) -> bool {
+                    <&str as Type<Postgres>>::compatible(ty)
+                }
+            }
+
+            impl<'q> Encode<'q, Postgres> for $ty {
+                fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+                    <&str as Encode<Postgres>>::encode(self.id(), buf)
+                }
+            }
+
+            impl<'r> Decode<'r, Postgres> for $ty {
+                fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+                    Ok(<&str as Decode<Postgres>>::decode(value)?.parse()?)
+                }
+            }
+        };
+    }
+
+    text_column!(ContentType);
+    text_column!(ContentStatus);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type_ids_round_trip() {
+        for content_type in ContentType::all() {
+            assert_eq!(content_type.id().parse::<ContentType>(), Ok(content_type));
+        }
+        assert_eq!("Local Business".parse::<ContentType>(), Ok(ContentType::LocalBusiness));
+        assert!("podcast".parse::<ContentType>().is_err());
+    }
+
+    #[test]
+    fn test_workflow_transitions() {
+        use ContentStatus::*;
+
+        assert_eq!(WorkflowAction::Submit.next_status(Draft), Some(Review));
+        assert_eq!(WorkflowAction::Approve.next_status(Review), Some(Published));
+        assert_eq!(WorkflowAction::RequestChanges.next_status(Review), Some(Draft));
+        assert_eq!(WorkflowAction::Approve.next_status(Draft), None);
+        assert_eq!(WorkflowAction::Archive.next_status(Archived), None);
+        assert_eq!(WorkflowAction::Restore.next_status(Archived), Some(Draft));
+        assert_eq!(WorkflowAction::Comment.next_status(Review), Some(Review));
+        assert_eq!("request-changes".parse(), Ok(WorkflowAction::RequestChanges));
+        assert_eq!("Published".parse(), Ok(Published));
+    }
+
+    #[test]
+    fn test_workflow_roles() {
+        assert!(WorkflowAction::Submit.permits(UserRole::Author, true));
+        assert!(!WorkflowAction::Submit.permits(UserRole::Author, false));
+        assert!(!WorkflowAction::Approve.permits(UserRole::Author, true));
+        assert!(WorkflowAction::Approve.permits(UserRole::Editor, false));
+        assert!(!WorkflowAction::Comment.permits(UserRole::Viewer, true));
+        assert_eq!("user".parse(), Ok(UserRole::Author));
+    }
+
+    #[test]
+    fn test_content_serializes_ids() {
+        let json = serde_json::to_value([ContentType::LocalBusiness, ContentType::FAQ]).unwrap();
+        assert_eq!(json, serde_json::json!(["local_business", "faq"]));
+        assert_eq!(serde_json::to_value(ContentStatus::Review).unwrap(), "review");
+        assert!(serde_json::from_value::<ContentType>("podcast".into()).is_err());
+    }
+
+    #[test]
+    fn test_content_metadata_keeps_extra_properties() {
+        let mut metadata: ContentMetadata = serde_json::from_value(serde_json::json!({
+            "keywords": ["sourdough"],
+            "recipe": {"recipeYield": "1 loaf"},
+        }))
+        .unwrap();
+        assert_eq!(metadata.keywords, ["sourdough"]);
+        assert_eq!(metadata.extra["recipe"]["recipeYield"], "1 loaf");
+
+        metadata.set_word_count(401);
+        assert_eq!(metadata.reading_time_minutes, 3);
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(json["word_count"], 401);
+        assert_eq!(json["recipe"]["recipeYield"], "1 loaf");
+    }
+
+    #[test]
+    fn test_content_type_schema_mapping() {
+        assert_eq!(ContentType::Tutorial.schema_type(), "HowTo");
+        assert_eq!(ContentType::Video.schema_type(), "VideoObject");
+        assert!(ContentType::Recipe.uses_metadata());
+        assert!(!ContentType::FAQ.uses_metadata());
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::str::FromStr;
 use uuid::Uuid;
 
 /// User entity
@@ -13,7 +14,7 @@ pub struct User {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UserRole {
     Admin,
     Editor,
@@ -21,6 +22,39 @@ pub enum UserRole {
     Viewer,
 }
 
+impl UserRole {
+    /// Stable id, as stored in `users.role`
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Editor => "editor",
+            Self::Author => "author",
+            Self::Viewer => "viewer",
+        }
+    }
+}
+
+impl FromStr for UserRole {
+    type Err = UnknownUserRole;
+
+    /// Accepts the id, case-insensitively. "user", the role registration
+    /// assigns, is an author.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "admin" => Ok(Self::Admin),
+            "editor" => Ok(Self::Editor),
+            "author" | "user" => Ok(Self::Author),
+            "viewer" => Ok(Self::Viewer),
+            _ => Err(UnknownUserRole(s.to_string())),
+        }
+    }
+}
+
+/// A role name that matches no known role
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown user role '{0}'")]
+pub struct UnknownUserRole(pub String);
+
 /// Authentication token claims
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenClaims {
@@ -0,0 +1,12 @@
+-- Per-tenant platform strategies
+
+-- NULL tenant_id is the global default used when a tenant has no override
+ALTER TABLE platform_strategies ADD COLUMN tenant_id UUID;
+ALTER TABLE platform_strategies DROP CONSTRAINT platform_strategies_platform_key;
+
+CREATE UNIQUE INDEX idx_platform_strategies_global
+    ON platform_strategies(platform) WHERE tenant_id IS NULL;
+CREATE UNIQUE INDEX idx_platform_strategies_tenant
+    ON platform_strategies(tenant_id, platform) WHERE tenant_id IS NOT NULL;
+
+COMMENT ON COLUMN platform_strategies.tenant_id IS 'Owning tenant, NULL for the global default';
@@ -0,0 +1,30 @@
+-- Store platforms under their canonical ids (see AIPlatform::id)
+
+UPDATE citations SET platform = CASE lower(trim(platform))
+    WHEN 'chatgpt' THEN 'chatgpt'
+    WHEN 'gpt' THEN 'chatgpt'
+    WHEN 'openai' THEN 'chatgpt'
+    WHEN 'claude' THEN 'claude'
+    WHEN 'anthropic' THEN 'claude'
+    WHEN 'perplexity' THEN 'perplexity'
+    WHEN 'gemini' THEN 'gemini'
+    WHEN 'google' THEN 'gemini'
+    WHEN 'bard' THEN 'gemini'
+    WHEN 'bing' THEN 'bing'
+    WHEN 'bing ai' THEN 'bing'
+    WHEN 'bing chat' THEN 'bing'
+    WHEN 'copilot' THEN 'bing'
+    ELSE platform
+END;
+
+UPDATE content_optimizations SET platform = CASE lower(trim(platform))
+    WHEN 'gpt' THEN 'chatgpt'
+    WHEN 'openai' THEN 'chatgpt'
+    WHEN 'anthropic' THEN 'claude'
+    WHEN 'google' THEN 'gemini'
+    WHEN 'bard' THEN 'gemini'
+    WHEN 'bing ai' THEN 'bing'
+    WHEN 'bing chat' THEN 'bing'
+    WHEN 'copilot' THEN 'bing'
+    ELSE lower(trim(platform))
+END;
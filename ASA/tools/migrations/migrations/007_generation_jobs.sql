@@ -0,0 +1,33 @@
+-- AI generation jobs: what produced each generated text, and what it cost
+
+CREATE TABLE generation_jobs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    kind VARCHAR(50) NOT NULL,
+    topic TEXT NOT NULL,
+    content_type VARCHAR(50) NOT NULL,
+    platform VARCHAR(50) NOT NULL,
+    provider VARCHAR(100) NOT NULL,
+    model VARCHAR(255) NOT NULL,
+    mock BOOLEAN NOT NULL DEFAULT FALSE,
+    system_prompt TEXT,
+    prompt TEXT NOT NULL,
+    prompt_tokens INTEGER,
+    completion_tokens INTEGER,
+    latency_ms INTEGER NOT NULL,
+    estimated_cost DECIMAL(12,6),
+    title TEXT,
+    output TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW()
+);
+
+-- Drafts created from a job link back to it
+ALTER TABLE content ADD COLUMN generation_job_id UUID REFERENCES generation_jobs(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_generation_jobs_created ON generation_jobs(created_at DESC);
+CREATE INDEX idx_generation_jobs_provider ON generation_jobs(provider, model);
+CREATE UNIQUE INDEX idx_content_generation_job ON content(generation_job_id) WHERE generation_job_id IS NOT NULL;
+
+COMMENT ON TABLE generation_jobs IS 'AI generation provenance, token usage and cost';
+COMMENT ON COLUMN generation_jobs.kind IS 'article, outline or stream';
+COMMENT ON COLUMN generation_jobs.estimated_cost IS 'USD from configured model prices, NULL when unknown';
+COMMENT ON COLUMN content.generation_job_id IS 'Generation job this content was drafted from';
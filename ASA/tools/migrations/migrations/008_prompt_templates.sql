@@ -0,0 +1,32 @@
+-- Versioned prompt templates for AI generation
+
+CREATE TABLE prompt_templates (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL,
+    content_type VARCHAR(50),
+    platform VARCHAR(50),
+    version INTEGER NOT NULL,
+    description TEXT,
+    system_prompt TEXT NOT NULL,
+    template TEXT NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP DEFAULT NOW()
+);
+
+-- Versions are numbered per name/content type/platform, with at most one active
+CREATE UNIQUE INDEX idx_prompt_templates_version
+    ON prompt_templates(name, COALESCE(content_type, ''), COALESCE(platform, ''), version);
+CREATE UNIQUE INDEX idx_prompt_templates_active
+    ON prompt_templates(name, COALESCE(content_type, ''), COALESCE(platform, '')) WHERE active;
+
+ALTER TABLE generation_jobs
+    ADD COLUMN prompt_template_id UUID REFERENCES prompt_templates(id) ON DELETE SET NULL,
+    ADD COLUMN prompt_template VARCHAR(100),
+    ADD COLUMN prompt_template_version INTEGER;
+
+CREATE INDEX idx_generation_jobs_template ON generation_jobs(prompt_template, prompt_template_version);
+
+COMMENT ON TABLE prompt_templates IS 'Named, versioned generation prompts per content type and platform';
+COMMENT ON COLUMN prompt_templates.content_type IS 'NULL applies to every content type';
+COMMENT ON COLUMN prompt_templates.platform IS 'NULL applies to every platform';
+COMMENT ON COLUMN generation_jobs.prompt_template_version IS '0 for the bundled default template';
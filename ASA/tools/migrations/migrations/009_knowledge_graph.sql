@@ -0,0 +1,28 @@
+-- Knowledge graph populated from content: entities are shared across content,
+-- linked to each item that mentions them
+
+-- Entities are identified by name (case-insensitive) and type
+CREATE UNIQUE INDEX idx_kg_entities_identity ON kg_entities(LOWER(name), entity_type);
+
+-- Relationships found in a content item are replaced when it is re-extracted
+ALTER TABLE kg_relationships
+    ADD COLUMN content_id UUID REFERENCES content(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_kg_relationships_content ON kg_relationships(content_id);
+
+CREATE TABLE kg_content_entities (
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    entity_id UUID NOT NULL REFERENCES kg_entities(id) ON DELETE CASCADE,
+    mentions INTEGER NOT NULL,
+    salience DECIMAL(3,2) NOT NULL,
+    source VARCHAR(20) NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW(),
+    PRIMARY KEY (content_id, entity_id)
+);
+
+CREATE INDEX idx_kg_content_entities_entity ON kg_content_entities(entity_id);
+
+COMMENT ON TABLE kg_content_entities IS 'Entities mentioned by each content item';
+COMMENT ON COLUMN kg_content_entities.salience IS '0-1, relative to the most prominent entity in the content';
+COMMENT ON COLUMN kg_content_entities.source IS 'dictionary, pattern or llm';
+COMMENT ON COLUMN kg_relationships.content_id IS 'Content the relationship was extracted from, NULL when added by hand';
@@ -0,0 +1,29 @@
+-- Every saved state of a content item, so edits can be compared and undone
+
+CREATE TABLE content_revisions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    event VARCHAR(20) NOT NULL,
+    title VARCHAR(500) NOT NULL,
+    slug VARCHAR(500) NOT NULL,
+    body TEXT NOT NULL,
+    content_type VARCHAR(50) NOT NULL,
+    status VARCHAR(50) NOT NULL,
+    metadata JSONB,
+    aeo_score_id UUID REFERENCES aeo_scores(id) ON DELETE SET NULL,
+    restored_from INTEGER,
+    created_at TIMESTAMP DEFAULT NOW(),
+    UNIQUE (content_id, revision)
+);
+
+-- Existing content starts its history at revision 1
+INSERT INTO content_revisions (content_id, revision, event, title, slug, body, content_type,
+                               status, metadata, created_at)
+SELECT id, 1, 'created', title, slug, body, content_type, status, metadata, updated_at
+FROM content;
+
+COMMENT ON TABLE content_revisions IS 'Snapshots of content written on every create, update, publish and restore';
+COMMENT ON COLUMN content_revisions.event IS 'created, updated, published, unpublished or restored';
+COMMENT ON COLUMN content_revisions.aeo_score_id IS 'AEO score calculated for this revision';
+COMMENT ON COLUMN content_revisions.restored_from IS 'Revision copied by a restore';
@@ -0,0 +1,27 @@
+-- Editorial workflow: statuses follow asa_models::ContentStatus and every
+-- transition is audited
+
+UPDATE content SET status = 'draft'
+WHERE status NOT IN ('draft', 'review', 'published', 'archived');
+
+ALTER TABLE content
+    ADD CONSTRAINT content_status_check
+    CHECK (status IN ('draft', 'review', 'published', 'archived'));
+
+CREATE TABLE content_workflow_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    content_id UUID NOT NULL REFERENCES content(id) ON DELETE CASCADE,
+    action VARCHAR(30) NOT NULL,
+    from_status VARCHAR(50) NOT NULL,
+    to_status VARCHAR(50) NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    comment TEXT,
+    created_at TIMESTAMP DEFAULT NOW()
+);
+
+CREATE INDEX idx_content_workflow_events_content
+    ON content_workflow_events(content_id, created_at DESC);
+
+COMMENT ON TABLE content_workflow_events IS 'Who moved content between workflow statuses, and review comments';
+COMMENT ON COLUMN content_workflow_events.action IS 'submit, approve, request_changes, publish, unpublish, archive, restore or comment';
+COMMENT ON COLUMN content_workflow_events.user_id IS 'NULL for system actions';
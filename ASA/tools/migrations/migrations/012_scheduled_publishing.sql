@@ -0,0 +1,17 @@
+-- Embargoed publishing and expiring pages, carried out by the content
+-- service's scheduler
+
+ALTER TABLE content
+    ADD COLUMN scheduled_publish_at TIMESTAMP,
+    ADD COLUMN scheduled_unpublish_at TIMESTAMP,
+    ADD COLUMN scheduled_by UUID REFERENCES users(id) ON DELETE SET NULL;
+
+-- The scheduler only looks at rows with something due
+CREATE INDEX idx_content_scheduled_publish ON content(scheduled_publish_at)
+    WHERE scheduled_publish_at IS NOT NULL;
+CREATE INDEX idx_content_scheduled_unpublish ON content(scheduled_unpublish_at)
+    WHERE scheduled_unpublish_at IS NOT NULL;
+
+COMMENT ON COLUMN content.scheduled_publish_at IS 'Publish at this time; cleared when it fires';
+COMMENT ON COLUMN content.scheduled_unpublish_at IS 'Unpublish at this time; cleared when it fires';
+COMMENT ON COLUMN content.scheduled_by IS 'User who set the schedule, recorded as the actor when it fires';
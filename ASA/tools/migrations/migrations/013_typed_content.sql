@@ -0,0 +1,42 @@
+-- Content types follow asa_models::ContentType and metadata decodes as
+-- asa_models::ContentMetadata
+
+UPDATE content SET content_type = lower(regexp_replace(trim(content_type), '[- ]', '_', 'g'));
+UPDATE content SET content_type = 'article'
+WHERE content_type NOT IN ('article', 'tutorial', 'guide', 'faq', 'product', 'service', 'recipe',
+                           'event', 'local_business', 'review', 'video', 'qa', 'software',
+                           'course', 'dataset');
+
+ALTER TABLE content
+    ADD CONSTRAINT content_type_check
+    CHECK (content_type IN ('article', 'tutorial', 'guide', 'faq', 'product', 'service', 'recipe',
+                            'event', 'local_business', 'review', 'video', 'qa', 'software',
+                            'course', 'dataset'));
+
+-- Typed fields with the wrong JSON type would fail to decode
+UPDATE content SET metadata = '{}' WHERE metadata IS NULL OR jsonb_typeof(metadata) <> 'object';
+UPDATE content SET metadata = metadata - 'keywords' WHERE jsonb_typeof(metadata->'keywords') <> 'array';
+UPDATE content SET metadata = metadata - 'entities' WHERE jsonb_typeof(metadata->'entities') <> 'array';
+UPDATE content SET metadata = metadata - 'tags' WHERE jsonb_typeof(metadata->'tags') <> 'array';
+UPDATE content SET metadata = metadata - 'language' WHERE jsonb_typeof(metadata->'language') <> 'string';
+
+-- Rough counts until content is next saved and measured properly
+UPDATE content
+SET metadata = metadata || jsonb_build_object(
+    'word_count', words,
+    'reading_time_minutes', CEIL(words / 200.0)::INT
+)
+FROM (
+    SELECT id AS content_id,
+           CASE WHEN trim(body) = '' THEN 0
+                ELSE array_length(regexp_split_to_array(trim(body), '\s+'), 1) END AS words
+    FROM content
+) counts
+WHERE content.id = counts.content_id;
+
+ALTER TABLE content
+    ALTER COLUMN metadata SET DEFAULT '{}',
+    ALTER COLUMN metadata SET NOT NULL,
+    ADD COLUMN excerpt TEXT;
+
+COMMENT ON COLUMN content.excerpt IS 'Opening of the body as plain text; NULL until next saved';
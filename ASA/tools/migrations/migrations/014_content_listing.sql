@@ -0,0 +1,24 @@
+-- Content listing: keyset pages in every sort order, the current AEO score
+-- on the content row, and tag and keyword filters
+
+ALTER TABLE content ADD COLUMN aeo_score DECIMAL(5,2);
+
+UPDATE content c
+SET aeo_score = (
+    SELECT s.overall_score FROM aeo_scores s
+    WHERE s.content_id = c.id
+    ORDER BY s.calculated_at DESC
+    LIMIT 1
+);
+
+-- Sort key then id, matching the listing's ORDER BY
+CREATE INDEX idx_content_created_id ON content(created_at, id);
+CREATE INDEX idx_content_updated_id ON content(updated_at, id);
+CREATE INDEX idx_content_published_id ON content(published_at, id);
+CREATE INDEX idx_content_title_id ON content(title, id);
+CREATE INDEX idx_content_aeo_score_id ON content(aeo_score, id);
+
+-- metadata @> '{"tags": [...]}' and '{"keywords": [...]}'
+CREATE INDEX idx_content_metadata ON content USING GIN(metadata jsonb_path_ops);
+
+COMMENT ON COLUMN content.aeo_score IS 'Overall score of the latest aeo_scores row, NULL until scored';
@@ -0,0 +1,6 @@
+-- Who wrote each revision
+
+ALTER TABLE content_revisions
+    ADD COLUMN created_by UUID REFERENCES users(id) ON DELETE SET NULL;
+
+COMMENT ON COLUMN content_revisions.created_by IS 'User whose change produced the revision, NULL for system changes and older revisions';
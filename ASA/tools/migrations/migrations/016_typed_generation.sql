@@ -0,0 +1,42 @@
+-- Generation jobs, revisions and prompt templates name content types by
+-- asa_models::ContentType id, like content since 013
+
+UPDATE generation_jobs SET content_type = lower(regexp_replace(trim(content_type), '[- ]', '_', 'g'));
+UPDATE generation_jobs SET content_type = 'article'
+WHERE content_type NOT IN ('article', 'tutorial', 'guide', 'faq', 'product', 'service', 'recipe',
+                           'event', 'local_business', 'review', 'video', 'qa', 'software',
+                           'course', 'dataset');
+
+ALTER TABLE generation_jobs
+    ADD CONSTRAINT generation_jobs_content_type_check
+    CHECK (content_type IN ('article', 'tutorial', 'guide', 'faq', 'product', 'service', 'recipe',
+                            'event', 'local_business', 'review', 'video', 'qa', 'software',
+                            'course', 'dataset'));
+
+-- Revisions taken before 013 kept the content type as it was then
+UPDATE content_revisions SET content_type = lower(regexp_replace(trim(content_type), '[- ]', '_', 'g'));
+UPDATE content_revisions SET content_type = 'article'
+WHERE content_type NOT IN ('article', 'tutorial', 'guide', 'faq', 'product', 'service', 'recipe',
+                           'event', 'local_business', 'review', 'video', 'qa', 'software',
+                           'course', 'dataset');
+
+ALTER TABLE content_revisions
+    ADD CONSTRAINT content_revisions_content_type_check
+    CHECK (content_type IN ('article', 'tutorial', 'guide', 'faq', 'product', 'service', 'recipe',
+                            'event', 'local_business', 'review', 'video', 'qa', 'software',
+                            'course', 'dataset'));
+
+-- Templates are matched against the canonical id. A spelling that another
+-- of the template's spellings also normalizes to is left as it is, and
+-- never matches.
+UPDATE prompt_templates t
+SET content_type = lower(regexp_replace(trim(t.content_type), '[- ]', '_', 'g'))
+WHERE t.content_type <> lower(regexp_replace(trim(t.content_type), '[- ]', '_', 'g'))
+  AND NOT EXISTS (
+      SELECT 1 FROM prompt_templates other
+      WHERE other.name = t.name
+        AND other.platform IS NOT DISTINCT FROM t.platform
+        AND other.content_type <> t.content_type
+        AND lower(regexp_replace(trim(other.content_type), '[- ]', '_', 'g'))
+            = lower(regexp_replace(trim(t.content_type), '[- ]', '_', 'g'))
+  );
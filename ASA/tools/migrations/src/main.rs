@@ -22,6 +22,18 @@ async fn main() -> anyhow::Result<()> {
         "002_aeo_tables.sql",
         "003_analytics.sql",
         "004_indexes.sql",
+        "005_platform_strategy_tenants.sql",
+        "006_normalize_platforms.sql",
+        "007_generation_jobs.sql",
+        "008_prompt_templates.sql",
+        "009_knowledge_graph.sql",
+        "010_content_revisions.sql",
+        "011_content_workflow.sql",
+        "012_scheduled_publishing.sql",
+        "013_typed_content.sql",
+        "014_content_listing.sql",
+        "015_revision_authors.sql",
+        "016_typed_generation.sql",
     ];
 
     for migration in migrations {